@@ -6,6 +6,26 @@ mod m20241206_000002_create_transaction;
 mod m20241206_000003_create_holdings;
 mod m20251206_070442_add_balance_to_account;
 mod m20251206_071653_add_deleted_at_to_account;
+mod m20261018_000001_make_optional_columns_nullable;
+mod m20261018_000002_add_opening_balance_to_account;
+mod m20261018_000003_create_transaction_split;
+mod m20261018_000004_create_import_profile;
+mod m20261018_000005_add_external_id_to_transaction;
+mod m20261018_000006_add_fingerprint_to_transaction;
+mod m20261018_000007_create_category;
+mod m20261018_000008_create_tag;
+mod m20261018_000009_create_transaction_rule;
+mod m20261018_000010_create_recurring_transaction;
+mod m20261018_000011_create_budget;
+mod m20261018_000012_create_savings_goal;
+mod m20261018_000013_create_balance_snapshot;
+mod m20261018_000014_create_fx_rate;
+mod m20261018_000015_add_implied_rate_to_transaction;
+mod m20261018_000016_create_custom_currency;
+mod m20261018_000017_add_original_amount_to_transaction;
+mod m20261018_000018_recompute_transaction_fingerprint;
+mod m20261018_000019_add_attempts_to_recurring_occurrence;
+mod m20261018_000020_make_holdings_optional_columns_nullable;
 
 pub struct Migrator;
 
@@ -19,6 +39,26 @@ impl MigratorTrait for Migrator {
             Box::new(m20241206_000003_create_holdings::Migration),
             Box::new(m20251206_070442_add_balance_to_account::Migration),
             Box::new(m20251206_071653_add_deleted_at_to_account::Migration),
+            Box::new(m20261018_000001_make_optional_columns_nullable::Migration),
+            Box::new(m20261018_000002_add_opening_balance_to_account::Migration),
+            Box::new(m20261018_000003_create_transaction_split::Migration),
+            Box::new(m20261018_000004_create_import_profile::Migration),
+            Box::new(m20261018_000005_add_external_id_to_transaction::Migration),
+            Box::new(m20261018_000006_add_fingerprint_to_transaction::Migration),
+            Box::new(m20261018_000007_create_category::Migration),
+            Box::new(m20261018_000008_create_tag::Migration),
+            Box::new(m20261018_000009_create_transaction_rule::Migration),
+            Box::new(m20261018_000010_create_recurring_transaction::Migration),
+            Box::new(m20261018_000011_create_budget::Migration),
+            Box::new(m20261018_000012_create_savings_goal::Migration),
+            Box::new(m20261018_000013_create_balance_snapshot::Migration),
+            Box::new(m20261018_000014_create_fx_rate::Migration),
+            Box::new(m20261018_000015_add_implied_rate_to_transaction::Migration),
+            Box::new(m20261018_000016_create_custom_currency::Migration),
+            Box::new(m20261018_000017_add_original_amount_to_transaction::Migration),
+            Box::new(m20261018_000018_recompute_transaction_fingerprint::Migration),
+            Box::new(m20261018_000019_add_attempts_to_recurring_occurrence::Migration),
+            Box::new(m20261018_000020_make_holdings_optional_columns_nullable::Migration),
         ]
     }
 }
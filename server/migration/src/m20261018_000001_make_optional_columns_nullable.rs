@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .modify_column(ColumnDef::new(Transaction::FromAccountId).uuid().null())
+                    .modify_column(ColumnDef::new(Transaction::ToAccountId).uuid().null())
+                    .modify_column(ColumnDef::new(Transaction::ToAmount).decimal_len(18, 4).null())
+                    .modify_column(ColumnDef::new(Transaction::ToCurrencyCode).string_len(3).null())
+                    .modify_column(ColumnDef::new(Transaction::Category).string_len(64).null())
+                    .modify_column(ColumnDef::new(Transaction::Note).text().null())
+                    .modify_column(ColumnDef::new(Transaction::RefTransactionId).uuid().null())
+                    .modify_column(ColumnDef::new(Transaction::Merchant).string_len(128).null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Fails if any income, expense or refund row lacks one of these columns.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .modify_column(ColumnDef::new(Transaction::FromAccountId).uuid().not_null())
+                    .modify_column(ColumnDef::new(Transaction::ToAccountId).uuid().not_null())
+                    .modify_column(ColumnDef::new(Transaction::ToAmount).decimal_len(18, 4).not_null())
+                    .modify_column(ColumnDef::new(Transaction::ToCurrencyCode).string_len(3).not_null())
+                    .modify_column(ColumnDef::new(Transaction::Category).string_len(64).not_null())
+                    .modify_column(ColumnDef::new(Transaction::Note).text().not_null())
+                    .modify_column(ColumnDef::new(Transaction::RefTransactionId).uuid().not_null())
+                    .modify_column(ColumnDef::new(Transaction::Merchant).string_len(128).not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Transaction {
+    Table,
+    FromAccountId,
+    ToAccountId,
+    ToAmount,
+    ToCurrencyCode,
+    Category,
+    Note,
+    RefTransactionId,
+    Merchant,
+}
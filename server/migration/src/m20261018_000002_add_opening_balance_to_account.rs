@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Account::Table)
+                    .add_column(
+                        ColumnDef::new(Account::OpeningBalance)
+                            .decimal_len(19, 4)
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE account SET opening_balance = balance")
+            .await?;
+
+        // Fold existing transactions into the stored balance, mirroring
+        // services::account::recompute_all_balances.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"UPDATE account a
+                   SET balance = a.opening_balance
+                       + COALESCE((SELECT SUM(COALESCE(t.to_amount, t.amount))
+                                   FROM "transaction" t
+                                   WHERE t.to_account_id = a.id), 0)
+                       - COALESCE((SELECT SUM(t.amount)
+                                   FROM "transaction" t
+                                   WHERE t.from_account_id = a.id), 0)"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE account SET balance = opening_balance")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Account::Table)
+                    .drop_column(Account::OpeningBalance)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Account {
+    Table,
+    OpeningBalance,
+}
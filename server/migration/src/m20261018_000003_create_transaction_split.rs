@@ -0,0 +1,86 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TransactionSplit::Table)
+                    .if_not_exists()
+                    .col(uuid(TransactionSplit::Id).primary_key())
+                    .col(uuid(TransactionSplit::TransactionId).not_null())
+                    .col(string_len(TransactionSplit::Category, 64).not_null())
+                    .col(decimal_len(TransactionSplit::Amount, 18, 4).not_null())
+                    .col(text_null(TransactionSplit::Note))
+                    .col(integer(TransactionSplit::Position).default(0).not_null())
+                    .col(timestamp_with_time_zone(TransactionSplit::CreatedAt).default(Expr::current_timestamp()).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_transaction_split_transaction")
+                            .from(TransactionSplit::Table, TransactionSplit::TransactionId)
+                            .to(Transaction::Table, Transaction::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE transaction_split ADD CONSTRAINT chk_split_amount_positive CHECK (amount > 0)"
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_transaction_split_transaction")
+                    .table(TransactionSplit::Table)
+                    .col(TransactionSplit::TransactionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_transaction_split_category")
+                    .table(TransactionSplit::Table)
+                    .col(TransactionSplit::Category)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TransactionSplit::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TransactionSplit {
+    Table,
+    Id,
+    TransactionId,
+    Category,
+    Amount,
+    Note,
+    Position,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Transaction {
+    Table,
+    Id,
+}
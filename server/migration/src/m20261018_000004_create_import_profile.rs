@@ -0,0 +1,103 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportProfile::Table)
+                    .if_not_exists()
+                    .col(uuid(ImportProfile::Id).primary_key())
+                    .col(uuid(ImportProfile::UserId).not_null())
+                    .col(uuid(ImportProfile::AccountId).not_null())
+                    .col(string_len(ImportProfile::Name, 100).not_null())
+                    .col(string_len(ImportProfile::Delimiter, 1).default(",").not_null())
+                    .col(boolean(ImportProfile::HasHeader).default(true).not_null())
+                    .col(string_len(ImportProfile::DateColumn, 64).not_null())
+                    .col(string_len(ImportProfile::DateFormat, 32).not_null())
+                    .col(string_len_null(ImportProfile::AmountColumn, 64))
+                    .col(string_len_null(ImportProfile::DebitColumn, 64))
+                    .col(string_len_null(ImportProfile::CreditColumn, 64))
+                    .col(string_len_null(ImportProfile::MerchantColumn, 64))
+                    .col(string_len_null(ImportProfile::NoteColumn, 64))
+                    .col(string_len(ImportProfile::DecimalSeparator, 1).default(".").not_null())
+                    .col(timestamp_with_time_zone(ImportProfile::CreatedAt).default(Expr::current_timestamp()).not_null())
+                    .col(timestamp_with_time_zone(ImportProfile::UpdatedAt).default(Expr::current_timestamp()).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_import_profile_user")
+                            .from(ImportProfile::Table, ImportProfile::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_import_profile_account")
+                            .from(ImportProfile::Table, ImportProfile::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_import_profile_user_name")
+                    .table(ImportProfile::Table)
+                    .col(ImportProfile::UserId)
+                    .col(ImportProfile::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImportProfile::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImportProfile {
+    Table,
+    Id,
+    UserId,
+    AccountId,
+    Name,
+    Delimiter,
+    HasHeader,
+    DateColumn,
+    DateFormat,
+    AmountColumn,
+    DebitColumn,
+    CreditColumn,
+    MerchantColumn,
+    NoteColumn,
+    DecimalSeparator,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Account {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
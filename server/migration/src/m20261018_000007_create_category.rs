@@ -0,0 +1,319 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const KIND_OF_TXN: &str = "CASE WHEN t.txn_type = 'income' THEN 'income' ELSE 'expense' END";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Category::Table)
+                    .if_not_exists()
+                    .col(uuid(Category::Id).primary_key())
+                    .col(uuid(Category::UserId).not_null())
+                    .col(uuid_null(Category::ParentId))
+                    .col(string_len(Category::Name, 64).not_null())
+                    .col(string_len(Category::Kind, 16).not_null())
+                    .col(string_len_null(Category::Icon, 32))
+                    .col(string_len_null(Category::Color, 16))
+                    .col(boolean(Category::Archived).default(false).not_null())
+                    .col(
+                        timestamp_with_time_zone(Category::CreatedAt)
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .col(
+                        timestamp_with_time_zone(Category::UpdatedAt)
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_category_user")
+                            .from(Category::Table, Category::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_category_parent")
+                            .from(Category::Table, Category::ParentId)
+                            .to(Category::Table, Category::Id)
+                            .on_delete(ForeignKeyAction::NoAction)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            "ALTER TABLE category ADD CONSTRAINT chk_category_kind CHECK (kind IN ('expense', 'income'))",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX uk_category_user_parent_name ON category \
+             (user_id, kind, COALESCE(parent_id, '00000000-0000-0000-0000-000000000000'::uuid), lower(name))",
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .add_column(ColumnDef::new(Transaction::CategoryId).uuid().null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_transaction_category")
+                            .from_tbl(Transaction::Table)
+                            .from_col(Transaction::CategoryId)
+                            .to_tbl(Category::Table)
+                            .to_col(Category::Id)
+                            .on_delete(ForeignKeyAction::NoAction)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TransactionSplit::Table)
+                    .add_column(ColumnDef::new(TransactionSplit::CategoryId).uuid().null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_transaction_split_category")
+                            .from_tbl(TransactionSplit::Table)
+                            .from_col(TransactionSplit::CategoryId)
+                            .to_tbl(Category::Table)
+                            .to_col(Category::Id)
+                            .on_delete(ForeignKeyAction::NoAction)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        db.execute_unprepared(&format!(
+            r#"INSERT INTO category (id, user_id, parent_id, name, kind)
+            SELECT gen_random_uuid(), user_id, NULL, min(name), kind
+            FROM (
+                SELECT t.user_id, replace(trim(t.category), ':', ' ') AS name, {kind} AS kind
+                FROM "transaction" t
+                WHERE trim(coalesce(t.category, '')) <> ''
+                UNION ALL
+                SELECT t.user_id, replace(trim(s.category), ':', ' '), {kind}
+                FROM transaction_split s
+                JOIN "transaction" t ON t.id = s.transaction_id
+                WHERE trim(s.category) <> ''
+            ) src
+            GROUP BY user_id, kind, lower(name)"#,
+            kind = KIND_OF_TXN
+        ))
+        .await?;
+
+        db.execute_unprepared(&format!(
+            r#"UPDATE "transaction" t SET category_id = c.id
+            FROM category c
+            WHERE c.user_id = t.user_id
+              AND c.parent_id IS NULL
+              AND c.kind = {kind}
+              AND lower(c.name) = lower(replace(trim(t.category), ':', ' '))"#,
+            kind = KIND_OF_TXN
+        ))
+        .await?;
+
+        db.execute_unprepared(&format!(
+            r#"UPDATE transaction_split s SET category_id = c.id
+            FROM "transaction" t, category c
+            WHERE t.id = s.transaction_id
+              AND c.user_id = t.user_id
+              AND c.parent_id IS NULL
+              AND c.kind = {kind}
+              AND lower(c.name) = lower(replace(trim(s.category), ':', ' '))"#,
+            kind = KIND_OF_TXN
+        ))
+        .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_transaction_split_category")
+                    .table(TransactionSplit::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TransactionSplit::Table)
+                    .modify_column(
+                        ColumnDef::new(TransactionSplit::CategoryId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .drop_column(TransactionSplit::Category)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .drop_column(Transaction::Category)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_transaction_category")
+                    .table(Transaction::Table)
+                    .col(Transaction::CategoryId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_transaction_split_category")
+                    .table(TransactionSplit::Table)
+                    .col(TransactionSplit::CategoryId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .add_column(ColumnDef::new(Transaction::Category).string_len(64).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TransactionSplit::Table)
+                    .add_column(
+                        ColumnDef::new(TransactionSplit::Category)
+                            .string_len(64)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        db.execute_unprepared(
+            r#"UPDATE "transaction" t SET category = c.name FROM category c WHERE c.id = t.category_id"#,
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "UPDATE transaction_split s SET category = c.name FROM category c WHERE c.id = s.category_id",
+        )
+        .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_transaction_split_category")
+                    .table(TransactionSplit::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TransactionSplit::Table)
+                    .drop_foreign_key(Alias::new("fk_transaction_split_category"))
+                    .drop_column(TransactionSplit::CategoryId)
+                    .modify_column(
+                        ColumnDef::new(TransactionSplit::Category)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_transaction_split_category")
+                    .table(TransactionSplit::Table)
+                    .col(TransactionSplit::Category)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .drop_foreign_key(Alias::new("fk_transaction_category"))
+                    .drop_column(Transaction::CategoryId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Category::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Category {
+    Table,
+    Id,
+    UserId,
+    ParentId,
+    Name,
+    Kind,
+    Icon,
+    Color,
+    Archived,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Transaction {
+    Table,
+    Category,
+    CategoryId,
+}
+
+#[derive(DeriveIden)]
+enum TransactionSplit {
+    Table,
+    Category,
+    CategoryId,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
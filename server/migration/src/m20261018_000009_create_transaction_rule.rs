@@ -0,0 +1,74 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TransactionRule::Table)
+                    .if_not_exists()
+                    .col(uuid(TransactionRule::Id).primary_key())
+                    .col(uuid(TransactionRule::UserId).not_null())
+                    .col(string_len(TransactionRule::Name, 100).not_null())
+                    .col(integer(TransactionRule::Priority).default(100).not_null())
+                    .col(boolean(TransactionRule::Enabled).default(true).not_null())
+                    .col(json_binary(TransactionRule::Conditions).not_null())
+                    .col(json_binary(TransactionRule::Actions).not_null())
+                    .col(timestamp_with_time_zone(TransactionRule::CreatedAt).default(Expr::current_timestamp()).not_null())
+                    .col(timestamp_with_time_zone(TransactionRule::UpdatedAt).default(Expr::current_timestamp()).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_transaction_rule_user")
+                            .from(TransactionRule::Table, TransactionRule::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_transaction_rule_user_priority")
+                    .table(TransactionRule::Table)
+                    .col(TransactionRule::UserId)
+                    .col(TransactionRule::Priority)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TransactionRule::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TransactionRule {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Priority,
+    Enabled,
+    Conditions,
+    Actions,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
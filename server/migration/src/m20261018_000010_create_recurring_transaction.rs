@@ -0,0 +1,237 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecurringTransaction::Table)
+                    .if_not_exists()
+                    .col(uuid(RecurringTransaction::Id).primary_key())
+                    .col(uuid(RecurringTransaction::UserId).not_null())
+                    .col(string_len(RecurringTransaction::Name, 100).not_null())
+                    .col(uuid_null(RecurringTransaction::FromAccountId))
+                    .col(uuid_null(RecurringTransaction::ToAccountId))
+                    .col(string_len(RecurringTransaction::TxnType, 16).not_null())
+                    .col(decimal_len(RecurringTransaction::Amount, 18, 4).not_null())
+                    .col(string_len(RecurringTransaction::CurrencyCode, 3).not_null())
+                    .col(decimal_len_null(RecurringTransaction::ToAmount, 18, 4))
+                    .col(string_len_null(RecurringTransaction::ToCurrencyCode, 3))
+                    .col(uuid_null(RecurringTransaction::CategoryId))
+                    .col(string_len_null(RecurringTransaction::Merchant, 128))
+                    .col(text_null(RecurringTransaction::Note))
+                    .col(json_binary(RecurringTransaction::Tags).default(Expr::cust("'[]'::jsonb")).not_null())
+                    .col(string_len(RecurringTransaction::Frequency, 16).not_null())
+                    .col(integer(RecurringTransaction::Interval).default(1).not_null())
+                    .col(small_integer_null(RecurringTransaction::DayOfMonth))
+                    .col(small_integer_null(RecurringTransaction::Weekday))
+                    .col(date(RecurringTransaction::StartDate).not_null())
+                    .col(date_null(RecurringTransaction::EndDate))
+                    .col(integer_null(RecurringTransaction::MaxOccurrences))
+                    .col(integer(RecurringTransaction::OccurrenceCount).default(0).not_null())
+                    .col(date_null(RecurringTransaction::NextOccurrence))
+                    .col(boolean(RecurringTransaction::Active).default(true).not_null())
+                    .col(timestamp_with_time_zone(RecurringTransaction::CreatedAt).default(Expr::current_timestamp()).not_null())
+                    .col(timestamp_with_time_zone(RecurringTransaction::UpdatedAt).default(Expr::current_timestamp()).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_transaction_user")
+                            .from(RecurringTransaction::Table, RecurringTransaction::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_transaction_from_account")
+                            .from(RecurringTransaction::Table, RecurringTransaction::FromAccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_transaction_to_account")
+                            .from(RecurringTransaction::Table, RecurringTransaction::ToAccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_transaction_category")
+                            .from(RecurringTransaction::Table, RecurringTransaction::CategoryId)
+                            .to(Category::Table, Category::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE recurring_transaction ADD CONSTRAINT chk_recurring_frequency CHECK (frequency IN ('weekly', 'monthly', 'yearly'))"
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_recurring_transaction_user")
+                    .table(RecurringTransaction::Table)
+                    .col(RecurringTransaction::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_recurring_transaction_next")
+                    .table(RecurringTransaction::Table)
+                    .col(RecurringTransaction::NextOccurrence)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecurringOccurrence::Table)
+                    .if_not_exists()
+                    .col(uuid(RecurringOccurrence::Id).primary_key())
+                    .col(uuid(RecurringOccurrence::RecurringId).not_null())
+                    .col(date(RecurringOccurrence::OccurrenceDate).not_null())
+                    .col(string_len(RecurringOccurrence::Status, 16).not_null())
+                    .col(date_null(RecurringOccurrence::PostponedTo))
+                    .col(uuid_null(RecurringOccurrence::TransactionId))
+                    .col(text_null(RecurringOccurrence::Error))
+                    .col(timestamp_with_time_zone(RecurringOccurrence::CreatedAt).default(Expr::current_timestamp()).not_null())
+                    .col(timestamp_with_time_zone(RecurringOccurrence::UpdatedAt).default(Expr::current_timestamp()).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_occurrence_recurring")
+                            .from(RecurringOccurrence::Table, RecurringOccurrence::RecurringId)
+                            .to(RecurringTransaction::Table, RecurringTransaction::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_occurrence_transaction")
+                            .from(RecurringOccurrence::Table, RecurringOccurrence::TransactionId)
+                            .to(Transaction::Table, Transaction::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE recurring_occurrence ADD CONSTRAINT chk_recurring_occurrence_status CHECK (status IN ('posted', 'skipped', 'postponed', 'failed'))"
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_recurring_occurrence_unique")
+                    .table(RecurringOccurrence::Table)
+                    .col(RecurringOccurrence::RecurringId)
+                    .col(RecurringOccurrence::OccurrenceDate)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecurringOccurrence::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RecurringTransaction::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RecurringTransaction {
+    Table,
+    Id,
+    UserId,
+    Name,
+    FromAccountId,
+    ToAccountId,
+    TxnType,
+    Amount,
+    CurrencyCode,
+    ToAmount,
+    ToCurrencyCode,
+    CategoryId,
+    Merchant,
+    Note,
+    Tags,
+    Frequency,
+    Interval,
+    DayOfMonth,
+    Weekday,
+    StartDate,
+    EndDate,
+    MaxOccurrences,
+    OccurrenceCount,
+    NextOccurrence,
+    Active,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum RecurringOccurrence {
+    Table,
+    Id,
+    RecurringId,
+    OccurrenceDate,
+    Status,
+    PostponedTo,
+    TransactionId,
+    Error,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Account {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Category {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Transaction {
+    Table,
+    Id,
+}
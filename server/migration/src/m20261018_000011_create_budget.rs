@@ -0,0 +1,148 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Budget::Table)
+                    .if_not_exists()
+                    .col(uuid(Budget::Id).primary_key())
+                    .col(uuid(Budget::UserId).not_null())
+                    .col(uuid_null(Budget::CategoryId))
+                    .col(string_len(Budget::Name, 100).not_null())
+                    .col(decimal_len(Budget::Amount, 18, 4).not_null())
+                    .col(string_len(Budget::CurrencyCode, 3).not_null())
+                    .col(string_len(Budget::Period, 16).not_null())
+                    .col(boolean(Budget::Rollover).default(false).not_null())
+                    .col(date(Budget::StartDate).not_null())
+                    .col(timestamp_with_time_zone(Budget::CreatedAt).default(Expr::current_timestamp()).not_null())
+                    .col(timestamp_with_time_zone(Budget::UpdatedAt).default(Expr::current_timestamp()).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_budget_user")
+                            .from(Budget::Table, Budget::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_budget_category")
+                            .from(Budget::Table, Budget::CategoryId)
+                            .to(Category::Table, Category::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            "ALTER TABLE budget ADD CONSTRAINT chk_budget_period CHECK (period IN ('monthly', 'yearly'))",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "ALTER TABLE budget ADD CONSTRAINT chk_budget_amount_positive CHECK (amount > 0)",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX uk_budget_user_category_period ON budget \
+             (user_id, COALESCE(category_id, '00000000-0000-0000-0000-000000000000'::uuid), period, currency_code)",
+        )
+        .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(BudgetAlert::Table)
+                    .if_not_exists()
+                    .col(uuid(BudgetAlert::Id).primary_key())
+                    .col(uuid(BudgetAlert::BudgetId).not_null())
+                    .col(date(BudgetAlert::PeriodStart).not_null())
+                    .col(small_integer(BudgetAlert::Threshold).not_null())
+                    .col(timestamp_with_time_zone(BudgetAlert::CreatedAt).default(Expr::current_timestamp()).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_budget_alert_budget")
+                            .from(BudgetAlert::Table, BudgetAlert::BudgetId)
+                            .to(Budget::Table, Budget::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_budget_alert_period_threshold")
+                    .table(BudgetAlert::Table)
+                    .col(BudgetAlert::BudgetId)
+                    .col(BudgetAlert::PeriodStart)
+                    .col(BudgetAlert::Threshold)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BudgetAlert::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Budget::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Budget {
+    Table,
+    Id,
+    UserId,
+    CategoryId,
+    Name,
+    Amount,
+    CurrencyCode,
+    Period,
+    Rollover,
+    StartDate,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum BudgetAlert {
+    Table,
+    Id,
+    BudgetId,
+    PeriodStart,
+    Threshold,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Category {
+    Table,
+    Id,
+}
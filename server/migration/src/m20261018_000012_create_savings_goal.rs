@@ -0,0 +1,139 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SavingsGoal::Table)
+                    .if_not_exists()
+                    .col(uuid(SavingsGoal::Id).primary_key())
+                    .col(uuid(SavingsGoal::UserId).not_null())
+                    .col(string_len(SavingsGoal::Name, 100).not_null())
+                    .col(decimal_len(SavingsGoal::TargetAmount, 18, 4).not_null())
+                    .col(string_len(SavingsGoal::CurrencyCode, 3).not_null())
+                    .col(date(SavingsGoal::StartDate).not_null())
+                    .col(date_null(SavingsGoal::Deadline))
+                    .col(timestamp_with_time_zone(SavingsGoal::CreatedAt).default(Expr::current_timestamp()).not_null())
+                    .col(timestamp_with_time_zone(SavingsGoal::UpdatedAt).default(Expr::current_timestamp()).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_savings_goal_user")
+                            .from(SavingsGoal::Table, SavingsGoal::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE savings_goal ADD CONSTRAINT chk_savings_goal_target CHECK (target_amount > 0)"
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_savings_goal_user")
+                    .table(SavingsGoal::Table)
+                    .col(SavingsGoal::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SavingsGoalAccount::Table)
+                    .if_not_exists()
+                    .col(uuid(SavingsGoalAccount::GoalId).not_null())
+                    .col(uuid(SavingsGoalAccount::AccountId).not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(SavingsGoalAccount::GoalId)
+                            .col(SavingsGoalAccount::AccountId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_savings_goal_account_goal")
+                            .from(SavingsGoalAccount::Table, SavingsGoalAccount::GoalId)
+                            .to(SavingsGoal::Table, SavingsGoal::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_savings_goal_account_account")
+                            .from(SavingsGoalAccount::Table, SavingsGoalAccount::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_savings_goal_account_account")
+                    .table(SavingsGoalAccount::Table)
+                    .col(SavingsGoalAccount::AccountId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SavingsGoalAccount::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(SavingsGoal::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SavingsGoal {
+    Table,
+    Id,
+    UserId,
+    Name,
+    TargetAmount,
+    CurrencyCode,
+    StartDate,
+    Deadline,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum SavingsGoalAccount {
+    Table,
+    GoalId,
+    AccountId,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Account {
+    Table,
+    Id,
+}
@@ -0,0 +1,124 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BalanceSnapshot::Table)
+                    .if_not_exists()
+                    .col(uuid(BalanceSnapshot::Id).primary_key())
+                    .col(uuid(BalanceSnapshot::UserId).not_null())
+                    .col(uuid(BalanceSnapshot::AccountId).not_null())
+                    .col(uuid_null(BalanceSnapshot::HoldingsId))
+                    .col(date(BalanceSnapshot::SnapshotDate).not_null())
+                    .col(string_len(BalanceSnapshot::CurrencyCode, 3).not_null())
+                    .col(decimal_len_null(BalanceSnapshot::Balance, 18, 4))
+                    .col(decimal_len_null(BalanceSnapshot::Quantity, 24, 8))
+                    .col(decimal_len_null(BalanceSnapshot::MarketValue, 18, 4))
+                    .col(timestamp_with_time_zone(BalanceSnapshot::CreatedAt).default(Expr::current_timestamp()).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_balance_snapshot_user")
+                            .from(BalanceSnapshot::Table, BalanceSnapshot::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_balance_snapshot_account")
+                            .from(BalanceSnapshot::Table, BalanceSnapshot::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_balance_snapshot_holdings")
+                            .from(BalanceSnapshot::Table, BalanceSnapshot::HoldingsId)
+                            .to(Holdings::Table, Holdings::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            "ALTER TABLE balance_snapshot ADD CONSTRAINT chk_balance_snapshot_kind CHECK \
+             ((holdings_id IS NULL AND balance IS NOT NULL) OR (holdings_id IS NOT NULL AND quantity IS NOT NULL AND market_value IS NOT NULL))",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX uk_balance_snapshot_account_date ON balance_snapshot \
+             (account_id, snapshot_date) WHERE holdings_id IS NULL",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX uk_balance_snapshot_holdings_date ON balance_snapshot \
+             (holdings_id, snapshot_date) WHERE holdings_id IS NOT NULL",
+        )
+        .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_balance_snapshot_user_date")
+                    .table(BalanceSnapshot::Table)
+                    .col(BalanceSnapshot::UserId)
+                    .col(BalanceSnapshot::SnapshotDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BalanceSnapshot::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BalanceSnapshot {
+    Table,
+    Id,
+    UserId,
+    AccountId,
+    HoldingsId,
+    SnapshotDate,
+    CurrencyCode,
+    Balance,
+    Quantity,
+    MarketValue,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Account {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Holdings {
+    Table,
+    Id,
+}
@@ -0,0 +1,107 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FxRate::Table)
+                    .if_not_exists()
+                    .col(uuid(FxRate::Id).primary_key())
+                    .col(date(FxRate::RateDate).not_null())
+                    .col(string_len(FxRate::BaseCurrency, 3).not_null())
+                    .col(string_len(FxRate::QuoteCurrency, 3).not_null())
+                    .col(decimal_len(FxRate::Rate, 24, 10).not_null())
+                    .col(string_len(FxRate::Source, 32).default("manual").not_null())
+                    .col(timestamp_with_time_zone(FxRate::CreatedAt).default(Expr::current_timestamp()).not_null())
+                    .col(timestamp_with_time_zone(FxRate::UpdatedAt).default(Expr::current_timestamp()).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            "ALTER TABLE fx_rate ADD CONSTRAINT chk_fx_rate_positive CHECK (rate > 0)",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "ALTER TABLE fx_rate ADD CONSTRAINT chk_fx_rate_pair CHECK (base_currency <> quote_currency)",
+        )
+        .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_fx_rate_date_pair_source")
+                    .table(FxRate::Table)
+                    .col(FxRate::RateDate)
+                    .col(FxRate::BaseCurrency)
+                    .col(FxRate::QuoteCurrency)
+                    .col(FxRate::Source)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_fx_rate_pair_date")
+                    .table(FxRate::Table)
+                    .col(FxRate::BaseCurrency)
+                    .col(FxRate::QuoteCurrency)
+                    .col(FxRate::RateDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(User::BaseCurrency).string_len(3).null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::BaseCurrency)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(FxRate::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum FxRate {
+    Table,
+    Id,
+    RateDate,
+    BaseCurrency,
+    QuoteCurrency,
+    Rate,
+    Source,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    BaseCurrency,
+}
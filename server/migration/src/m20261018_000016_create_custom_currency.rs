@@ -0,0 +1,113 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CustomCurrency::Table)
+                    .if_not_exists()
+                    .col(uuid(CustomCurrency::Id).primary_key())
+                    .col(uuid(CustomCurrency::UserId).not_null())
+                    .col(string_len(CustomCurrency::Code, 10).not_null())
+                    .col(string_len(CustomCurrency::Name, 64).not_null())
+                    .col(small_integer(CustomCurrency::MinorUnits).default(2).not_null())
+                    .col(timestamp_with_time_zone(CustomCurrency::CreatedAt).default(Expr::current_timestamp()).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_custom_currency_user")
+                            .from(CustomCurrency::Table, CustomCurrency::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_custom_currency_user_code")
+                    .table(CustomCurrency::Table)
+                    .col(CustomCurrency::UserId)
+                    .col(CustomCurrency::Code)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE custom_currency ADD CONSTRAINT chk_custom_currency_minor_units \
+                 CHECK (minor_units BETWEEN 0 AND 4)",
+            )
+            .await?;
+
+        // Custom codes are longer than the three letters of ISO 4217.
+        for (table, column) in CURRENCY_COLUMNS {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "ALTER TABLE \"{}\" ALTER COLUMN {} TYPE varchar(10)",
+                    table, column
+                ))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (table, column) in CURRENCY_COLUMNS {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "ALTER TABLE \"{}\" ALTER COLUMN {} TYPE varchar(3)",
+                    table, column
+                ))
+                .await?;
+        }
+
+        manager
+            .drop_table(Table::drop().table(CustomCurrency::Table).to_owned())
+            .await
+    }
+}
+
+const CURRENCY_COLUMNS: &[(&str, &str)] = &[
+    ("account", "currency_code"),
+    ("balance_snapshot", "currency_code"),
+    ("budget", "currency_code"),
+    ("fx_rate", "base_currency"),
+    ("fx_rate", "quote_currency"),
+    ("holdings", "currency_code"),
+    ("recurring_transaction", "currency_code"),
+    ("recurring_transaction", "to_currency_code"),
+    ("savings_goal", "currency_code"),
+    ("transaction", "currency_code"),
+    ("transaction", "to_currency_code"),
+    ("user", "base_currency"),
+];
+
+#[derive(DeriveIden)]
+enum CustomCurrency {
+    Table,
+    Id,
+    UserId,
+    Code,
+    Name,
+    MinorUnits,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .add_column(
+                        ColumnDef::new(Transaction::OriginalAmount)
+                            .decimal_len(18, 4)
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(Transaction::OriginalCurrencyCode)
+                            .string_len(10)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE \"transaction\" ADD CONSTRAINT chk_transaction_original \
+                 CHECK ((original_amount IS NULL) = (original_currency_code IS NULL))",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .drop_column(Transaction::OriginalAmount)
+                    .drop_column(Transaction::OriginalCurrencyCode)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Transaction {
+    Table,
+    OriginalAmount,
+    OriginalCurrencyCode,
+}
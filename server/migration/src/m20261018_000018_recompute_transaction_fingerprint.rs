@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, Statement, Value};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const UPDATE_CHUNK_SIZE: usize = 1000;
+
+/// Same normalization as the transaction service. Postgres character classes
+/// depend on the database locale, so merchants are normalized in Rust.
+fn normalize_merchant(merchant: Option<&str>) -> String {
+    merchant
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .take(64)
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        let rows = db
+            .query_all(Statement::from_string(
+                backend,
+                r#"SELECT id::text AS id,
+                    concat_ws('|',
+                        txn_type,
+                        CASE WHEN from_account_id IS NOT NULL
+                            THEN 'from|' || from_account_id::text
+                            ELSE 'to|' || to_account_id::text END,
+                        trim_scale(amount)::text,
+                        currency_code) AS prefix,
+                    merchant
+                FROM "transaction"
+                WHERE from_account_id IS NOT NULL OR to_account_id IS NOT NULL"#,
+            ))
+            .await?;
+
+        let mut fingerprints = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: String = row.try_get("", "id")?;
+            let prefix: String = row.try_get("", "prefix")?;
+            let merchant: Option<String> = row.try_get("", "merchant")?;
+            fingerprints.push((
+                id,
+                format!("{}|{}", prefix, normalize_merchant(merchant.as_deref())),
+            ));
+        }
+
+        for chunk in fingerprints.chunks(UPDATE_CHUNK_SIZE) {
+            let mut values: Vec<Value> = Vec::with_capacity(chunk.len() * 2);
+            let mut placeholders = Vec::with_capacity(chunk.len());
+            for (id, fingerprint) in chunk {
+                placeholders.push(format!(
+                    "(${}::uuid, ${})",
+                    values.len() + 1,
+                    values.len() + 2
+                ));
+                values.push(id.clone().into());
+                values.push(fingerprint.clone().into());
+            }
+            db.execute(Statement::from_sql_and_values(
+                backend,
+                format!(
+                    r#"UPDATE "transaction" AS t SET fingerprint = v.fingerprint
+                    FROM (VALUES {}) AS v(id, fingerprint)
+                    WHERE t.id = v.id"#,
+                    placeholders.join(", ")
+                ),
+                values,
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Holdings::Table)
+                    .modify_column(ColumnDef::new(Holdings::Name).string_len(128).null())
+                    .modify_column(ColumnDef::new(Holdings::LastPrice).decimal_len(18, 6).null())
+                    .modify_column(ColumnDef::new(Holdings::LastPriceAt).timestamp_with_time_zone().null())
+                    .modify_column(ColumnDef::new(Holdings::MarketValue).decimal_len(18, 4).null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Fails if any imported position was stored without a name or price.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Holdings::Table)
+                    .modify_column(ColumnDef::new(Holdings::Name).string_len(128).not_null())
+                    .modify_column(ColumnDef::new(Holdings::LastPrice).decimal_len(18, 6).not_null())
+                    .modify_column(ColumnDef::new(Holdings::LastPriceAt).timestamp_with_time_zone().not_null())
+                    .modify_column(ColumnDef::new(Holdings::MarketValue).decimal_len(18, 4).not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Holdings {
+    Table,
+    Name,
+    LastPrice,
+    LastPriceAt,
+    MarketValue,
+}
@@ -1,4 +1,5 @@
 use std::env;
+use std::time::Duration;
 use tracing::warn;
 
 pub fn get_database_url() -> String {
@@ -80,3 +81,12 @@ impl NotificationConfig {
     }
 }
 
+
+pub fn get_scheduler_interval() -> Duration {
+    let secs = env::var("SCHEDULER_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(3600);
+    Duration::from_secs(secs)
+}
@@ -13,6 +13,8 @@ pub struct Model {
     pub r#type: String,
     #[sea_orm(column_type = "Decimal(Some((19, 4)))")]
     pub balance: Decimal,
+    #[sea_orm(column_type = "Decimal(Some((19, 4)))")]
+    pub opening_balance: Decimal,
     pub currency_code: String,
     pub deleted_at: Option<DateTimeWithTimeZone>,
     pub created_at: DateTimeWithTimeZone,
@@ -3,6 +3,21 @@
 pub mod prelude;
 
 pub mod account;
+pub mod balance_snapshot;
+pub mod budget;
+pub mod budget_alert;
+pub mod category;
+pub mod custom_currency;
+pub mod fx_rate;
 pub mod holdings;
+pub mod import_profile;
+pub mod recurring_occurrence;
+pub mod recurring_transaction;
+pub mod savings_goal;
+pub mod savings_goal_account;
+pub mod tag;
 pub mod transaction;
+pub mod transaction_rule;
+pub mod transaction_split;
+pub mod transaction_tags;
 pub mod user;
@@ -1,7 +1,22 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
 
 pub use super::account::Entity as Account;
+pub use super::balance_snapshot::Entity as BalanceSnapshot;
+pub use super::budget::Entity as Budget;
+pub use super::budget_alert::Entity as BudgetAlert;
+pub use super::category::Entity as Category;
+pub use super::custom_currency::Entity as CustomCurrency;
+pub use super::fx_rate::Entity as FxRate;
 pub use super::holdings::Entity as Holdings;
+pub use super::import_profile::Entity as ImportProfile;
+pub use super::recurring_occurrence::Entity as RecurringOccurrence;
+pub use super::recurring_transaction::Entity as RecurringTransaction;
+pub use super::savings_goal::Entity as SavingsGoal;
+pub use super::savings_goal_account::Entity as SavingsGoalAccount;
+pub use super::tag::Entity as Tag;
 pub use super::transaction::Entity as Transaction;
+pub use super::transaction_rule::Entity as TransactionRule;
+pub use super::transaction_split::Entity as TransactionSplit;
+pub use super::transaction_tags::Entity as TransactionTags;
 pub use super::user::Entity as User;
 
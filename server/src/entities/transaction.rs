@@ -18,12 +18,19 @@ pub struct Model {
     #[sea_orm(column_type = "Decimal(Some((18, 4)))")]
     pub to_amount: Option<Decimal>,
     pub to_currency_code: Option<String>,
-    pub category: Option<String>,
+    #[sea_orm(column_type = "Decimal(Some((24, 10)))")]
+    pub implied_rate: Option<Decimal>,
+    #[sea_orm(column_type = "Decimal(Some((18, 4)))")]
+    pub original_amount: Option<Decimal>,
+    pub original_currency_code: Option<String>,
+    pub category_id: Option<Uuid>,
     #[sea_orm(column_type = "Text")]
     pub note: Option<String>,
     pub occurred_at: DateTimeWithTimeZone,
     pub ref_transaction_id: Option<Uuid>,
     pub merchant: Option<String>,
+    pub external_id: Option<String>,
+    pub fingerprint: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
 }
@@ -46,6 +53,14 @@ pub enum Relation {
         on_delete = "SetNull"
     )]
     Account1,
+    #[sea_orm(
+        belongs_to = "super::category::Entity",
+        from = "Column::CategoryId",
+        to = "super::category::Column::Id",
+        on_update = "Cascade",
+        on_delete = "NoAction"
+    )]
+    Category,
     #[sea_orm(
         belongs_to = "Entity",
         from = "Column::RefTransactionId",
@@ -54,6 +69,10 @@ pub enum Relation {
         on_delete = "SetNull"
     )]
     SelfRef,
+    #[sea_orm(has_many = "super::transaction_split::Entity")]
+    TransactionSplit,
+    #[sea_orm(has_many = "super::transaction_tags::Entity")]
+    TransactionTags,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::UserId",
@@ -64,6 +83,33 @@ pub enum Relation {
     User,
 }
 
+impl Related<super::category::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Category.def()
+    }
+}
+
+impl Related<super::transaction_split::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransactionSplit.def()
+    }
+}
+
+impl Related<super::transaction_tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransactionTags.def()
+    }
+}
+
+impl Related<super::tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::transaction_tags::Relation::Tag.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(super::transaction_tags::Relation::Transaction.def().rev())
+    }
+}
+
 impl Related<super::user::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::User.def()
@@ -10,6 +10,7 @@ pub struct Model {
     pub id: Uuid,
     pub username: String,
     pub password_hash: String,
+    pub base_currency: Option<String>,
     pub created_at: DateTimeWithTimeZone,
 }
 
@@ -4,6 +4,7 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use serde::Serialize;
 use serde_json::json;
 use tracing::error;
 
@@ -58,21 +59,42 @@ pub enum ServiceError {
 
     #[error("Conflict: {0}")]
     Conflict(String),
+
+    #[error("Batch rejected: {} item(s) failed", .0.len())]
+    Batch(Vec<BatchItemError>),
 }
 
-impl IntoResponse for ServiceError {
-    fn into_response(self) -> Response {
-        let (status, message) = match &self {
+#[derive(Debug, Serialize)]
+pub struct BatchItemError {
+    pub index: usize,
+    pub error: String,
+}
+
+impl ServiceError {
+    pub fn status_and_message(&self) -> (StatusCode, String) {
+        match self {
             ServiceError::NotFound => (StatusCode::NOT_FOUND, "Resource not found".to_string()),
             ServiceError::Forbidden => (StatusCode::FORBIDDEN, "Access forbidden".to_string()),
             ServiceError::Validation(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
             ServiceError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
+            ServiceError::Batch(_) => (StatusCode::BAD_REQUEST, "Batch rejected".to_string()),
             ServiceError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string()),
-        };
+        }
+    }
+}
+
+impl IntoResponse for ServiceError {
+    fn into_response(self) -> Response {
+        let (status, message) = self.status_and_message();
 
         error!(error = %self, status = %status, "request failed");
 
-        (status, Json(json!({ "error": message }))).into_response()
+        match self {
+            ServiceError::Batch(items) => {
+                (status, Json(json!({ "error": message, "items": items }))).into_response()
+            }
+            _ => (status, Json(json!({ "error": message }))).into_response(),
+        }
     }
 }
 
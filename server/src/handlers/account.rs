@@ -1,16 +1,24 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Extension, Json,
 };
+use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::errors::ServiceError;
 use crate::middleware::auth::AuthUser;
 use crate::services::account::{
-    self, AccountResponse, CreateAccountRequest, UpdateAccountRequest,
+    self, AccountResponse, BalanceRecomputeResponse, CreateAccountRequest,
+    UpdateAccountRequest,
 };
+use crate::services::fx::{self, ConvertParams, Converted};
 use crate::state::AppState;
 
+#[derive(Deserialize)]
+pub struct RecomputeQuery {
+    pub dry_run: Option<bool>,
+}
+
 pub async fn create_account_handler(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
@@ -32,8 +40,10 @@ pub async fn get_account_handler(
 pub async fn list_accounts_handler(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
-) -> Result<Json<Vec<AccountResponse>>, ServiceError> {
+    Query(params): Query<ConvertParams>,
+) -> Result<Json<Vec<Converted<AccountResponse>>>, ServiceError> {
     let accounts = account::list_accounts(&state.db, user.id).await?;
+    let accounts = fx::with_conversion(&state.db, user.id, accounts, &params).await?;
     Ok(Json(accounts))
 }
 
@@ -56,3 +66,30 @@ pub async fn delete_account_handler(
     account::delete_account(&state.db, user.id, account_id).await?;
     Ok(Json(()))
 }
+
+pub async fn recompute_account_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(account_id): Path<Uuid>,
+    Query(query): Query<RecomputeQuery>,
+) -> Result<Json<BalanceRecomputeResponse>, ServiceError> {
+    let result = account::recompute_balance(
+        &state.db,
+        user.id,
+        account_id,
+        query.dry_run.unwrap_or(false),
+    )
+    .await?;
+    Ok(Json(result))
+}
+
+pub async fn recompute_all_accounts_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<RecomputeQuery>,
+) -> Result<Json<Vec<BalanceRecomputeResponse>>, ServiceError> {
+    let results =
+        account::recompute_all_balances(&state.db, user.id, query.dry_run.unwrap_or(false))
+            .await?;
+    Ok(Json(results))
+}
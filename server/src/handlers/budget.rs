@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::budget::{
+    self, BudgetResponse, BudgetStatusResponse, CreateBudgetRequest, UpdateBudgetRequest,
+};
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct BudgetStatusQuery {
+    pub date: Option<NaiveDate>,
+}
+
+pub async fn create_budget_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<CreateBudgetRequest>,
+) -> Result<Json<BudgetResponse>, ServiceError> {
+    let budget = budget::create_budget(&state.db, user.id, payload).await?;
+    Ok(Json(budget))
+}
+
+pub async fn get_budget_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(budget_id): Path<Uuid>,
+) -> Result<Json<BudgetResponse>, ServiceError> {
+    let budget = budget::get_budget(&state.db, user.id, budget_id).await?;
+    Ok(Json(budget))
+}
+
+pub async fn list_budgets_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> Result<Json<Vec<BudgetResponse>>, ServiceError> {
+    let budgets = budget::list_budgets(&state.db, user.id).await?;
+    Ok(Json(budgets))
+}
+
+pub async fn update_budget_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(budget_id): Path<Uuid>,
+    Json(payload): Json<UpdateBudgetRequest>,
+) -> Result<Json<BudgetResponse>, ServiceError> {
+    let budget = budget::update_budget(&state.db, user.id, budget_id, payload).await?;
+    Ok(Json(budget))
+}
+
+pub async fn delete_budget_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(budget_id): Path<Uuid>,
+) -> Result<Json<()>, ServiceError> {
+    budget::delete_budget(&state.db, user.id, budget_id).await?;
+    Ok(Json(()))
+}
+
+pub async fn get_budget_status_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(budget_id): Path<Uuid>,
+    Query(query): Query<BudgetStatusQuery>,
+) -> Result<Json<BudgetStatusResponse>, ServiceError> {
+    let status = budget::get_budget_status(&state.db, user.id, budget_id, query.date).await?;
+    Ok(Json(status))
+}
@@ -0,0 +1,66 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::category::{
+    self, CategoryResponse, CreateCategoryRequest, UpdateCategoryRequest,
+};
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct ListCategoriesQuery {
+    pub include_archived: Option<bool>,
+}
+
+pub async fn create_category_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<CreateCategoryRequest>,
+) -> Result<Json<CategoryResponse>, ServiceError> {
+    let category = category::create_category(&state.db, user.id, payload).await?;
+    Ok(Json(category))
+}
+
+pub async fn get_category_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(category_id): Path<Uuid>,
+) -> Result<Json<CategoryResponse>, ServiceError> {
+    let category = category::get_category(&state.db, user.id, category_id).await?;
+    Ok(Json(category))
+}
+
+pub async fn list_categories_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<ListCategoriesQuery>,
+) -> Result<Json<Vec<CategoryResponse>>, ServiceError> {
+    let categories =
+        category::list_categories(&state.db, user.id, query.include_archived.unwrap_or(false))
+            .await?;
+    Ok(Json(categories))
+}
+
+pub async fn update_category_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(category_id): Path<Uuid>,
+    Json(payload): Json<UpdateCategoryRequest>,
+) -> Result<Json<CategoryResponse>, ServiceError> {
+    let category = category::update_category(&state.db, user.id, category_id, payload).await?;
+    Ok(Json(category))
+}
+
+pub async fn delete_category_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(category_id): Path<Uuid>,
+) -> Result<Json<()>, ServiceError> {
+    category::delete_category(&state.db, user.id, category_id).await?;
+    Ok(Json(()))
+}
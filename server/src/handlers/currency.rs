@@ -0,0 +1,35 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::currency::{self, CreateCurrencyRequest, CurrencyResponse};
+use crate::state::AppState;
+
+pub async fn list_currencies_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> Result<Json<Vec<CurrencyResponse>>, ServiceError> {
+    let currencies = currency::list_currencies(&state.db, user.id).await?;
+    Ok(Json(currencies))
+}
+
+pub async fn create_currency_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<CreateCurrencyRequest>,
+) -> Result<Json<CurrencyResponse>, ServiceError> {
+    let currency = currency::create_currency(&state.db, user.id, payload).await?;
+    Ok(Json(currency))
+}
+
+pub async fn delete_currency_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(code): Path<String>,
+) -> Result<Json<()>, ServiceError> {
+    currency::delete_currency(&state.db, user.id, &code).await?;
+    Ok(Json(()))
+}
@@ -0,0 +1,39 @@
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use serde::Deserialize;
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::export::{self, ExportFormat};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    pub format: ExportFormat,
+}
+
+pub async fn export_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ServiceError> {
+    let stream = export::export_stream(state.db.clone(), user.id, query.format);
+    let disposition = format!(
+        "attachment; filename=\"life_os_export.{}\"",
+        query.format.file_extension()
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, query.format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
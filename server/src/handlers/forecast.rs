@@ -0,0 +1,19 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::forecast::{self, ForecastQuery, ForecastResponse};
+use crate::state::AppState;
+
+pub async fn forecast_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<ForecastQuery>,
+) -> Result<Json<ForecastResponse>, ServiceError> {
+    let forecast =
+        forecast::get_forecast(&state.db, state.notifier.as_ref(), user.id, query).await?;
+    Ok(Json(forecast))
+}
@@ -0,0 +1,24 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use crate::errors::ServiceError;
+use crate::services::fx::{self, ConversionResponse, ConvertQuery, FxRateQuery, FxRateResponse};
+use crate::state::AppState;
+
+pub async fn list_rates_handler(
+    State(state): State<AppState>,
+    Query(query): Query<FxRateQuery>,
+) -> Result<Json<Vec<FxRateResponse>>, ServiceError> {
+    let rates = fx::list_rates(&state.db, query).await?;
+    Ok(Json(rates))
+}
+
+pub async fn convert_handler(
+    State(state): State<AppState>,
+    Query(query): Query<ConvertQuery>,
+) -> Result<Json<ConversionResponse>, ServiceError> {
+    let conversion = fx::convert_amount(&state.db, query).await?;
+    Ok(Json(conversion))
+}
@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::goal::{
+    self, CreateGoalRequest, GoalProgressResponse, GoalResponse, UpdateGoalRequest,
+};
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct GoalProgressQuery {
+    pub date: Option<NaiveDate>,
+}
+
+pub async fn create_goal_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<CreateGoalRequest>,
+) -> Result<Json<GoalResponse>, ServiceError> {
+    let goal = goal::create_goal(&state.db, user.id, payload).await?;
+    Ok(Json(goal))
+}
+
+pub async fn get_goal_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(goal_id): Path<Uuid>,
+) -> Result<Json<GoalResponse>, ServiceError> {
+    let goal = goal::get_goal(&state.db, user.id, goal_id).await?;
+    Ok(Json(goal))
+}
+
+pub async fn list_goals_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> Result<Json<Vec<GoalResponse>>, ServiceError> {
+    let goals = goal::list_goals(&state.db, user.id).await?;
+    Ok(Json(goals))
+}
+
+pub async fn update_goal_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(goal_id): Path<Uuid>,
+    Json(payload): Json<UpdateGoalRequest>,
+) -> Result<Json<GoalResponse>, ServiceError> {
+    let goal = goal::update_goal(&state.db, user.id, goal_id, payload).await?;
+    Ok(Json(goal))
+}
+
+pub async fn delete_goal_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(goal_id): Path<Uuid>,
+) -> Result<Json<()>, ServiceError> {
+    goal::delete_goal(&state.db, user.id, goal_id).await?;
+    Ok(Json(()))
+}
+
+pub async fn get_goal_progress_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(goal_id): Path<Uuid>,
+    Query(query): Query<GoalProgressQuery>,
+) -> Result<Json<GoalProgressResponse>, ServiceError> {
+    let progress = goal::get_goal_progress(&state.db, user.id, goal_id, query.date).await?;
+    Ok(Json(progress))
+}
@@ -10,12 +10,14 @@ use crate::middleware::auth::AuthUser;
 use crate::services::holdings::{
     self, CreateHoldingsRequest, HoldingsResponse, UpdateHoldingsRequest,
 };
+use crate::services::fx::{self, ConvertParams, Converted};
 use crate::state::AppState;
 
 #[derive(Deserialize)]
 pub struct HoldingsQuery {
     pub account_id: Option<Uuid>,
     pub asset_type: Option<String>,
+    pub convert: Option<bool>,
 }
 
 pub async fn create_holdings_handler(
@@ -40,10 +42,14 @@ pub async fn list_holdings_handler(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
     Query(query): Query<HoldingsQuery>,
-) -> Result<Json<Vec<HoldingsResponse>>, ServiceError> {
+) -> Result<Json<Vec<Converted<HoldingsResponse>>>, ServiceError> {
     let holdings_list =
         holdings::list_holdings(&state.db, user.id, query.account_id, query.asset_type)
             .await?;
+    let params = ConvertParams {
+        convert: query.convert,
+    };
+    let holdings_list = fx::with_conversion(&state.db, user.id, holdings_list, &params).await?;
     Ok(Json(holdings_list))
 }
 
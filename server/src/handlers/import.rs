@@ -0,0 +1,113 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::import::{
+    self,
+    profile::{self, CreateImportProfileRequest, ImportProfileResponse, UpdateImportProfileRequest},
+    ImportCsvRequest, ImportPreviewResponse, ImportResultResponse, ImportStatementRequest,
+};
+use crate::state::AppState;
+
+pub async fn create_import_profile_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<CreateImportProfileRequest>,
+) -> Result<Json<ImportProfileResponse>, ServiceError> {
+    let profile = profile::create_profile(&state.db, user.id, payload).await?;
+    Ok(Json(profile))
+}
+
+pub async fn list_import_profiles_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> Result<Json<Vec<ImportProfileResponse>>, ServiceError> {
+    let profiles = profile::list_profiles(&state.db, user.id).await?;
+    Ok(Json(profiles))
+}
+
+pub async fn get_import_profile_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(profile_id): Path<Uuid>,
+) -> Result<Json<ImportProfileResponse>, ServiceError> {
+    let profile = profile::get_profile(&state.db, user.id, profile_id).await?;
+    Ok(Json(profile))
+}
+
+pub async fn update_import_profile_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(profile_id): Path<Uuid>,
+    Json(payload): Json<UpdateImportProfileRequest>,
+) -> Result<Json<ImportProfileResponse>, ServiceError> {
+    let profile = profile::update_profile(&state.db, user.id, profile_id, payload).await?;
+    Ok(Json(profile))
+}
+
+pub async fn delete_import_profile_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(profile_id): Path<Uuid>,
+) -> Result<Json<()>, ServiceError> {
+    profile::delete_profile(&state.db, user.id, profile_id).await?;
+    Ok(Json(()))
+}
+
+pub async fn preview_csv_import_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<ImportCsvRequest>,
+) -> Result<Json<ImportPreviewResponse>, ServiceError> {
+    let preview = import::preview_csv(&state.db, user.id, payload).await?;
+    Ok(Json(preview))
+}
+
+pub async fn import_csv_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<ImportCsvRequest>,
+) -> Result<Json<ImportResultResponse>, ServiceError> {
+    let result = import::import_csv(&state.db, user.id, payload).await?;
+    Ok(Json(result))
+}
+
+pub async fn preview_ofx_import_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<ImportStatementRequest>,
+) -> Result<Json<ImportPreviewResponse>, ServiceError> {
+    let preview = import::preview_ofx(&state.db, user.id, payload).await?;
+    Ok(Json(preview))
+}
+
+pub async fn import_ofx_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<ImportStatementRequest>,
+) -> Result<Json<ImportResultResponse>, ServiceError> {
+    let result = import::import_ofx(&state.db, user.id, payload).await?;
+    Ok(Json(result))
+}
+
+pub async fn preview_qif_import_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<ImportStatementRequest>,
+) -> Result<Json<ImportPreviewResponse>, ServiceError> {
+    let preview = import::preview_qif(&state.db, user.id, payload).await?;
+    Ok(Json(preview))
+}
+
+pub async fn import_qif_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<ImportStatementRequest>,
+) -> Result<Json<ImportResultResponse>, ServiceError> {
+    let result = import::import_qif(&state.db, user.id, payload).await?;
+    Ok(Json(result))
+}
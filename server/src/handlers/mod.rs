@@ -1,5 +1,19 @@
 pub mod account;
 pub mod auth;
+pub mod budget;
+pub mod category;
+pub mod currency;
+pub mod export;
+pub mod forecast;
+pub mod fx;
+pub mod goal;
 pub mod holdings;
+pub mod import;
+pub mod recurring;
+pub mod report;
+pub mod rule;
+pub mod settings;
+pub mod snapshot;
+pub mod tag;
 pub mod test;
 pub mod transaction;
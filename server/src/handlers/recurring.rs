@@ -0,0 +1,124 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::recurring::{
+    self, CreateRecurringRequest, OccurrenceResponse, PostponeOccurrenceRequest, RecurringResponse,
+    RunRecurringResponse, SkipOccurrenceRequest, UpdateRecurringRequest,
+};
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct PreviewQuery {
+    pub count: Option<usize>,
+}
+
+pub async fn create_recurring_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<CreateRecurringRequest>,
+) -> Result<Json<RecurringResponse>, ServiceError> {
+    let recurring = recurring::create_recurring(&state.db, user.id, payload).await?;
+    Ok(Json(recurring))
+}
+
+pub async fn get_recurring_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(recurring_id): Path<Uuid>,
+) -> Result<Json<RecurringResponse>, ServiceError> {
+    let recurring = recurring::get_recurring(&state.db, user.id, recurring_id).await?;
+    Ok(Json(recurring))
+}
+
+pub async fn list_recurring_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> Result<Json<Vec<RecurringResponse>>, ServiceError> {
+    let templates = recurring::list_recurring(&state.db, user.id).await?;
+    Ok(Json(templates))
+}
+
+pub async fn update_recurring_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(recurring_id): Path<Uuid>,
+    Json(payload): Json<UpdateRecurringRequest>,
+) -> Result<Json<RecurringResponse>, ServiceError> {
+    let recurring = recurring::update_recurring(&state.db, user.id, recurring_id, payload).await?;
+    Ok(Json(recurring))
+}
+
+pub async fn delete_recurring_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(recurring_id): Path<Uuid>,
+) -> Result<Json<()>, ServiceError> {
+    recurring::delete_recurring(&state.db, user.id, recurring_id).await?;
+    Ok(Json(()))
+}
+
+pub async fn preview_recurring_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(recurring_id): Path<Uuid>,
+    Query(query): Query<PreviewQuery>,
+) -> Result<Json<Vec<OccurrenceResponse>>, ServiceError> {
+    let preview =
+        recurring::preview_occurrences(&state.db, user.id, recurring_id, query.count).await?;
+    Ok(Json(preview))
+}
+
+pub async fn list_occurrences_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(recurring_id): Path<Uuid>,
+) -> Result<Json<Vec<OccurrenceResponse>>, ServiceError> {
+    let occurrences = recurring::list_occurrences(&state.db, user.id, recurring_id).await?;
+    Ok(Json(occurrences))
+}
+
+pub async fn skip_occurrence_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(recurring_id): Path<Uuid>,
+    Json(payload): Json<SkipOccurrenceRequest>,
+) -> Result<Json<OccurrenceResponse>, ServiceError> {
+    let occurrence = recurring::skip_occurrence(&state.db, user.id, recurring_id, payload).await?;
+    Ok(Json(occurrence))
+}
+
+pub async fn postpone_occurrence_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(recurring_id): Path<Uuid>,
+    Json(payload): Json<PostponeOccurrenceRequest>,
+) -> Result<Json<OccurrenceResponse>, ServiceError> {
+    let occurrence =
+        recurring::postpone_occurrence(&state.db, user.id, recurring_id, payload).await?;
+    Ok(Json(occurrence))
+}
+
+pub async fn restore_occurrence_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path((recurring_id, date)): Path<(Uuid, NaiveDate)>,
+) -> Result<Json<()>, ServiceError> {
+    recurring::restore_occurrence(&state.db, user.id, recurring_id, date).await?;
+    Ok(Json(()))
+}
+
+pub async fn run_recurring_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> Result<Json<RunRecurringResponse>, ServiceError> {
+    let today = Utc::now().date_naive();
+    let result = recurring::materialize_due(&state.db, Some(user.id), today).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,48 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::report::{
+    self, BreakdownQuery, BreakdownReportResponse, FxCostQuery, FxCostResponse, NetWorthQuery,
+    NetWorthResponse, SummaryQuery, SummaryReportResponse,
+};
+use crate::state::AppState;
+
+pub async fn summary_report_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<SummaryQuery>,
+) -> Result<Json<SummaryReportResponse>, ServiceError> {
+    let report = report::get_summary(&state.db, user.id, query).await?;
+    Ok(Json(report))
+}
+
+pub async fn breakdown_report_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<BreakdownQuery>,
+) -> Result<Json<BreakdownReportResponse>, ServiceError> {
+    let report = report::get_breakdown(&state.db, user.id, query).await?;
+    Ok(Json(report))
+}
+
+pub async fn net_worth_report_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<NetWorthQuery>,
+) -> Result<Json<NetWorthResponse>, ServiceError> {
+    let report = report::get_net_worth(&state.db, user.id, query).await?;
+    Ok(Json(report))
+}
+
+pub async fn fx_cost_report_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<FxCostQuery>,
+) -> Result<Json<FxCostResponse>, ServiceError> {
+    let report = report::get_fx_cost(&state.db, user.id, query).await?;
+    Ok(Json(report))
+}
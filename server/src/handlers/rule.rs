@@ -0,0 +1,66 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::rule::{
+    self, ApplyRulesRequest, ApplyRulesResponse, CreateRuleRequest, RuleResponse, UpdateRuleRequest,
+};
+use crate::state::AppState;
+
+pub async fn create_rule_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<CreateRuleRequest>,
+) -> Result<Json<RuleResponse>, ServiceError> {
+    let rule = rule::create_rule(&state.db, user.id, payload).await?;
+    Ok(Json(rule))
+}
+
+pub async fn get_rule_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<Json<RuleResponse>, ServiceError> {
+    let rule = rule::get_rule(&state.db, user.id, rule_id).await?;
+    Ok(Json(rule))
+}
+
+pub async fn list_rules_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> Result<Json<Vec<RuleResponse>>, ServiceError> {
+    let rules = rule::list_rules(&state.db, user.id).await?;
+    Ok(Json(rules))
+}
+
+pub async fn update_rule_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(rule_id): Path<Uuid>,
+    Json(payload): Json<UpdateRuleRequest>,
+) -> Result<Json<RuleResponse>, ServiceError> {
+    let rule = rule::update_rule(&state.db, user.id, rule_id, payload).await?;
+    Ok(Json(rule))
+}
+
+pub async fn delete_rule_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<Json<()>, ServiceError> {
+    rule::delete_rule(&state.db, user.id, rule_id).await?;
+    Ok(Json(()))
+}
+
+pub async fn apply_rules_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<ApplyRulesRequest>,
+) -> Result<Json<ApplyRulesResponse>, ServiceError> {
+    let result = rule::apply_rules(&state.db, user.id, payload).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,23 @@
+use axum::{extract::State, Extension, Json};
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::settings::{self, SettingsResponse, UpdateSettingsRequest};
+use crate::state::AppState;
+
+pub async fn get_settings_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> Result<Json<SettingsResponse>, ServiceError> {
+    let settings = settings::get_settings(&state.db, user.id).await?;
+    Ok(Json(settings))
+}
+
+pub async fn update_settings_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<UpdateSettingsRequest>,
+) -> Result<Json<SettingsResponse>, ServiceError> {
+    let settings = settings::update_settings(&state.db, user.id, payload).await?;
+    Ok(Json(settings))
+}
@@ -0,0 +1,29 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::snapshot::{
+    self, BackfillSnapshotsRequest, BackfillSnapshotsResponse, SnapshotQuery, SnapshotResponse,
+};
+use crate::state::AppState;
+
+pub async fn list_snapshots_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<SnapshotQuery>,
+) -> Result<Json<Vec<SnapshotResponse>>, ServiceError> {
+    let snapshots = snapshot::list_snapshots(&state.db, user.id, query).await?;
+    Ok(Json(snapshots))
+}
+
+pub async fn backfill_snapshots_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<BackfillSnapshotsRequest>,
+) -> Result<Json<BackfillSnapshotsResponse>, ServiceError> {
+    let result = snapshot::backfill_snapshots(&state.db, user.id, payload).await?;
+    Ok(Json(result))
+}
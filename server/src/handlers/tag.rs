@@ -0,0 +1,14 @@
+use axum::{extract::State, Extension, Json};
+
+use crate::errors::ServiceError;
+use crate::middleware::auth::AuthUser;
+use crate::services::tag::{self, TagResponse};
+use crate::state::AppState;
+
+pub async fn list_tags_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> Result<Json<Vec<TagResponse>>, ServiceError> {
+    let tags = tag::list_tags(&state.db, user.id).await?;
+    Ok(Json(tags))
+}
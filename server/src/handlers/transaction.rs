@@ -6,6 +6,8 @@ use uuid::Uuid;
 
 use crate::errors::ServiceError;
 use crate::middleware::auth::AuthUser;
+use crate::services::budget;
+use crate::services::fx::{self, ConvertParams, Converted};
 use crate::services::transaction::{
     self, CreateTransactionRequest, TransactionQuery, TransactionResponse,
     UpdateTransactionRequest,
@@ -18,9 +20,21 @@ pub async fn create_transaction_handler(
     Json(payload): Json<CreateTransactionRequest>,
 ) -> Result<Json<TransactionResponse>, ServiceError> {
     let txn = transaction::create_transaction(&state.db, user.id, payload).await?;
+    budget::spawn_alert_check(state.db.clone(), state.notifier.clone(), user.id, vec![txn.id]);
     Ok(Json(txn))
 }
 
+pub async fn create_transactions_batch_handler(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<Vec<CreateTransactionRequest>>,
+) -> Result<Json<Vec<TransactionResponse>>, ServiceError> {
+    let txns = transaction::create_transactions_batch(&state.db, user.id, payload).await?;
+    let ids = txns.iter().map(|t| t.id).collect();
+    budget::spawn_alert_check(state.db.clone(), state.notifier.clone(), user.id, ids);
+    Ok(Json(txns))
+}
+
 pub async fn get_transaction_handler(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
@@ -34,8 +48,10 @@ pub async fn list_transactions_handler(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
     Query(filter): Query<TransactionQuery>,
-) -> Result<Json<Vec<TransactionResponse>>, ServiceError> {
+    Query(params): Query<ConvertParams>,
+) -> Result<Json<Vec<Converted<TransactionResponse>>>, ServiceError> {
     let txns = transaction::list_transactions(&state.db, user.id, filter).await?;
+    let txns = fx::with_conversion(&state.db, user.id, txns, &params).await?;
     Ok(Json(txns))
 }
 
@@ -47,6 +63,7 @@ pub async fn update_transaction_handler(
 ) -> Result<Json<TransactionResponse>, ServiceError> {
     let txn =
         transaction::update_transaction(&state.db, user.id, txn_id, payload).await?;
+    budget::spawn_alert_check(state.db.clone(), state.notifier.clone(), user.id, vec![txn.id]);
     Ok(Json(txn))
 }
 
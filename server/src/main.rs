@@ -1,4 +1,6 @@
+use sea_orm::DatabaseConnection;
 use server::{config, db, routes, services, state};
+use services::fx::ingest::{self, IngestRatesRequest};
 use services::notify::{EmailNotifier, FeishuNotifier, MultiNotifier, NoopNotifier, Notifier};
 use state::AppState;
 use std::sync::Arc;
@@ -13,8 +15,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let database_url = config::get_database_url();
     let db = db::establish_connection(&database_url).await?;
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        return run_command(&db, &args).await;
+    }
+
     let notifier = build_notifier();
 
+    tokio::spawn(services::scheduler::run(
+        db.clone(),
+        notifier.clone(),
+        config::get_scheduler_interval(),
+    ));
+
     let state = AppState { db, notifier };
     let app = routes::create_router(state);
 
@@ -32,6 +45,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+const USAGE: &str = "Usage: server import-fx <ecb|csv> <file> [source]";
+
+/// One-off commands that run against the database instead of serving.
+async fn run_command(
+    db: &DatabaseConnection,
+    args: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    match args {
+        [command, format, path, source @ ..] if command == "import-fx" && source.len() <= 1 => {
+            let content = tokio::fs::read_to_string(path).await?;
+            let result = ingest::ingest_rates(
+                db,
+                IngestRatesRequest {
+                    format: format.clone(),
+                    content,
+                    source: source.first().cloned(),
+                },
+            )
+            .await?;
+
+            for e in &result.errors {
+                eprintln!("line {}: {}", e.line, e.error);
+            }
+            match (result.start, result.end) {
+                (Some(start), Some(end)) => println!(
+                    "Loaded {} rates from {} to {} ({} new or changed, {} rows skipped)",
+                    result.parsed,
+                    start,
+                    end,
+                    result.written,
+                    result.errors.len()
+                ),
+                _ => println!("No rates loaded ({} rows skipped)", result.errors.len()),
+            }
+            Ok(())
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
 fn build_notifier() -> Arc<dyn Notifier> {
     let config = config::NotificationConfig::from_env();
     let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
@@ -6,17 +6,58 @@ use axum::{
 
 use crate::handlers::account::{
     create_account_handler, delete_account_handler, get_account_handler, list_accounts_handler,
-    update_account_handler,
+    recompute_account_handler, recompute_all_accounts_handler, update_account_handler,
 };
 use crate::handlers::auth::{login_handler, register_handler};
+use crate::handlers::budget::{
+    create_budget_handler, delete_budget_handler, get_budget_handler, get_budget_status_handler,
+    list_budgets_handler, update_budget_handler,
+};
+use crate::handlers::category::{
+    create_category_handler, delete_category_handler, get_category_handler,
+    list_categories_handler, update_category_handler,
+};
+use crate::handlers::currency::{
+    create_currency_handler, delete_currency_handler, list_currencies_handler,
+};
+use crate::handlers::export::export_handler;
+use crate::handlers::forecast::forecast_handler;
+use crate::handlers::fx::{convert_handler, list_rates_handler};
+use crate::handlers::goal::{
+    create_goal_handler, delete_goal_handler, get_goal_handler, get_goal_progress_handler,
+    list_goals_handler, update_goal_handler,
+};
 use crate::handlers::holdings::{
     create_holdings_handler, delete_holdings_handler, get_holdings_handler,
     list_holdings_handler, update_holdings_handler,
 };
+use crate::handlers::import::{
+    create_import_profile_handler, delete_import_profile_handler, get_import_profile_handler,
+    import_csv_handler, import_ofx_handler, import_qif_handler, list_import_profiles_handler,
+    preview_csv_import_handler, preview_ofx_import_handler, preview_qif_import_handler,
+    update_import_profile_handler,
+};
+use crate::handlers::recurring::{
+    create_recurring_handler, delete_recurring_handler, get_recurring_handler,
+    list_occurrences_handler, list_recurring_handler, postpone_occurrence_handler,
+    preview_recurring_handler, restore_occurrence_handler, run_recurring_handler,
+    skip_occurrence_handler, update_recurring_handler,
+};
+use crate::handlers::report::{
+    breakdown_report_handler, fx_cost_report_handler, net_worth_report_handler,
+    summary_report_handler,
+};
+use crate::handlers::rule::{
+    apply_rules_handler, create_rule_handler, delete_rule_handler, get_rule_handler,
+    list_rules_handler, update_rule_handler,
+};
+use crate::handlers::settings::{get_settings_handler, update_settings_handler};
+use crate::handlers::snapshot::{backfill_snapshots_handler, list_snapshots_handler};
+use crate::handlers::tag::list_tags_handler;
 use crate::handlers::test::test_notification_handler;
 use crate::handlers::transaction::{
-    create_transaction_handler, delete_transaction_handler, get_transaction_handler,
-    list_transactions_handler, update_transaction_handler,
+    create_transaction_handler, create_transactions_batch_handler, delete_transaction_handler,
+    get_transaction_handler, list_transactions_handler, update_transaction_handler,
 };
 use crate::middleware::auth::auth_middleware;
 use crate::state::AppState;
@@ -28,16 +69,83 @@ pub fn create_router(state: AppState) -> Router {
         .route("/accounts/{account_id}", get(get_account_handler))
         .route("/accounts/{account_id}", put(update_account_handler))
         .route("/accounts/{account_id}", delete(delete_account_handler))
+        .route("/accounts/recompute", post(recompute_all_accounts_handler))
+        .route("/accounts/{account_id}/recompute", post(recompute_account_handler))
         .route("/transactions", post(create_transaction_handler))
         .route("/transactions", get(list_transactions_handler))
+        .route("/transactions/batch", post(create_transactions_batch_handler))
         .route("/transactions/{txn_id}", get(get_transaction_handler))
         .route("/transactions/{txn_id}", put(update_transaction_handler))
         .route("/transactions/{txn_id}", delete(delete_transaction_handler))
+        .route("/categories", post(create_category_handler))
+        .route("/categories", get(list_categories_handler))
+        .route("/categories/{category_id}", get(get_category_handler))
+        .route("/categories/{category_id}", put(update_category_handler))
+        .route("/categories/{category_id}", delete(delete_category_handler))
+        .route("/tags", get(list_tags_handler))
+        .route("/rules", post(create_rule_handler))
+        .route("/rules", get(list_rules_handler))
+        .route("/rules/apply", post(apply_rules_handler))
+        .route("/rules/{rule_id}", get(get_rule_handler))
+        .route("/rules/{rule_id}", put(update_rule_handler))
+        .route("/rules/{rule_id}", delete(delete_rule_handler))
+        .route("/budgets", post(create_budget_handler))
+        .route("/budgets", get(list_budgets_handler))
+        .route("/budgets/{budget_id}", get(get_budget_handler))
+        .route("/budgets/{budget_id}", put(update_budget_handler))
+        .route("/budgets/{budget_id}", delete(delete_budget_handler))
+        .route("/budgets/{budget_id}/status", get(get_budget_status_handler))
+        .route("/goals", post(create_goal_handler))
+        .route("/goals", get(list_goals_handler))
+        .route("/goals/{goal_id}", get(get_goal_handler))
+        .route("/goals/{goal_id}", put(update_goal_handler))
+        .route("/goals/{goal_id}", delete(delete_goal_handler))
+        .route("/goals/{goal_id}/progress", get(get_goal_progress_handler))
+        .route("/recurring", post(create_recurring_handler))
+        .route("/recurring", get(list_recurring_handler))
+        .route("/recurring/run", post(run_recurring_handler))
+        .route("/recurring/{recurring_id}", get(get_recurring_handler))
+        .route("/recurring/{recurring_id}", put(update_recurring_handler))
+        .route("/recurring/{recurring_id}", delete(delete_recurring_handler))
+        .route("/recurring/{recurring_id}/preview", get(preview_recurring_handler))
+        .route("/recurring/{recurring_id}/occurrences", get(list_occurrences_handler))
+        .route("/recurring/{recurring_id}/skip", post(skip_occurrence_handler))
+        .route("/recurring/{recurring_id}/postpone", post(postpone_occurrence_handler))
+        .route(
+            "/recurring/{recurring_id}/occurrences/{date}",
+            delete(restore_occurrence_handler),
+        )
+        .route("/reports/summary", get(summary_report_handler))
+        .route("/reports/breakdown", get(breakdown_report_handler))
+        .route("/reports/net-worth", get(net_worth_report_handler))
+        .route("/reports/fx-cost", get(fx_cost_report_handler))
+        .route("/forecast", get(forecast_handler))
+        .route("/fx/rates", get(list_rates_handler))
+        .route("/fx/convert", get(convert_handler))
+        .route("/currencies", get(list_currencies_handler))
+        .route("/currencies", post(create_currency_handler))
+        .route("/currencies/{code}", delete(delete_currency_handler))
+        .route("/settings", get(get_settings_handler))
+        .route("/settings", put(update_settings_handler))
+        .route("/snapshots", get(list_snapshots_handler))
+        .route("/snapshots/backfill", post(backfill_snapshots_handler))
         .route("/holdings", post(create_holdings_handler))
         .route("/holdings", get(list_holdings_handler))
         .route("/holdings/{holdings_id}", get(get_holdings_handler))
         .route("/holdings/{holdings_id}", put(update_holdings_handler))
         .route("/holdings/{holdings_id}", delete(delete_holdings_handler))
+        .route("/import/profiles", post(create_import_profile_handler))
+        .route("/import/profiles", get(list_import_profiles_handler))
+        .route("/import/profiles/{profile_id}", get(get_import_profile_handler))
+        .route("/import/profiles/{profile_id}", put(update_import_profile_handler))
+        .route("/import/profiles/{profile_id}", delete(delete_import_profile_handler))
+        .route("/import/csv/preview", post(preview_csv_import_handler))
+        .route("/import/csv", post(import_csv_handler))
+        .route("/import/ofx/preview", post(preview_ofx_import_handler))
+        .route("/import/ofx", post(import_ofx_handler))
+        .route("/import/qif/preview", post(preview_qif_import_handler))
+        .route("/import/qif", post(import_qif_handler))
+        .route("/export", get(export_handler))
         .layer(middleware::from_fn(auth_middleware));
 
     Router::new()
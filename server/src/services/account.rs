@@ -1,11 +1,22 @@
-use chrono::{DateTime, Utc};
-use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, Set};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use sea_orm::{
+    sea_query::{Expr, Func},
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbBackend,
+    EntityTrait, FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    Statement, TransactionTrait,
+};
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use rust_decimal::Decimal;
 
-use crate::entities::{account, holdings, prelude::*, transaction};
+use crate::entities::{account, prelude::*, transaction};
 use crate::errors::ServiceError;
+use crate::services::currency::validate_currency_code;
+
+/// Account types whose balance is money owed rather than owned.
+pub(crate) const LIABILITY_TYPES: &[&str] =
+    &["credit_card", "credit", "loan", "mortgage", "liability"];
 
 #[derive(Debug, Deserialize)]
 pub struct CreateAccountRequest {
@@ -28,6 +39,7 @@ pub struct AccountResponse {
     pub name: String,
     pub r#type: String,
     pub balance: Decimal,
+    pub opening_balance: Decimal,
     pub currency_code: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -40,6 +52,7 @@ impl From<account::Model> for AccountResponse {
             name: model.name,
             r#type: model.r#type,
             balance: model.balance,
+            opening_balance: model.opening_balance,
             currency_code: model.currency_code,
             created_at: model.created_at.with_timezone(&Utc),
             updated_at: model.updated_at.with_timezone(&Utc),
@@ -47,6 +60,15 @@ impl From<account::Model> for AccountResponse {
     }
 }
 
+#[derive(Debug, Serialize)]
+pub struct BalanceRecomputeResponse {
+    pub account_id: Uuid,
+    pub stored_balance: Decimal,
+    pub computed_balance: Decimal,
+    pub drift: Decimal,
+    pub repaired: bool,
+}
+
 fn validate_account_type(t: &str) -> Result<(), ServiceError> {
     if t.trim().is_empty() {
         return Err(ServiceError::Validation(
@@ -56,26 +78,7 @@ fn validate_account_type(t: &str) -> Result<(), ServiceError> {
     Ok(())
 }
 
-fn validate_currency_code(code: &str) -> Result<(), ServiceError> {
-    if code.trim().is_empty() {
-        return Err(ServiceError::Validation(
-            "Currency code cannot be empty".to_string()
-        ));
-    }
-    if code.len() > 10 {
-        return Err(ServiceError::Validation(
-            "Currency code too long (max 10 chars)".to_string()
-        ));
-    }
-    if !code.chars().all(|c| c.is_ascii_alphanumeric()) {
-        return Err(ServiceError::Validation(
-            "Currency code must be alphanumeric".to_string()
-        ));
-    }
-    Ok(())
-}
-
-async fn load_owned_account(
+pub(crate) async fn load_owned_account(
     db: &DatabaseConnection,
     user_id: Uuid,
     account_id: Uuid,
@@ -96,6 +99,209 @@ async fn load_owned_account(
     Ok(account)
 }
 
+pub(crate) async fn adjust_balance<C: ConnectionTrait>(
+    db: &C,
+    account_id: Uuid,
+    delta: Decimal,
+) -> Result<(), ServiceError> {
+    let now: sea_orm::prelude::DateTimeWithTimeZone = Utc::now().into();
+    Account::update_many()
+        .col_expr(
+            account::Column::Balance,
+            Expr::col(account::Column::Balance).add(delta),
+        )
+        .col_expr(account::Column::UpdatedAt, Expr::value(now))
+        .filter(account::Column::Id.eq(account_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub(crate) async fn compute_balance<C: ConnectionTrait>(
+    db: &C,
+    account: &account::Model,
+) -> Result<Decimal, ServiceError> {
+    let credited: Option<Decimal> = Transaction::find()
+        .select_only()
+        .column_as(
+            Expr::expr(Func::coalesce([
+                Expr::col(transaction::Column::ToAmount).into(),
+                Expr::col(transaction::Column::Amount).into(),
+            ]))
+            .sum(),
+            "total",
+        )
+        .filter(transaction::Column::ToAccountId.eq(account.id))
+        .into_tuple()
+        .one(db)
+        .await?
+        .flatten();
+
+    let debited: Option<Decimal> = Transaction::find()
+        .select_only()
+        .column_as(Expr::col(transaction::Column::Amount).sum(), "total")
+        .filter(transaction::Column::FromAccountId.eq(account.id))
+        .into_tuple()
+        .one(db)
+        .await?
+        .flatten();
+
+    Ok(account.opening_balance + credited.unwrap_or(Decimal::ZERO)
+        - debited.unwrap_or(Decimal::ZERO))
+}
+
+#[derive(Debug, FromQueryResult)]
+struct DailyDelta {
+    account_id: Uuid,
+    day: NaiveDate,
+    delta: Decimal,
+}
+
+/// End-of-day balances of every account the user had on each of `dates`
+/// (ascending), replayed from the opening balance. An entry is `None` once
+/// the account has been deleted.
+pub(crate) async fn balance_history<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    dates: &[NaiveDate],
+) -> Result<Vec<(account::Model, Vec<Option<Decimal>>)>, ServiceError> {
+    let Some(last) = dates.last() else {
+        return Ok(Vec::new());
+    };
+    let until = Utc.from_utc_datetime(&last.succ_opt().unwrap_or(*last).and_time(NaiveTime::MIN));
+
+    let accounts = Account::find()
+        .filter(account::Column::UserId.eq(user_id))
+        .order_by_asc(account::Column::Name)
+        .all(db)
+        .await?;
+
+    let rows = DailyDelta::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"SELECT x.account_id, x.day, SUM(x.delta) AS delta
+           FROM (
+               SELECT to_account_id AS account_id,
+                      (occurred_at AT TIME ZONE 'UTC')::date AS day,
+                      COALESCE(to_amount, amount) AS delta
+               FROM "transaction"
+               WHERE user_id = $1 AND to_account_id IS NOT NULL AND occurred_at < $2
+               UNION ALL
+               SELECT from_account_id AS account_id,
+                      (occurred_at AT TIME ZONE 'UTC')::date AS day,
+                      -amount AS delta
+               FROM "transaction"
+               WHERE user_id = $1 AND from_account_id IS NOT NULL AND occurred_at < $2
+           ) x
+           GROUP BY 1, 2
+           ORDER BY 1, 2"#,
+        [user_id.into(), until.into()],
+    ))
+    .all(db)
+    .await?;
+
+    let mut deltas: HashMap<Uuid, Vec<(NaiveDate, Decimal)>> = HashMap::new();
+    for row in rows {
+        deltas
+            .entry(row.account_id)
+            .or_default()
+            .push((row.day, row.delta));
+    }
+
+    Ok(accounts
+        .into_iter()
+        .map(|account| {
+            let days = deltas.remove(&account.id).unwrap_or_default();
+            let deleted_on = account.deleted_at.map(|d| d.with_timezone(&Utc).date_naive());
+            let mut balance = account.opening_balance;
+            let mut next = days.iter().peekable();
+            let balances = dates
+                .iter()
+                .map(|date| {
+                    while let Some((_, delta)) = next.next_if(|(day, _)| day <= date) {
+                        balance += *delta;
+                    }
+                    match deleted_on {
+                        Some(deleted) if deleted <= *date => None,
+                        _ => Some(balance),
+                    }
+                })
+                .collect();
+            (account, balances)
+        })
+        .collect())
+}
+
+async fn recompute_locked<C: ConnectionTrait>(
+    db: &C,
+    account: account::Model,
+    dry_run: bool,
+) -> Result<BalanceRecomputeResponse, ServiceError> {
+    let computed = compute_balance(db, &account).await?;
+    let drift = account.balance - computed;
+    let repaired = !dry_run && !drift.is_zero();
+
+    let response = BalanceRecomputeResponse {
+        account_id: account.id,
+        stored_balance: account.balance,
+        computed_balance: computed,
+        drift,
+        repaired,
+    };
+
+    if repaired {
+        let mut active: account::ActiveModel = account.into();
+        active.balance = Set(computed);
+        active.updated_at = Set(Utc::now().into());
+        active.update(db).await?;
+    }
+
+    Ok(response)
+}
+
+pub async fn recompute_balance(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    account_id: Uuid,
+    dry_run: bool,
+) -> Result<BalanceRecomputeResponse, ServiceError> {
+    load_owned_account(db, user_id, account_id).await?;
+
+    let tx = db.begin().await?;
+    let account = Account::find_by_id(account_id)
+        .lock_exclusive()
+        .one(&tx)
+        .await?
+        .ok_or(ServiceError::NotFound)?;
+
+    let response = recompute_locked(&tx, account, dry_run).await?;
+    tx.commit().await?;
+
+    Ok(response)
+}
+
+pub async fn recompute_all_balances(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    dry_run: bool,
+) -> Result<Vec<BalanceRecomputeResponse>, ServiceError> {
+    let tx = db.begin().await?;
+    let accounts = Account::find()
+        .filter(account::Column::UserId.eq(user_id))
+        .filter(account::Column::DeletedAt.is_null())
+        .lock_exclusive()
+        .all(&tx)
+        .await?;
+
+    let mut results = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        results.push(recompute_locked(&tx, account, dry_run).await?);
+    }
+    tx.commit().await?;
+
+    Ok(results)
+}
+
 pub async fn create_account(
     db: &DatabaseConnection,
     user_id: Uuid,
@@ -109,8 +315,7 @@ pub async fn create_account(
     let account_type = req.r#type.trim().to_lowercase();
     validate_account_type(&account_type)?;
 
-    let currency = req.currency_code.trim().to_uppercase();
-    validate_currency_code(&currency)?;
+    let currency = validate_currency_code(db, user_id, &req.currency_code).await?;
 
     let initial_balance = req.initial_balance.unwrap_or(Decimal::ZERO);
 
@@ -121,6 +326,7 @@ pub async fn create_account(
         name: Set(name.to_string()),
         r#type: Set(account_type),
         balance: Set(initial_balance),
+        opening_balance: Set(initial_balance),
         currency_code: Set(currency),
         created_at: Set(now),
         updated_at: Set(now),
@@ -165,9 +371,25 @@ pub async fn update_account(
         let normalized = t.trim().to_lowercase();
         validate_account_type(&normalized)?;
     }
-    if let Some(ref c) = req.currency_code {
-        let normalized = c.trim().to_uppercase();
-        validate_currency_code(&normalized)?;
+    let currency_code = match req.currency_code {
+        Some(ref c) => Some(validate_currency_code(db, user_id, c).await?),
+        None => None,
+    };
+    if currency_code.as_ref().is_some_and(|c| *c != account.currency_code) {
+        let used = Transaction::find()
+            .filter(transaction::Column::UserId.eq(user_id))
+            .filter(
+                Condition::any()
+                    .add(transaction::Column::FromAccountId.eq(account.id))
+                    .add(transaction::Column::ToAccountId.eq(account.id)),
+            )
+            .count(db)
+            .await?;
+        if used > 0 {
+            return Err(ServiceError::Conflict(
+                "Cannot change the currency of an account with transactions".to_string(),
+            ));
+        }
     }
     if let Some(ref n) = req.name {
         if n.trim().is_empty() {
@@ -183,8 +405,8 @@ pub async fn update_account(
     if let Some(t) = req.r#type {
         active.r#type = Set(t.trim().to_lowercase());
     }
-    if let Some(c) = req.currency_code {
-        active.currency_code = Set(c.trim().to_uppercase());
+    if let Some(c) = currency_code {
+        active.currency_code = Set(c);
     }
     active.updated_at = Set(Utc::now().into());
 
@@ -42,6 +42,7 @@ pub async fn register(
         id: Set(user_id),
         username: Set(username.clone()),
         password_hash: Set(password_hash),
+        base_currency: Set(None),
         created_at: Set(Utc::now().into()),
     };
 
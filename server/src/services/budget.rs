@@ -0,0 +1,645 @@
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    DbBackend, EntityTrait, FromQueryResult, QueryFilter, QueryOrder, Set, Statement, Value,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::entities::{budget, budget_alert, prelude::*, transaction, transaction_split};
+use crate::errors::ServiceError;
+use crate::services::category::CategoryTree;
+use crate::services::notify::Notifier;
+use crate::services::currency::validate_currency_code;
+
+const VALID_PERIODS: &[&str] = &["monthly", "yearly"];
+
+/// Spending thresholds, in percent of the available amount, that trigger an alert.
+const ALERT_THRESHOLDS: &[i16] = &[80, 100];
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBudgetRequest {
+    pub name: String,
+    pub category_id: Option<Uuid>,
+    pub amount: Decimal,
+    pub currency_code: String,
+    pub period: Option<String>,
+    pub rollover: Option<bool>,
+    pub start_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateBudgetRequest {
+    pub name: Option<String>,
+    pub amount: Option<Decimal>,
+    pub rollover: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BudgetResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub category_id: Option<Uuid>,
+    pub category: Option<String>,
+    pub amount: Decimal,
+    pub currency_code: String,
+    pub period: String,
+    pub rollover: bool,
+    pub start_date: NaiveDate,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BudgetResponse {
+    fn new(model: budget::Model, categories: &CategoryTree) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            category_id: model.category_id,
+            category: model.category_id.and_then(|id| categories.path(id)),
+            amount: model.amount,
+            currency_code: model.currency_code,
+            period: model.period,
+            rollover: model.rollover,
+            start_date: model.start_date,
+            created_at: model.created_at.with_timezone(&Utc),
+            updated_at: model.updated_at.with_timezone(&Utc),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BudgetStatusResponse {
+    pub budget_id: Uuid,
+    pub name: String,
+    pub currency_code: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub limit: Decimal,
+    pub rollover: Decimal,
+    pub available: Decimal,
+    pub spent: Decimal,
+    pub remaining: Decimal,
+    /// `None` when rollover has used up the whole period's limit.
+    pub percent_used: Option<Decimal>,
+    pub over_budget: bool,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct PeriodSpend {
+    period_start: NaiveDate,
+    spent: Decimal,
+}
+
+fn period_start(period: &str, date: NaiveDate) -> NaiveDate {
+    let month = if period == "yearly" { 1 } else { date.month() };
+    NaiveDate::from_ymd_opt(date.year(), month, 1).unwrap_or(date)
+}
+
+fn next_period(period: &str, start: NaiveDate) -> NaiveDate {
+    let months = if period == "yearly" { 12 } else { 1 };
+    start
+        .checked_add_months(Months::new(months))
+        .unwrap_or(NaiveDate::MAX)
+}
+
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN))
+}
+
+fn validate_name(name: &str) -> Result<String, ServiceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ServiceError::Validation(
+            "Budget name cannot be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > 100 {
+        return Err(ServiceError::Validation(
+            "Budget name too long (max 100 chars)".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_amount(amount: Decimal) -> Result<(), ServiceError> {
+    if amount <= Decimal::ZERO {
+        return Err(ServiceError::Validation(
+            "Budget amount must be positive".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Expense spending per period, net of refunds, with split lines counted
+/// against their own categories.
+async fn spent_by_period<C: ConnectionTrait>(
+    db: &C,
+    budget: &budget::Model,
+    categories: Option<&[Uuid]>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<HashMap<NaiveDate, Decimal>, ServiceError> {
+    let unit = if budget.period == "yearly" {
+        "year"
+    } else {
+        "month"
+    };
+    let mut values: Vec<Value> = vec![
+        budget.user_id.into(),
+        budget.currency_code.clone().into(),
+        start_of_day(from).into(),
+        start_of_day(to).into(),
+    ];
+
+    let category_filter = match categories {
+        Some(ids) => {
+            let placeholders: Vec<String> = ids
+                .iter()
+                .enumerate()
+                .map(|(i, _)| format!("${}", values.len() + i + 1))
+                .collect();
+            values.extend(ids.iter().map(|id| Value::from(*id)));
+            format!("WHERE x.category_id IN ({})", placeholders.join(", "))
+        }
+        None => String::new(),
+    };
+
+    let sql = format!(
+        r#"SELECT (date_trunc('{unit}', x.occurred_at AT TIME ZONE 'UTC'))::date AS period_start,
+                  SUM(x.amount) AS spent
+           FROM (
+               SELECT t.occurred_at,
+                      COALESCE(s.category_id, t.category_id) AS category_id,
+                      COALESCE(s.amount, t.amount) AS amount
+               FROM "transaction" t
+               LEFT JOIN transaction_split s ON s.transaction_id = t.id
+               WHERE t.user_id = $1 AND t.txn_type = 'expense' AND t.currency_code = $2
+                 AND t.occurred_at >= $3 AND t.occurred_at < $4
+               UNION ALL
+               SELECT t.occurred_at,
+                      COALESCE(t.category_id, s.category_id, r.category_id) AS category_id,
+                      -COALESCE(ROUND(t.amount * s.amount / r.amount, 4), t.amount) AS amount
+               FROM "transaction" t
+               JOIN "transaction" r ON r.id = t.ref_transaction_id
+               LEFT JOIN transaction_split s
+                      ON s.transaction_id = r.id AND t.category_id IS NULL
+               WHERE t.user_id = $1 AND t.txn_type = 'refund' AND r.txn_type = 'expense'
+                 AND t.currency_code = $2 AND t.occurred_at >= $3 AND t.occurred_at < $4
+           ) x
+           {category_filter}
+           GROUP BY 1"#
+    );
+
+    let rows = PeriodSpend::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        values,
+    ))
+    .all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| (r.period_start, r.spent))
+        .collect())
+}
+
+async fn compute_status<C: ConnectionTrait>(
+    db: &C,
+    budget: &budget::Model,
+    categories: &CategoryTree,
+    date: NaiveDate,
+) -> Result<BudgetStatusResponse, ServiceError> {
+    let start = period_start(&budget.period, date);
+    let end = next_period(&budget.period, start);
+    let from = if budget.rollover && budget.start_date < start {
+        budget.start_date
+    } else {
+        start
+    };
+
+    let category_ids = budget.category_id.map(|id| categories.descendants(id));
+    let spent = spent_by_period(db, budget, category_ids.as_deref(), from, end).await?;
+
+    let mut carried = Decimal::ZERO;
+    let mut cursor = from;
+    while cursor < start {
+        carried += budget.amount - spent.get(&cursor).copied().unwrap_or_default();
+        cursor = next_period(&budget.period, cursor);
+    }
+
+    let spent = spent.get(&start).copied().unwrap_or_default();
+    let available = budget.amount + carried;
+    let percent_used =
+        (available > Decimal::ZERO).then(|| (spent * Decimal::ONE_HUNDRED / available).round_dp(2));
+
+    Ok(BudgetStatusResponse {
+        budget_id: budget.id,
+        name: budget.name.clone(),
+        currency_code: budget.currency_code.clone(),
+        period_start: start,
+        period_end: end.pred_opt().unwrap_or(end),
+        limit: budget.amount,
+        rollover: carried,
+        available,
+        spent,
+        remaining: available - spent,
+        percent_used,
+        over_budget: spent > available,
+    })
+}
+
+async fn load_owned_budget<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    budget_id: Uuid,
+) -> Result<budget::Model, ServiceError> {
+    let budget = Budget::find_by_id(budget_id)
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound)?;
+
+    if budget.user_id != user_id {
+        return Err(ServiceError::Forbidden);
+    }
+
+    Ok(budget)
+}
+
+pub async fn create_budget(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: CreateBudgetRequest,
+) -> Result<BudgetResponse, ServiceError> {
+    let name = validate_name(&req.name)?;
+    validate_amount(req.amount)?;
+    let currency_code = validate_currency_code(db, user_id, &req.currency_code).await?;
+
+    let period = req.period.unwrap_or_else(|| "monthly".to_string());
+    if !VALID_PERIODS.contains(&period.as_str()) {
+        return Err(ServiceError::Validation(format!(
+            "Invalid period: {}. Must be one of: {}",
+            period,
+            VALID_PERIODS.join(", ")
+        )));
+    }
+
+    let categories = CategoryTree::load(db, user_id).await?;
+    if let Some(category_id) = req.category_id {
+        let category =
+            Category::find_by_id(category_id)
+                .one(db)
+                .await?
+                .ok_or(ServiceError::Validation(format!(
+                    "Category {} not found",
+                    category_id
+                )))?;
+        if category.user_id != user_id {
+            return Err(ServiceError::Forbidden);
+        }
+        if category.kind != "expense" {
+            return Err(ServiceError::Validation(
+                "Budgets can only track expense categories".to_string(),
+            ));
+        }
+    }
+
+    let mut existing = Budget::find()
+        .filter(budget::Column::UserId.eq(user_id))
+        .filter(budget::Column::Period.eq(period.clone()))
+        .filter(budget::Column::CurrencyCode.eq(currency_code.clone()));
+    existing = match req.category_id {
+        Some(id) => existing.filter(budget::Column::CategoryId.eq(id)),
+        None => existing.filter(budget::Column::CategoryId.is_null()),
+    };
+    if existing.one(db).await?.is_some() {
+        return Err(ServiceError::Conflict(
+            "A budget for this category, period and currency already exists".to_string(),
+        ));
+    }
+
+    let start_date = period_start(
+        &period,
+        req.start_date.unwrap_or_else(|| Utc::now().date_naive()),
+    );
+    let now = Utc::now().into();
+    let budget = budget::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        category_id: Set(req.category_id),
+        name: Set(name),
+        amount: Set(req.amount),
+        currency_code: Set(currency_code),
+        period: Set(period),
+        rollover: Set(req.rollover.unwrap_or(false)),
+        start_date: Set(start_date),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    let model = budget.insert(db).await?;
+    Ok(BudgetResponse::new(model, &categories))
+}
+
+pub async fn get_budget(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    budget_id: Uuid,
+) -> Result<BudgetResponse, ServiceError> {
+    let budget = load_owned_budget(db, user_id, budget_id).await?;
+    let categories = CategoryTree::load(db, user_id).await?;
+    Ok(BudgetResponse::new(budget, &categories))
+}
+
+pub async fn list_budgets(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<BudgetResponse>, ServiceError> {
+    let budgets = Budget::find()
+        .filter(budget::Column::UserId.eq(user_id))
+        .order_by_asc(budget::Column::Name)
+        .all(db)
+        .await?;
+    let categories = CategoryTree::load(db, user_id).await?;
+
+    Ok(budgets
+        .into_iter()
+        .map(|b| BudgetResponse::new(b, &categories))
+        .collect())
+}
+
+pub async fn update_budget(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    budget_id: Uuid,
+    req: UpdateBudgetRequest,
+) -> Result<BudgetResponse, ServiceError> {
+    let existing = load_owned_budget(db, user_id, budget_id).await?;
+    let mut budget: budget::ActiveModel = existing.into();
+
+    if let Some(name) = req.name {
+        budget.name = Set(validate_name(&name)?);
+    }
+    if let Some(amount) = req.amount {
+        validate_amount(amount)?;
+        budget.amount = Set(amount);
+    }
+    if let Some(rollover) = req.rollover {
+        budget.rollover = Set(rollover);
+    }
+    budget.updated_at = Set(Utc::now().into());
+
+    let model = budget.update(db).await?;
+    let categories = CategoryTree::load(db, user_id).await?;
+    Ok(BudgetResponse::new(model, &categories))
+}
+
+pub async fn delete_budget(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    budget_id: Uuid,
+) -> Result<(), ServiceError> {
+    let budget = load_owned_budget(db, user_id, budget_id).await?;
+    let active: budget::ActiveModel = budget.into();
+    active.delete(db).await?;
+    Ok(())
+}
+
+pub async fn get_budget_status(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    budget_id: Uuid,
+    date: Option<NaiveDate>,
+) -> Result<BudgetStatusResponse, ServiceError> {
+    let budget = load_owned_budget(db, user_id, budget_id).await?;
+    let categories = CategoryTree::load(db, user_id).await?;
+    let date = date.unwrap_or_else(|| Utc::now().date_naive());
+    compute_status(db, &budget, &categories, date).await
+}
+
+/// Sends an alert for thresholds the current period has newly crossed and
+/// records them once the notification went out. Each threshold is sent at most
+/// once per budget period; a failed send is retried on the next check.
+async fn evaluate_alerts(
+    db: &DatabaseConnection,
+    notifier: &dyn Notifier,
+    budget: &budget::Model,
+    categories: &CategoryTree,
+    today: NaiveDate,
+) -> Result<Option<i16>, ServiceError> {
+    let status = compute_status(db, budget, categories, today).await?;
+    let reached = match status.percent_used {
+        Some(percent) => percent,
+        // Rollover used up the limit, so any real spending is over budget.
+        None if status.spent > Decimal::ZERO => Decimal::ONE_HUNDRED,
+        None => return Ok(None),
+    };
+
+    let crossed: Vec<i16> = ALERT_THRESHOLDS
+        .iter()
+        .copied()
+        .filter(|&threshold| reached >= Decimal::from(threshold))
+        .collect();
+    if crossed.is_empty() {
+        return Ok(None);
+    }
+
+    let recorded: HashSet<i16> = BudgetAlert::find()
+        .filter(budget_alert::Column::BudgetId.eq(budget.id))
+        .filter(budget_alert::Column::PeriodStart.eq(status.period_start))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|a| a.threshold)
+        .collect();
+    let pending: Vec<i16> = crossed
+        .into_iter()
+        .filter(|threshold| !recorded.contains(threshold))
+        .collect();
+    let Some(&threshold) = pending.iter().max() else {
+        return Ok(None);
+    };
+
+    let message = format!(
+        "Budget '{}' has reached {}% for the period starting {}: spent {} of {} {}",
+        status.name,
+        threshold,
+        status.period_start,
+        status.spent.round_dp(2),
+        status.available.round_dp(2),
+        status.currency_code
+    );
+    if let Err(e) = notifier.send(&message).await {
+        warn!(budget_id = %budget.id, error = %e, "Failed to send budget alert");
+        return Ok(None);
+    }
+
+    let alerts = pending.into_iter().map(|threshold| budget_alert::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        budget_id: Set(budget.id),
+        period_start: Set(status.period_start),
+        threshold: Set(threshold),
+        created_at: Set(Utc::now().into()),
+    });
+    BudgetAlert::insert_many(alerts)
+        .on_conflict(
+            OnConflict::columns([
+                budget_alert::Column::BudgetId,
+                budget_alert::Column::PeriodStart,
+                budget_alert::Column::Threshold,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(Some(threshold))
+}
+
+/// Checks the budgets touched by the given transactions and alerts on any
+/// threshold crossed in their current period.
+pub async fn check_alerts(
+    db: &DatabaseConnection,
+    notifier: &dyn Notifier,
+    user_id: Uuid,
+    transaction_ids: &[Uuid],
+) -> Result<usize, ServiceError> {
+    let today = Utc::now().date_naive();
+    let txns = Transaction::find()
+        .filter(transaction::Column::UserId.eq(user_id))
+        .filter(transaction::Column::Id.is_in(transaction_ids.to_vec()))
+        .filter(transaction::Column::TxnType.is_in(["expense", "refund"]))
+        .all(db)
+        .await?;
+    if txns.is_empty() {
+        return Ok(0);
+    }
+
+    let budgets = Budget::find()
+        .filter(budget::Column::UserId.eq(user_id))
+        .all(db)
+        .await?;
+    if budgets.is_empty() {
+        return Ok(0);
+    }
+
+    let ref_ids: Vec<Uuid> = txns.iter().filter_map(|t| t.ref_transaction_id).collect();
+    let splits = TransactionSplit::find()
+        .filter(
+            transaction_split::Column::TransactionId
+                .is_in(txns.iter().map(|t| t.id).chain(ref_ids.iter().copied())),
+        )
+        .all(db)
+        .await?;
+    let ref_categories: HashMap<Uuid, Option<Uuid>> = Transaction::find()
+        .filter(transaction::Column::Id.is_in(ref_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|t| (t.id, t.category_id))
+        .collect();
+
+    let categories = CategoryTree::load(db, user_id).await?;
+    let mut touched: HashSet<Uuid> = HashSet::new();
+    for txn in &txns {
+        let date = txn.occurred_at.with_timezone(&Utc).date_naive();
+        let split_categories = |id: Uuid| {
+            splits
+                .iter()
+                .filter(move |s| s.transaction_id == id)
+                .map(|s| s.category_id)
+        };
+        let mut txn_categories: Vec<Uuid> = split_categories(txn.id).collect();
+        match (txn.category_id, txn.ref_transaction_id) {
+            (Some(id), _) => txn_categories.push(id),
+            // Uncategorized refunds count against the original's categories.
+            (None, Some(ref_id)) => {
+                txn_categories.extend(ref_categories.get(&ref_id).copied().flatten());
+                txn_categories.extend(split_categories(ref_id));
+            }
+            (None, None) => {}
+        }
+
+        for budget in &budgets {
+            if budget.currency_code != txn.currency_code
+                || period_start(&budget.period, date) != period_start(&budget.period, today)
+            {
+                continue;
+            }
+            let matches = match budget.category_id {
+                None => true,
+                Some(id) => {
+                    let scope = categories.descendants(id);
+                    txn_categories.iter().any(|c| scope.contains(c))
+                }
+            };
+            if matches {
+                touched.insert(budget.id);
+            }
+        }
+    }
+
+    let mut sent = 0;
+    for budget in budgets.iter().filter(|b| touched.contains(&b.id)) {
+        if evaluate_alerts(db, notifier, budget, &categories, today)
+            .await?
+            .is_some()
+        {
+            sent += 1;
+        }
+    }
+
+    Ok(sent)
+}
+
+/// Runs `check_alerts` in the background so the request is not held up by
+/// slow notification channels.
+pub fn spawn_alert_check(
+    db: DatabaseConnection,
+    notifier: Arc<dyn Notifier>,
+    user_id: Uuid,
+    transaction_ids: Vec<Uuid>,
+) {
+    tokio::spawn(async move {
+        if let Err(e) = check_alerts(&db, notifier.as_ref(), user_id, &transaction_ids).await {
+            error!(user_id = %user_id, error = %e, "Failed to check budget alerts");
+        }
+    });
+}
+
+/// Evaluates every budget's current period, catching spending that did not go
+/// through the interactive endpoints (imports, recurring transactions).
+pub async fn sweep_alerts(
+    db: &DatabaseConnection,
+    notifier: &dyn Notifier,
+    today: NaiveDate,
+) -> Result<usize, ServiceError> {
+    let budgets = Budget::find()
+        .order_by_asc(budget::Column::UserId)
+        .all(db)
+        .await?;
+
+    let mut categories = CategoryTree::default();
+    let mut loaded_for = None;
+    let mut sent = 0;
+    for budget in &budgets {
+        if loaded_for != Some(budget.user_id) {
+            categories = CategoryTree::load(db, budget.user_id).await?;
+            loaded_for = Some(budget.user_id);
+        }
+        if evaluate_alerts(db, notifier, budget, &categories, today)
+            .await?
+            .is_some()
+        {
+            sent += 1;
+        }
+    }
+
+    Ok(sent)
+}
@@ -0,0 +1,619 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::{Expr, Func},
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{
+    budget, category, prelude::*, recurring_transaction, transaction, transaction_rule,
+    transaction_split,
+};
+use crate::errors::ServiceError;
+use crate::utils::nullable::double_option;
+
+pub const PATH_SEPARATOR: char = ':';
+
+const VALID_KINDS: &[&str] = &["expense", "income"];
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateCategoryRequest {
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    pub kind: Option<String>,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateCategoryRequest {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub parent_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub icon: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub color: Option<Option<String>>,
+    pub archived: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryResponse {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+    pub path: String,
+    pub kind: String,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    pub archived: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CategoryResponse {
+    fn new(model: category::Model, tree: &CategoryTree) -> Self {
+        Self {
+            path: tree.path(model.id).unwrap_or_else(|| model.name.clone()),
+            id: model.id,
+            parent_id: model.parent_id,
+            name: model.name,
+            kind: model.kind,
+            icon: model.icon,
+            color: model.color,
+            archived: model.archived,
+            created_at: model.created_at.with_timezone(&Utc),
+            updated_at: model.updated_at.with_timezone(&Utc),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CategoryTree {
+    nodes: HashMap<Uuid, category::Model>,
+}
+
+impl CategoryTree {
+    pub async fn load<C: ConnectionTrait>(db: &C, user_id: Uuid) -> Result<Self, ServiceError> {
+        let nodes = Category::find()
+            .filter(category::Column::UserId.eq(user_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|c| (c.id, c))
+            .collect();
+
+        Ok(Self { nodes })
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<&category::Model> {
+        self.nodes.get(&id)
+    }
+
+    pub fn path(&self, id: Uuid) -> Option<String> {
+        let mut names = Vec::new();
+        let mut current = self.nodes.get(&id);
+        while let Some(node) = current {
+            names.push(node.name.as_str());
+            if names.len() > self.nodes.len() {
+                break;
+            }
+            current = node.parent_id.and_then(|p| self.nodes.get(&p));
+        }
+        if names.is_empty() {
+            return None;
+        }
+        names.reverse();
+        Some(names.join(&PATH_SEPARATOR.to_string()))
+    }
+
+    pub fn root(&self, id: Uuid) -> Option<&category::Model> {
+        let mut current = self.nodes.get(&id)?;
+        for _ in 0..self.nodes.len() {
+            match current.parent_id.and_then(|p| self.nodes.get(&p)) {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        Some(current)
+    }
+
+    pub fn descendants(&self, id: Uuid) -> Vec<Uuid> {
+        let mut result = vec![id];
+        let mut i = 0;
+        while i < result.len() {
+            let parent = result[i];
+            result.extend(
+                self.nodes
+                    .values()
+                    .filter(|c| c.parent_id == Some(parent))
+                    .map(|c| c.id),
+            );
+            i += 1;
+        }
+        result
+    }
+
+    /// The category followed by its parents up to the root.
+    pub fn ancestors(&self, id: Uuid) -> Vec<Uuid> {
+        let mut result = Vec::new();
+        let mut current = self.nodes.get(&id);
+        while let Some(node) = current {
+            result.push(node.id);
+            if result.len() > self.nodes.len() {
+                break;
+            }
+            current = node.parent_id.and_then(|p| self.nodes.get(&p));
+        }
+        result
+    }
+
+    fn is_ancestor(&self, ancestor: Uuid, id: Uuid) -> bool {
+        let mut current = Some(id);
+        for _ in 0..=self.nodes.len() {
+            match current {
+                Some(c) if c == ancestor => return true,
+                Some(c) => current = self.nodes.get(&c).and_then(|n| n.parent_id),
+                None => return false,
+            }
+        }
+        false
+    }
+
+    fn child(&self, kind: &str, parent_id: Option<Uuid>, name: &str) -> Option<&category::Model> {
+        let name = name.to_lowercase();
+        self.nodes
+            .values()
+            .find(|c| c.kind == kind && c.parent_id == parent_id && c.name.to_lowercase() == name)
+    }
+
+    pub fn find_path(&self, path: &str) -> Vec<&category::Model> {
+        let segments = split_path(path);
+        if segments.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<&category::Model> = Vec::new();
+        for kind in VALID_KINDS {
+            let mut parent = None;
+            let mut found = None;
+            for segment in &segments {
+                found = self.child(kind, parent, segment);
+                match found {
+                    Some(node) => parent = Some(node.id),
+                    None => break,
+                }
+            }
+            matches.extend(found);
+        }
+        matches
+    }
+}
+
+fn split_path(path: &str) -> Vec<&str> {
+    path.split(PATH_SEPARATOR)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+pub(crate) fn kind_for_txn_type(txn_type: &str) -> &'static str {
+    if txn_type == "income" {
+        "income"
+    } else {
+        "expense"
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, ServiceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ServiceError::Validation(
+            "Category name cannot be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > 64 {
+        return Err(ServiceError::Validation(
+            "Category name too long (max 64 chars)".to_string(),
+        ));
+    }
+    if name.contains(PATH_SEPARATOR) {
+        return Err(ServiceError::Validation(format!(
+            "Category name cannot contain '{}'",
+            PATH_SEPARATOR
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_kind(kind: &str) -> Result<String, ServiceError> {
+    let kind = kind.trim().to_lowercase();
+    if !VALID_KINDS.contains(&kind.as_str()) {
+        return Err(ServiceError::Validation(format!(
+            "Invalid category kind: {}",
+            kind
+        )));
+    }
+    Ok(kind)
+}
+
+fn normalize_icon(icon: Option<String>) -> Result<Option<String>, ServiceError> {
+    let icon = icon.map(|i| i.trim().to_string()).filter(|i| !i.is_empty());
+    if icon.as_ref().is_some_and(|i| i.chars().count() > 32) {
+        return Err(ServiceError::Validation(
+            "Icon too long (max 32 chars)".to_string(),
+        ));
+    }
+    Ok(icon)
+}
+
+fn normalize_color(color: Option<String>) -> Result<Option<String>, ServiceError> {
+    let Some(color) = color
+        .map(|c| c.trim().to_lowercase())
+        .filter(|c| !c.is_empty())
+    else {
+        return Ok(None);
+    };
+
+    let valid = color.strip_prefix('#').is_some_and(|hex| {
+        (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    });
+    if !valid {
+        return Err(ServiceError::Validation(
+            "Color must be a hex value like #RGB or #RRGGBB".to_string(),
+        ));
+    }
+    Ok(Some(color))
+}
+
+fn ensure_unique_name(
+    tree: &CategoryTree,
+    kind: &str,
+    parent_id: Option<Uuid>,
+    name: &str,
+    exclude: Option<Uuid>,
+) -> Result<(), ServiceError> {
+    match tree.child(kind, parent_id, name) {
+        Some(existing) if Some(existing.id) != exclude => Err(ServiceError::Conflict(format!(
+            "Category {} already exists",
+            tree.path(existing.id)
+                .unwrap_or_else(|| existing.name.clone())
+        ))),
+        _ => Ok(()),
+    }
+}
+
+async fn load_owned_category<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    category_id: Uuid,
+) -> Result<category::Model, ServiceError> {
+    let category = Category::find_by_id(category_id)
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound)?;
+
+    if category.user_id != user_id {
+        return Err(ServiceError::Forbidden);
+    }
+
+    Ok(category)
+}
+
+async fn find_child<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    kind: &str,
+    parent_id: Option<Uuid>,
+    name: &str,
+) -> Result<Option<category::Model>, ServiceError> {
+    let parent = match parent_id {
+        Some(id) => category::Column::ParentId.eq(id),
+        None => category::Column::ParentId.is_null(),
+    };
+
+    let found = Category::find()
+        .filter(category::Column::UserId.eq(user_id))
+        .filter(category::Column::Kind.eq(kind))
+        .filter(parent)
+        .filter(Expr::expr(Func::lower(Expr::col(category::Column::Name))).eq(name.to_lowercase()))
+        .one(db)
+        .await?;
+
+    Ok(found)
+}
+
+/// Resolves a `Parent:Child` path to a category id, creating missing nodes.
+pub(crate) async fn resolve_path<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    kind: &str,
+    path: &str,
+) -> Result<Uuid, ServiceError> {
+    let segments = split_path(path);
+    if segments.is_empty() {
+        return Err(ServiceError::Validation(
+            "Category cannot be empty".to_string(),
+        ));
+    }
+
+    let mut parent_id = None;
+    for segment in segments {
+        let name = validate_name(segment)?;
+        let node = match find_child(db, user_id, kind, parent_id, &name).await? {
+            Some(existing) => existing,
+            None => {
+                let now = Utc::now().into();
+                category::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    user_id: Set(user_id),
+                    parent_id: Set(parent_id),
+                    name: Set(name),
+                    kind: Set(kind.to_string()),
+                    icon: Set(None),
+                    color: Set(None),
+                    archived: Set(false),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                }
+                .insert(db)
+                .await?
+            }
+        };
+        parent_id = Some(node.id);
+
+        if node.archived {
+            return Err(ServiceError::Validation(format!(
+                "Category {} is archived",
+                path.trim()
+            )));
+        }
+    }
+
+    Ok(parent_id.expect("path has at least one segment"))
+}
+
+/// Checks that `category_id` can be assigned to a transaction of the given kind.
+/// `current` is the category already stored on the record, which may stay even if archived.
+pub(crate) async fn verify_assignable<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    kind: &str,
+    category_id: Uuid,
+    current: Option<Uuid>,
+) -> Result<(), ServiceError> {
+    let category =
+        Category::find_by_id(category_id)
+            .one(db)
+            .await?
+            .ok_or(ServiceError::Validation(format!(
+                "Category {} not found",
+                category_id
+            )))?;
+
+    if category.user_id != user_id {
+        return Err(ServiceError::Forbidden);
+    }
+
+    if category.kind != kind {
+        return Err(ServiceError::Validation(format!(
+            "Category {} is not an {} category",
+            category.name, kind
+        )));
+    }
+
+    if category.archived && current != Some(category_id) {
+        return Err(ServiceError::Validation(format!(
+            "Category {} is archived",
+            category.name
+        )));
+    }
+
+    Ok(())
+}
+
+pub async fn create_category(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: CreateCategoryRequest,
+) -> Result<CategoryResponse, ServiceError> {
+    let name = validate_name(&req.name)?;
+    let tree = CategoryTree::load(db, user_id).await?;
+
+    let kind = match req.parent_id {
+        Some(parent_id) => {
+            let parent = tree.get(parent_id).ok_or(ServiceError::Validation(format!(
+                "Parent category {} not found",
+                parent_id
+            )))?;
+            let kind = parent.kind.clone();
+            if let Some(requested) = req.kind {
+                if validate_kind(&requested)? != kind {
+                    return Err(ServiceError::Validation(
+                        "Category kind must match its parent".to_string(),
+                    ));
+                }
+            }
+            kind
+        }
+        None => validate_kind(req.kind.as_deref().unwrap_or("expense"))?,
+    };
+
+    ensure_unique_name(&tree, &kind, req.parent_id, &name, None)?;
+
+    let now = Utc::now().into();
+    let category = category::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        parent_id: Set(req.parent_id),
+        name: Set(name),
+        kind: Set(kind),
+        icon: Set(normalize_icon(req.icon)?),
+        color: Set(normalize_color(req.color)?),
+        archived: Set(false),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    let model = category.insert(db).await?;
+    let mut tree = tree;
+    tree.nodes.insert(model.id, model.clone());
+
+    Ok(CategoryResponse::new(model, &tree))
+}
+
+pub async fn get_category(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    category_id: Uuid,
+) -> Result<CategoryResponse, ServiceError> {
+    let category = load_owned_category(db, user_id, category_id).await?;
+    let tree = CategoryTree::load(db, user_id).await?;
+    Ok(CategoryResponse::new(category, &tree))
+}
+
+pub async fn list_categories(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    include_archived: bool,
+) -> Result<Vec<CategoryResponse>, ServiceError> {
+    let tree = CategoryTree::load(db, user_id).await?;
+
+    let mut query = Category::find().filter(category::Column::UserId.eq(user_id));
+    if !include_archived {
+        query = query.filter(category::Column::Archived.eq(false));
+    }
+
+    let categories = query
+        .order_by_asc(category::Column::Kind)
+        .order_by_asc(category::Column::Name)
+        .all(db)
+        .await?;
+
+    let mut responses: Vec<CategoryResponse> = categories
+        .into_iter()
+        .map(|c| CategoryResponse::new(c, &tree))
+        .collect();
+    responses.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.path.cmp(&b.path)));
+
+    Ok(responses)
+}
+
+pub async fn update_category(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    category_id: Uuid,
+    req: UpdateCategoryRequest,
+) -> Result<CategoryResponse, ServiceError> {
+    let existing = load_owned_category(db, user_id, category_id).await?;
+    let mut tree = CategoryTree::load(db, user_id).await?;
+
+    let name = match req.name {
+        Some(name) => validate_name(&name)?,
+        None => existing.name.clone(),
+    };
+    let parent_id = req.parent_id.unwrap_or(existing.parent_id);
+
+    if let Some(parent_id) = parent_id {
+        let parent = tree.get(parent_id).ok_or(ServiceError::Validation(format!(
+            "Parent category {} not found",
+            parent_id
+        )))?;
+        if parent.kind != existing.kind {
+            return Err(ServiceError::Validation(
+                "Category kind must match its parent".to_string(),
+            ));
+        }
+        if tree.is_ancestor(category_id, parent_id) {
+            return Err(ServiceError::Validation(
+                "Category cannot be moved under itself or its descendants".to_string(),
+            ));
+        }
+    }
+
+    ensure_unique_name(&tree, &existing.kind, parent_id, &name, Some(category_id))?;
+
+    let mut category: category::ActiveModel = existing.clone().into();
+    category.name = Set(name);
+    category.parent_id = Set(parent_id);
+    if let Some(icon) = req.icon {
+        category.icon = Set(normalize_icon(icon)?);
+    }
+    if let Some(color) = req.color {
+        category.color = Set(normalize_color(color)?);
+    }
+    if let Some(archived) = req.archived {
+        category.archived = Set(archived);
+    }
+    category.updated_at = Set(Utc::now().into());
+
+    let model = category.update(db).await?;
+    tree.nodes.insert(model.id, model.clone());
+
+    Ok(CategoryResponse::new(model, &tree))
+}
+
+pub async fn delete_category(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    category_id: Uuid,
+) -> Result<(), ServiceError> {
+    let category = load_owned_category(db, user_id, category_id).await?;
+
+    let children = Category::find()
+        .filter(category::Column::ParentId.eq(category_id))
+        .count(db)
+        .await?;
+    if children > 0 {
+        return Err(ServiceError::Conflict(
+            "Cannot delete category with subcategories".to_string(),
+        ));
+    }
+
+    let used_by_transactions = Transaction::find()
+        .filter(transaction::Column::CategoryId.eq(category_id))
+        .count(db)
+        .await?;
+    let used_by_splits = TransactionSplit::find()
+        .filter(transaction_split::Column::CategoryId.eq(category_id))
+        .count(db)
+        .await?;
+    if used_by_transactions + used_by_splits > 0 {
+        return Err(ServiceError::Conflict(
+            "Cannot delete category in use; archive it instead".to_string(),
+        ));
+    }
+
+    // Budgets would be deleted with the category and recurring templates and
+    // rules would silently lose it.
+    let used_by_budgets = Budget::find()
+        .filter(budget::Column::CategoryId.eq(category_id))
+        .count(db)
+        .await?;
+    let used_by_recurring = RecurringTransaction::find()
+        .filter(recurring_transaction::Column::CategoryId.eq(category_id))
+        .count(db)
+        .await?;
+    let used_by_rules = TransactionRule::find()
+        .filter(transaction_rule::Column::UserId.eq(user_id))
+        .filter(Expr::cust_with_values(
+            "actions->>'category_id' = $1",
+            [category_id.to_string()],
+        ))
+        .count(db)
+        .await?;
+    if used_by_budgets + used_by_recurring + used_by_rules > 0 {
+        return Err(ServiceError::Conflict(
+            "Cannot delete category used by budgets, recurring transactions or rules; archive it instead"
+                .to_string(),
+        ));
+    }
+
+    let active: category::ActiveModel = category.into();
+    active.delete(db).await?;
+
+    Ok(())
+}
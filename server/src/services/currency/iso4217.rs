@@ -0,0 +1,199 @@
+//! ISO 4217 currency table.
+
+pub(crate) struct IsoCurrency {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub minor_units: u32,
+}
+
+const fn iso(code: &'static str, minor_units: u32, name: &'static str) -> IsoCurrency {
+    IsoCurrency {
+        code,
+        name,
+        minor_units,
+    }
+}
+
+/// Active codes. Funds and precious metals without a defined minor unit use
+/// the storage scale.
+pub(crate) const CURRENT: &[IsoCurrency] = &[
+    iso("AED", 2, "UAE Dirham"),
+    iso("AFN", 2, "Afghani"),
+    iso("ALL", 2, "Lek"),
+    iso("AMD", 2, "Armenian Dram"),
+    iso("AOA", 2, "Kwanza"),
+    iso("ARS", 2, "Argentine Peso"),
+    iso("AUD", 2, "Australian Dollar"),
+    iso("AWG", 2, "Aruban Florin"),
+    iso("AZN", 2, "Azerbaijan Manat"),
+    iso("BAM", 2, "Convertible Mark"),
+    iso("BBD", 2, "Barbados Dollar"),
+    iso("BDT", 2, "Taka"),
+    iso("BGN", 2, "Bulgarian Lev"),
+    iso("BHD", 3, "Bahraini Dinar"),
+    iso("BIF", 0, "Burundi Franc"),
+    iso("BMD", 2, "Bermudian Dollar"),
+    iso("BND", 2, "Brunei Dollar"),
+    iso("BOB", 2, "Boliviano"),
+    iso("BOV", 2, "Mvdol"),
+    iso("BRL", 2, "Brazilian Real"),
+    iso("BSD", 2, "Bahamian Dollar"),
+    iso("BTN", 2, "Ngultrum"),
+    iso("BWP", 2, "Pula"),
+    iso("BYN", 2, "Belarusian Ruble"),
+    iso("BZD", 2, "Belize Dollar"),
+    iso("CAD", 2, "Canadian Dollar"),
+    iso("CDF", 2, "Congolese Franc"),
+    iso("CHE", 2, "WIR Euro"),
+    iso("CHF", 2, "Swiss Franc"),
+    iso("CHW", 2, "WIR Franc"),
+    iso("CLF", 4, "Unidad de Fomento"),
+    iso("CLP", 0, "Chilean Peso"),
+    iso("CNY", 2, "Yuan Renminbi"),
+    iso("COP", 2, "Colombian Peso"),
+    iso("COU", 2, "Unidad de Valor Real"),
+    iso("CRC", 2, "Costa Rican Colon"),
+    iso("CUP", 2, "Cuban Peso"),
+    iso("CVE", 2, "Cabo Verde Escudo"),
+    iso("CZK", 2, "Czech Koruna"),
+    iso("DJF", 0, "Djibouti Franc"),
+    iso("DKK", 2, "Danish Krone"),
+    iso("DOP", 2, "Dominican Peso"),
+    iso("DZD", 2, "Algerian Dinar"),
+    iso("EGP", 2, "Egyptian Pound"),
+    iso("ERN", 2, "Nakfa"),
+    iso("ETB", 2, "Ethiopian Birr"),
+    iso("EUR", 2, "Euro"),
+    iso("FJD", 2, "Fiji Dollar"),
+    iso("FKP", 2, "Falkland Islands Pound"),
+    iso("GBP", 2, "Pound Sterling"),
+    iso("GEL", 2, "Lari"),
+    iso("GHS", 2, "Ghana Cedi"),
+    iso("GIP", 2, "Gibraltar Pound"),
+    iso("GMD", 2, "Dalasi"),
+    iso("GNF", 0, "Guinean Franc"),
+    iso("GTQ", 2, "Quetzal"),
+    iso("GYD", 2, "Guyana Dollar"),
+    iso("HKD", 2, "Hong Kong Dollar"),
+    iso("HNL", 2, "Lempira"),
+    iso("HTG", 2, "Gourde"),
+    iso("HUF", 2, "Forint"),
+    iso("IDR", 2, "Rupiah"),
+    iso("ILS", 2, "New Israeli Sheqel"),
+    iso("INR", 2, "Indian Rupee"),
+    iso("IQD", 3, "Iraqi Dinar"),
+    iso("IRR", 2, "Iranian Rial"),
+    iso("ISK", 0, "Iceland Krona"),
+    iso("JMD", 2, "Jamaican Dollar"),
+    iso("JOD", 3, "Jordanian Dinar"),
+    iso("JPY", 0, "Yen"),
+    iso("KES", 2, "Kenyan Shilling"),
+    iso("KGS", 2, "Som"),
+    iso("KHR", 2, "Riel"),
+    iso("KMF", 0, "Comorian Franc"),
+    iso("KPW", 2, "North Korean Won"),
+    iso("KRW", 0, "Won"),
+    iso("KWD", 3, "Kuwaiti Dinar"),
+    iso("KYD", 2, "Cayman Islands Dollar"),
+    iso("KZT", 2, "Tenge"),
+    iso("LAK", 2, "Lao Kip"),
+    iso("LBP", 2, "Lebanese Pound"),
+    iso("LKR", 2, "Sri Lanka Rupee"),
+    iso("LRD", 2, "Liberian Dollar"),
+    iso("LSL", 2, "Loti"),
+    iso("LYD", 3, "Libyan Dinar"),
+    iso("MAD", 2, "Moroccan Dirham"),
+    iso("MDL", 2, "Moldovan Leu"),
+    iso("MGA", 2, "Malagasy Ariary"),
+    iso("MKD", 2, "Denar"),
+    iso("MMK", 2, "Kyat"),
+    iso("MNT", 2, "Tugrik"),
+    iso("MOP", 2, "Pataca"),
+    iso("MRU", 2, "Ouguiya"),
+    iso("MUR", 2, "Mauritius Rupee"),
+    iso("MVR", 2, "Rufiyaa"),
+    iso("MWK", 2, "Malawi Kwacha"),
+    iso("MXN", 2, "Mexican Peso"),
+    iso("MXV", 2, "Mexican Unidad de Inversion"),
+    iso("MYR", 2, "Malaysian Ringgit"),
+    iso("MZN", 2, "Mozambique Metical"),
+    iso("NAD", 2, "Namibia Dollar"),
+    iso("NGN", 2, "Naira"),
+    iso("NIO", 2, "Cordoba Oro"),
+    iso("NOK", 2, "Norwegian Krone"),
+    iso("NPR", 2, "Nepalese Rupee"),
+    iso("NZD", 2, "New Zealand Dollar"),
+    iso("OMR", 3, "Rial Omani"),
+    iso("PAB", 2, "Balboa"),
+    iso("PEN", 2, "Sol"),
+    iso("PGK", 2, "Kina"),
+    iso("PHP", 2, "Philippine Peso"),
+    iso("PKR", 2, "Pakistan Rupee"),
+    iso("PLN", 2, "Zloty"),
+    iso("PYG", 0, "Guarani"),
+    iso("QAR", 2, "Qatari Rial"),
+    iso("RON", 2, "Romanian Leu"),
+    iso("RSD", 2, "Serbian Dinar"),
+    iso("RUB", 2, "Russian Ruble"),
+    iso("RWF", 0, "Rwanda Franc"),
+    iso("SAR", 2, "Saudi Riyal"),
+    iso("SBD", 2, "Solomon Islands Dollar"),
+    iso("SCR", 2, "Seychelles Rupee"),
+    iso("SDG", 2, "Sudanese Pound"),
+    iso("SEK", 2, "Swedish Krona"),
+    iso("SGD", 2, "Singapore Dollar"),
+    iso("SHP", 2, "Saint Helena Pound"),
+    iso("SLE", 2, "Leone"),
+    iso("SOS", 2, "Somali Shilling"),
+    iso("SRD", 2, "Surinam Dollar"),
+    iso("SSP", 2, "South Sudanese Pound"),
+    iso("STN", 2, "Dobra"),
+    iso("SVC", 2, "El Salvador Colon"),
+    iso("SYP", 2, "Syrian Pound"),
+    iso("SZL", 2, "Lilangeni"),
+    iso("THB", 2, "Baht"),
+    iso("TJS", 2, "Somoni"),
+    iso("TMT", 2, "Turkmenistan New Manat"),
+    iso("TND", 3, "Tunisian Dinar"),
+    iso("TOP", 2, "Pa'anga"),
+    iso("TRY", 2, "Turkish Lira"),
+    iso("TTD", 2, "Trinidad and Tobago Dollar"),
+    iso("TWD", 2, "New Taiwan Dollar"),
+    iso("TZS", 2, "Tanzanian Shilling"),
+    iso("UAH", 2, "Hryvnia"),
+    iso("UGX", 0, "Uganda Shilling"),
+    iso("USD", 2, "US Dollar"),
+    iso("USN", 2, "US Dollar (Next day)"),
+    iso("UYI", 0, "Uruguay Peso en Unidades Indexadas"),
+    iso("UYU", 2, "Peso Uruguayo"),
+    iso("UYW", 4, "Unidad Previsional"),
+    iso("UZS", 2, "Uzbekistan Sum"),
+    iso("VED", 2, "Bolivar Soberano"),
+    iso("VES", 2, "Bolivar Soberano"),
+    iso("VND", 0, "Dong"),
+    iso("VUV", 0, "Vatu"),
+    iso("WST", 2, "Tala"),
+    iso("XAF", 0, "CFA Franc BEAC"),
+    iso("XAG", 4, "Silver"),
+    iso("XAU", 4, "Gold"),
+    iso("XCD", 2, "East Caribbean Dollar"),
+    iso("XCG", 2, "Caribbean Guilder"),
+    iso("XDR", 4, "SDR (Special Drawing Right)"),
+    iso("XOF", 0, "CFA Franc BCEAO"),
+    iso("XPD", 4, "Palladium"),
+    iso("XPF", 0, "CFP Franc"),
+    iso("XPT", 4, "Platinum"),
+    iso("YER", 2, "Yemeni Rial"),
+    iso("ZAR", 2, "Rand"),
+    iso("ZMW", 2, "Zambian Kwacha"),
+    iso("ZWG", 2, "Zimbabwe Gold"),
+];
+
+/// Withdrawn codes that still turn up in historical rate data, such as the
+/// euro legacy currencies in the ECB reference series.
+pub(crate) const HISTORIC: &[&str] = &[
+    "ANG", "ATS", "AZM", "BEF", "BYR", "CUC", "CYP", "DEM", "EEK", "ESP", "FIM", "FRF", "GHC",
+    "GRD", "HRK", "IEP", "ITL", "LTL", "LUF", "LVL", "MRO", "MTL", "MZM", "NLG", "PTE", "ROL",
+    "RUR", "SDD", "SIT", "SKK", "SLL", "STD", "TMM", "TRL", "VEB", "VEF", "XEU", "YUM", "ZMK",
+    "ZWL",
+];
@@ -0,0 +1,302 @@
+mod iso4217;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{
+    account, budget, custom_currency, holdings, prelude::*, recurring_transaction, savings_goal,
+    transaction, user,
+};
+use crate::errors::ServiceError;
+
+use iso4217::{IsoCurrency, CURRENT, HISTORIC};
+
+/// Decimal places amounts are stored with; the precision used for codes
+/// that define no minor unit of their own.
+pub const STORAGE_SCALE: u32 = 4;
+
+const DEFAULT_MINOR_UNITS: u32 = 2;
+
+const MAX_CUSTOM_CODE_LEN: usize = 10;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCurrencyRequest {
+    pub code: String,
+    pub name: String,
+    pub minor_units: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CurrencyResponse {
+    pub code: String,
+    pub name: String,
+    pub minor_units: u32,
+    pub custom: bool,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<&IsoCurrency> for CurrencyResponse {
+    fn from(currency: &IsoCurrency) -> Self {
+        Self {
+            code: currency.code.to_string(),
+            name: currency.name.to_string(),
+            minor_units: currency.minor_units,
+            custom: false,
+            created_at: None,
+        }
+    }
+}
+
+impl From<custom_currency::Model> for CurrencyResponse {
+    fn from(model: custom_currency::Model) -> Self {
+        Self {
+            code: model.code,
+            name: model.name,
+            minor_units: model.minor_units as u32,
+            custom: true,
+            created_at: Some(model.created_at.with_timezone(&Utc)),
+        }
+    }
+}
+
+fn iso_currency(code: &str) -> Option<&'static IsoCurrency> {
+    CURRENT.iter().find(|c| c.code == code)
+}
+
+/// The currency codes amounts may be recorded in: ISO 4217 plus custom codes
+/// (crypto, loyalty points) with their minor units.
+#[derive(Debug, Clone, Default)]
+pub struct Currencies {
+    custom: HashMap<String, u32>,
+    historic: bool,
+}
+
+impl Currencies {
+    /// ISO 4217 codes and the user's own custom codes.
+    pub async fn load<C: ConnectionTrait>(db: &C, user_id: Uuid) -> Result<Self, ServiceError> {
+        let custom = CustomCurrency::find()
+            .filter(custom_currency::Column::UserId.eq(user_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|c| (c.code, c.minor_units as u32))
+            .collect();
+        Ok(Self {
+            custom,
+            historic: false,
+        })
+    }
+
+    /// Every code an FX rate may be quoted in. Rates are shared, so this
+    /// takes withdrawn ISO codes and any user's custom codes.
+    pub async fn load_for_rates<C: ConnectionTrait>(db: &C) -> Result<Self, ServiceError> {
+        let mut custom: HashMap<String, u32> = HashMap::new();
+        for model in CustomCurrency::find().all(db).await? {
+            let minor_units = custom.entry(model.code).or_default();
+            *minor_units = (*minor_units).max(model.minor_units as u32);
+        }
+        Ok(Self {
+            custom,
+            historic: true,
+        })
+    }
+
+    /// Trims and uppercases `code`, rejecting codes that are not known.
+    pub fn normalize(&self, code: &str) -> Result<String, ServiceError> {
+        let code = code.trim().to_uppercase();
+        if code.is_empty() {
+            return Err(ServiceError::Validation(
+                "Currency code cannot be empty".to_string(),
+            ));
+        }
+        let known = iso_currency(&code).is_some()
+            || self.custom.contains_key(&code)
+            || (self.historic && HISTORIC.contains(&code.as_str()));
+        if !known {
+            return Err(ServiceError::Validation(format!(
+                "Unknown currency code: {}",
+                code
+            )));
+        }
+        Ok(code)
+    }
+
+    pub fn minor_units(&self, code: &str) -> u32 {
+        iso_currency(code)
+            .map(|c| c.minor_units)
+            .or_else(|| self.custom.get(code).copied())
+            .unwrap_or(STORAGE_SCALE)
+    }
+
+    /// Rounds `amount` to the minor units of `code`.
+    pub fn round(&self, amount: Decimal, code: &str) -> Decimal {
+        amount.round_dp(self.minor_units(code))
+    }
+}
+
+/// Normalizes a currency code for `user_id`, accepting ISO 4217 codes and
+/// the user's custom codes.
+pub(crate) async fn validate_currency_code<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    code: &str,
+) -> Result<String, ServiceError> {
+    Currencies::load(db, user_id).await?.normalize(code)
+}
+
+fn validate_custom_code(code: &str) -> Result<String, ServiceError> {
+    let code = code.trim().to_uppercase();
+    if code.len() < 2 || code.len() > MAX_CUSTOM_CODE_LEN {
+        return Err(ServiceError::Validation(format!(
+            "Currency code must be 2 to {} characters",
+            MAX_CUSTOM_CODE_LEN
+        )));
+    }
+    if !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(ServiceError::Validation(
+            "Currency code must be alphanumeric".to_string(),
+        ));
+    }
+    if iso_currency(&code).is_some() {
+        return Err(ServiceError::Validation(format!(
+            "{} is already an ISO 4217 currency",
+            code
+        )));
+    }
+    Ok(code)
+}
+
+/// ISO 4217 currencies followed by the user's custom codes.
+pub async fn list_currencies(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<CurrencyResponse>, ServiceError> {
+    let mut custom = CustomCurrency::find()
+        .filter(custom_currency::Column::UserId.eq(user_id))
+        .all(db)
+        .await?;
+    custom.sort_by(|a, b| a.code.cmp(&b.code));
+
+    Ok(CURRENT
+        .iter()
+        .map(CurrencyResponse::from)
+        .chain(custom.into_iter().map(CurrencyResponse::from))
+        .collect())
+}
+
+pub async fn create_currency(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: CreateCurrencyRequest,
+) -> Result<CurrencyResponse, ServiceError> {
+    let code = validate_custom_code(&req.code)?;
+    let name = req.name.trim();
+    if name.is_empty() || name.chars().count() > 64 {
+        return Err(ServiceError::Validation(
+            "Currency name must be 1 to 64 characters".to_string(),
+        ));
+    }
+    let minor_units = req.minor_units.unwrap_or(DEFAULT_MINOR_UNITS);
+    if minor_units > STORAGE_SCALE {
+        return Err(ServiceError::Validation(format!(
+            "minor_units must be between 0 and {}",
+            STORAGE_SCALE
+        )));
+    }
+
+    let existing = CustomCurrency::find()
+        .filter(custom_currency::Column::UserId.eq(user_id))
+        .filter(custom_currency::Column::Code.eq(&code))
+        .one(db)
+        .await?;
+    if existing.is_some() {
+        return Err(ServiceError::Conflict(format!(
+            "Currency {} already exists",
+            code
+        )));
+    }
+
+    let model = custom_currency::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        code: Set(code),
+        name: Set(name.to_string()),
+        minor_units: Set(minor_units as i16),
+        created_at: Set(Utc::now().into()),
+    }
+    .insert(db)
+    .await?;
+
+    Ok(CurrencyResponse::from(model))
+}
+
+/// Removes a custom code. Codes still used by the user's records are kept.
+pub async fn delete_currency(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    code: &str,
+) -> Result<(), ServiceError> {
+    let code = code.trim().to_uppercase();
+    let currency = CustomCurrency::find()
+        .filter(custom_currency::Column::UserId.eq(user_id))
+        .filter(custom_currency::Column::Code.eq(&code))
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound)?;
+
+    let in_use = Account::find()
+        .filter(account::Column::UserId.eq(user_id))
+        .filter(account::Column::CurrencyCode.eq(&code))
+        .count(db)
+        .await?
+        + Transaction::find()
+            .filter(transaction::Column::UserId.eq(user_id))
+            .filter(
+                Condition::any()
+                    .add(transaction::Column::CurrencyCode.eq(&code))
+                    .add(transaction::Column::ToCurrencyCode.eq(&code))
+                    .add(transaction::Column::OriginalCurrencyCode.eq(&code)),
+            )
+            .count(db)
+            .await?
+        + Holdings::find()
+            .filter(holdings::Column::UserId.eq(user_id))
+            .filter(holdings::Column::CurrencyCode.eq(&code))
+            .count(db)
+            .await?
+        + Budget::find()
+            .filter(budget::Column::UserId.eq(user_id))
+            .filter(budget::Column::CurrencyCode.eq(&code))
+            .count(db)
+            .await?
+        + SavingsGoal::find()
+            .filter(savings_goal::Column::UserId.eq(user_id))
+            .filter(savings_goal::Column::CurrencyCode.eq(&code))
+            .count(db)
+            .await?
+        + RecurringTransaction::find()
+            .filter(recurring_transaction::Column::UserId.eq(user_id))
+            .filter(recurring_transaction::Column::CurrencyCode.eq(&code))
+            .count(db)
+            .await?
+        + User::find_by_id(user_id)
+            .filter(user::Column::BaseCurrency.eq(&code))
+            .count(db)
+            .await?;
+    if in_use > 0 {
+        return Err(ServiceError::Conflict(format!(
+            "Currency {} is still in use",
+            code
+        )));
+    }
+
+    CustomCurrency::delete_by_id(currency.id).exec(db).await?;
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+
+use super::{ExportContext, ExportTransaction, ExportWriter};
+
+pub struct CsvWriter;
+
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn row(fields: &[String]) -> String {
+    let mut line = fields.iter().map(|f| escape(f)).collect::<Vec<_>>().join(",");
+    line.push('\n');
+    line
+}
+
+fn opt<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn amount(value: Decimal) -> String {
+    value.normalize().to_string()
+}
+
+fn timestamp(value: &DateTimeWithTimeZone) -> String {
+    value.with_timezone(&Utc).to_rfc3339()
+}
+
+fn account_name(ctx: &ExportContext, id: Option<Uuid>) -> String {
+    opt(id.and_then(|id| ctx.account(id)).map(|a| a.name.clone()))
+}
+
+impl ExportWriter for CsvWriter {
+    fn begin(&mut self, ctx: &ExportContext) -> String {
+        let mut out = String::from("# accounts\n");
+        out.push_str(&row(&[
+            "id".into(),
+            "name".into(),
+            "type".into(),
+            "currency_code".into(),
+            "opening_balance".into(),
+            "balance".into(),
+            "created_at".into(),
+            "deleted_at".into(),
+        ]));
+        for account in &ctx.accounts {
+            out.push_str(&row(&[
+                account.id.to_string(),
+                account.name.clone(),
+                account.r#type.clone(),
+                account.currency_code.clone(),
+                amount(account.opening_balance),
+                amount(account.balance),
+                timestamp(&account.created_at),
+                opt(account.deleted_at.as_ref().map(timestamp)),
+            ]));
+        }
+
+        out.push_str("\n# transactions\n");
+        out.push_str(&row(&[
+            "id".into(),
+            "occurred_at".into(),
+            "txn_type".into(),
+            "from_account_id".into(),
+            "from_account".into(),
+            "to_account_id".into(),
+            "to_account".into(),
+            "amount".into(),
+            "currency_code".into(),
+            "to_amount".into(),
+            "to_currency_code".into(),
+            "category".into(),
+            "merchant".into(),
+            "note".into(),
+            "ref_transaction_id".into(),
+            "external_id".into(),
+            "splits".into(),
+            "tags".into(),
+        ]));
+        out
+    }
+
+    fn transactions(&mut self, ctx: &ExportContext, rows: &[ExportTransaction]) -> String {
+        let mut out = String::new();
+        for ExportTransaction {
+            txn,
+            splits,
+            category,
+            tags,
+            ..
+        } in rows
+        {
+            let splits = splits
+                .iter()
+                .map(|s| {
+                    let category = ctx.category(Some(s.category_id)).unwrap_or_default();
+                    format!("{}={}", category, amount(s.amount))
+                })
+                .collect::<Vec<_>>()
+                .join("|");
+
+            out.push_str(&row(&[
+                txn.id.to_string(),
+                timestamp(&txn.occurred_at),
+                txn.txn_type.clone(),
+                opt(txn.from_account_id),
+                account_name(ctx, txn.from_account_id),
+                opt(txn.to_account_id),
+                account_name(ctx, txn.to_account_id),
+                amount(txn.amount),
+                txn.currency_code.clone(),
+                opt(txn.to_amount.map(amount)),
+                opt(txn.to_currency_code.clone()),
+                opt(category.clone()),
+                opt(txn.merchant.clone()),
+                opt(txn.note.clone()),
+                opt(txn.ref_transaction_id),
+                opt(txn.external_id.clone()),
+                splits,
+                tags.join("|"),
+            ]));
+        }
+        out
+    }
+
+    fn finish(&mut self, ctx: &ExportContext) -> String {
+        let mut out = String::from("\n# holdings\n");
+        out.push_str(&row(&[
+            "id".into(),
+            "account_id".into(),
+            "account".into(),
+            "asset_type".into(),
+            "symbol".into(),
+            "name".into(),
+            "quantity".into(),
+            "currency_code".into(),
+            "cost_basis_total".into(),
+            "last_price".into(),
+            "last_price_at".into(),
+            "market_value".into(),
+        ]));
+        for holding in &ctx.holdings {
+            out.push_str(&row(&[
+                holding.id.to_string(),
+                holding.account_id.to_string(),
+                account_name(ctx, Some(holding.account_id)),
+                holding.asset_type.clone(),
+                holding.symbol.clone(),
+                opt(holding.name.clone()),
+                amount(holding.quantity),
+                holding.currency_code.clone(),
+                amount(holding.cost_basis_total),
+                opt(holding.last_price.map(amount)),
+                opt(holding.last_price_at.as_ref().map(timestamp)),
+                opt(holding.market_value.map(amount)),
+            ]));
+        }
+        out
+    }
+}
@@ -0,0 +1,53 @@
+use serde::Serialize;
+
+use super::{ExportContext, ExportTransaction, ExportWriter};
+use crate::services::account::AccountResponse;
+use crate::services::holdings::HoldingsResponse;
+use crate::services::transaction::TransactionResponse;
+
+#[derive(Default)]
+pub struct JsonWriter {
+    wrote_transaction: bool,
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("export records always serialize")
+}
+
+fn json_array<T: Serialize>(items: impl Iterator<Item = T>) -> String {
+    let items: Vec<String> = items.map(|item| to_json(&item)).collect();
+    format!("[{}]", items.join(","))
+}
+
+impl ExportWriter for JsonWriter {
+    fn begin(&mut self, ctx: &ExportContext) -> String {
+        format!(
+            "{{\"generated_at\":{},\"accounts\":{},\"transactions\":[",
+            to_json(&ctx.generated_at),
+            json_array(ctx.accounts.iter().cloned().map(AccountResponse::from)),
+        )
+    }
+
+    fn transactions(&mut self, ctx: &ExportContext, rows: &[ExportTransaction]) -> String {
+        let mut out = String::new();
+        for row in rows {
+            if self.wrote_transaction {
+                out.push(',');
+            }
+            self.wrote_transaction = true;
+
+            let mut response =
+                TransactionResponse::new(row.txn.clone(), row.splits.clone(), &ctx.categories);
+            response.tags = row.tags.clone();
+            out.push_str(&to_json(&response));
+        }
+        out
+    }
+
+    fn finish(&mut self, ctx: &ExportContext) -> String {
+        format!(
+            "],\"holdings\":{}}}",
+            json_array(ctx.holdings.iter().cloned().map(HoldingsResponse::from)),
+        )
+    }
+}
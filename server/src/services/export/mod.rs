@@ -0,0 +1,253 @@
+pub mod csv;
+pub mod json;
+pub mod plaintext;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sea_orm::{
+    AccessMode, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    IsolationLevel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::entities::{account, holdings, prelude::*, transaction, transaction_split};
+use crate::errors::ServiceError;
+use crate::services::category::CategoryTree;
+use crate::services::tag::load_tags;
+use crate::services::transaction::load_splits;
+
+const PAGE_SIZE: u64 = 500;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Beancount,
+    Ledger,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Beancount | ExportFormat::Ledger => "text/plain; charset=utf-8",
+        }
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Beancount => "beancount",
+            ExportFormat::Ledger => "ledger",
+        }
+    }
+
+    fn writer(&self) -> Box<dyn ExportWriter> {
+        match self {
+            ExportFormat::Csv => Box::new(csv::CsvWriter),
+            ExportFormat::Json => Box::new(json::JsonWriter::default()),
+            ExportFormat::Beancount => Box::new(plaintext::PlainTextWriter::new(
+                plaintext::Dialect::Beancount,
+            )),
+            ExportFormat::Ledger => {
+                Box::new(plaintext::PlainTextWriter::new(plaintext::Dialect::Ledger))
+            }
+        }
+    }
+}
+
+pub struct ExportContext {
+    pub accounts: Vec<account::Model>,
+    pub holdings: Vec<holdings::Model>,
+    pub categories: CategoryTree,
+    pub start_date: NaiveDate,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl ExportContext {
+    pub fn account(&self, id: Uuid) -> Option<&account::Model> {
+        self.accounts.iter().find(|a| a.id == id)
+    }
+
+    pub fn category(&self, id: Option<Uuid>) -> Option<String> {
+        id.and_then(|id| self.categories.path(id))
+    }
+}
+
+pub struct ExportTransaction {
+    pub txn: transaction::Model,
+    pub splits: Vec<transaction_split::Model>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub ref_category: Option<String>,
+}
+
+pub trait ExportWriter: Send {
+    fn begin(&mut self, ctx: &ExportContext) -> String;
+    fn transactions(&mut self, ctx: &ExportContext, rows: &[ExportTransaction]) -> String;
+    fn finish(&mut self, ctx: &ExportContext) -> String;
+}
+
+async fn load_context(
+    db: &DatabaseTransaction,
+    user_id: Uuid,
+) -> Result<ExportContext, ServiceError> {
+    let accounts = Account::find()
+        .filter(account::Column::UserId.eq(user_id))
+        .order_by_asc(account::Column::CreatedAt)
+        .order_by_asc(account::Column::Id)
+        .all(db)
+        .await?;
+
+    let holdings = Holdings::find()
+        .filter(holdings::Column::UserId.eq(user_id))
+        .order_by_asc(holdings::Column::AccountId)
+        .order_by_asc(holdings::Column::Symbol)
+        .all(db)
+        .await?;
+
+    let categories = CategoryTree::load(db, user_id).await?;
+
+    let first_txn: Option<DateTime<Utc>> = Transaction::find()
+        .select_only()
+        .column_as(transaction::Column::OccurredAt.min(), "first")
+        .filter(transaction::Column::UserId.eq(user_id))
+        .into_tuple::<Option<chrono::DateTime<chrono::FixedOffset>>>()
+        .one(db)
+        .await?
+        .flatten()
+        .map(|dt| dt.with_timezone(&Utc));
+
+    let first_account = accounts.iter().map(|a| a.created_at.with_timezone(&Utc)).min();
+    let generated_at = Utc::now();
+    let start_date = [first_txn, first_account]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(generated_at)
+        .date_naive();
+
+    Ok(ExportContext {
+        accounts,
+        holdings,
+        categories,
+        start_date,
+        generated_at,
+    })
+}
+
+async fn load_page(
+    db: &DatabaseTransaction,
+    ctx: &ExportContext,
+    user_id: Uuid,
+    page: u64,
+) -> Result<Vec<ExportTransaction>, ServiceError> {
+    let txns = Transaction::find()
+        .filter(transaction::Column::UserId.eq(user_id))
+        .order_by_asc(transaction::Column::OccurredAt)
+        .order_by_asc(transaction::Column::CreatedAt)
+        .order_by_asc(transaction::Column::Id)
+        .paginate(db, PAGE_SIZE)
+        .fetch_page(page)
+        .await?;
+
+    let ids: Vec<Uuid> = txns.iter().map(|t| t.id).collect();
+    let mut splits = load_splits(db, ids.clone()).await?;
+    let mut tags = load_tags(db, ids).await?;
+
+    let ref_ids: Vec<Uuid> = txns.iter().filter_map(|t| t.ref_transaction_id).collect();
+    let ref_categories: HashMap<Uuid, Option<Uuid>> = if ref_ids.is_empty() {
+        HashMap::new()
+    } else {
+        Transaction::find()
+            .select_only()
+            .column(transaction::Column::Id)
+            .column(transaction::Column::CategoryId)
+            .filter(transaction::Column::Id.is_in(ref_ids))
+            .into_tuple::<(Uuid, Option<Uuid>)>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect()
+    };
+
+    Ok(txns
+        .into_iter()
+        .map(|txn| ExportTransaction {
+            splits: splits.remove(&txn.id).unwrap_or_default(),
+            category: ctx.category(txn.category_id),
+            tags: tags.remove(&txn.id).unwrap_or_default(),
+            ref_category: ctx.category(
+                txn.ref_transaction_id
+                    .and_then(|id| ref_categories.get(&id).copied().flatten()),
+            ),
+            txn,
+        })
+        .collect())
+}
+
+async fn emit(tx: &mpsc::Sender<Result<String, std::io::Error>>, chunk: String) -> bool {
+    chunk.is_empty() || tx.send(Ok(chunk)).await.is_ok()
+}
+
+async fn produce(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    format: ExportFormat,
+    tx: &mpsc::Sender<Result<String, std::io::Error>>,
+) -> Result<(), ServiceError> {
+    let snapshot = db
+        .begin_with_config(Some(IsolationLevel::RepeatableRead), Some(AccessMode::ReadOnly))
+        .await?;
+
+    let ctx = load_context(&snapshot, user_id).await?;
+    let mut writer = format.writer();
+
+    if !emit(tx, writer.begin(&ctx)).await {
+        return Ok(());
+    }
+
+    let mut page = 0;
+    loop {
+        let rows = load_page(&snapshot, &ctx, user_id, page).await?;
+        if rows.is_empty() {
+            break;
+        }
+        if !emit(tx, writer.transactions(&ctx, &rows)).await {
+            return Ok(());
+        }
+        if (rows.len() as u64) < PAGE_SIZE {
+            break;
+        }
+        page += 1;
+    }
+
+    emit(tx, writer.finish(&ctx)).await;
+    snapshot.commit().await?;
+
+    Ok(())
+}
+
+pub fn export_stream(
+    db: DatabaseConnection,
+    user_id: Uuid,
+    format: ExportFormat,
+) -> ReceiverStream<Result<String, std::io::Error>> {
+    let (tx, rx) = mpsc::channel(4);
+
+    tokio::spawn(async move {
+        if let Err(e) = produce(&db, user_id, format, &tx).await {
+            error!(error = %e, user_id = %user_id, "export failed");
+            let _ = tx.send(Err(std::io::Error::other(e.to_string()))).await;
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
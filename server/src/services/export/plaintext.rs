@@ -0,0 +1,437 @@
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use uuid::Uuid;
+
+use super::{ExportContext, ExportTransaction, ExportWriter};
+use crate::entities::transaction;
+use crate::services::account::LIABILITY_TYPES;
+
+const OPENING_BALANCES: &str = "Equity:Opening-Balances";
+const ADJUSTMENTS: &str = "Equity:Adjustments";
+const TRANSFER_FEES: &str = "Expenses:Transfer-Fees";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Beancount,
+    Ledger,
+}
+
+struct Posting {
+    account: String,
+    amount: Decimal,
+    currency: String,
+    total_price: Option<(Decimal, String)>,
+}
+
+impl Posting {
+    fn new(account: String, amount: Decimal, currency: &str) -> Self {
+        Self {
+            account,
+            amount,
+            currency: currency.to_string(),
+            total_price: None,
+        }
+    }
+}
+
+pub struct PlainTextWriter {
+    dialect: Dialect,
+    account_names: HashMap<Uuid, String>,
+    used_accounts: BTreeSet<String>,
+}
+
+impl PlainTextWriter {
+    pub fn new(dialect: Dialect) -> Self {
+        Self {
+            dialect,
+            account_names: HashMap::new(),
+            used_accounts: BTreeSet::new(),
+        }
+    }
+
+    fn component(&self, raw: &str) -> Option<String> {
+        let name = match self.dialect {
+            Dialect::Beancount => raw
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(|word| {
+                    let mut chars = word.chars();
+                    let first = chars.next().map(|c| c.to_ascii_uppercase());
+                    first.into_iter().chain(chars).collect::<String>()
+                })
+                .collect::<String>(),
+            Dialect::Ledger => raw
+                .replace(':', "-")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+
+        (!name.is_empty()).then_some(name)
+    }
+
+    fn category_account(&mut self, root: &str, category: Option<&str>) -> String {
+        let parts: Vec<String> = category
+            .unwrap_or_default()
+            .split([':', '/'])
+            .filter_map(|part| self.component(part))
+            .collect();
+
+        let name = if parts.is_empty() {
+            format!("{}:Uncategorized", root)
+        } else {
+            format!("{}:{}", root, parts.join(":"))
+        };
+        self.used_accounts.insert(name.clone());
+        name
+    }
+
+    fn named_account(&mut self, name: &str) -> String {
+        self.used_accounts.insert(name.to_string());
+        name.to_string()
+    }
+
+    fn account_name(&self, id: Uuid) -> String {
+        self.account_names
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| format!("Assets:Unknown:{}", self.short_id(id)))
+    }
+
+    fn short_id(&self, id: Uuid) -> String {
+        let short = id.simple().to_string()[..8].to_string();
+        match self.dialect {
+            Dialect::Beancount => format!("A{}", short.to_uppercase()),
+            Dialect::Ledger => short,
+        }
+    }
+
+    fn date(&self, date: NaiveDate) -> String {
+        match self.dialect {
+            Dialect::Beancount => date.format("%Y-%m-%d").to_string(),
+            Dialect::Ledger => date.format("%Y/%m/%d").to_string(),
+        }
+    }
+
+    fn quote(&self, text: &str) -> String {
+        let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        match self.dialect {
+            Dialect::Beancount => {
+                format!("\"{}\"", flat.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+            Dialect::Ledger => flat,
+        }
+    }
+
+    fn header(&self, date: NaiveDate, payee: Option<&str>, narration: &str) -> String {
+        match (self.dialect, payee) {
+            (Dialect::Beancount, Some(payee)) => format!(
+                "{} * {} {}\n",
+                self.date(date),
+                self.quote(payee),
+                self.quote(narration)
+            ),
+            (Dialect::Beancount, None) => {
+                format!("{} * {}\n", self.date(date), self.quote(narration))
+            }
+            (Dialect::Ledger, Some(payee)) => {
+                let mut out = format!("{} * {}\n", self.date(date), self.quote(payee));
+                if !narration.is_empty() {
+                    out.push_str(&format!("  ; {}\n", self.quote(narration)));
+                }
+                out
+            }
+            (Dialect::Ledger, None) => format!("{} * {}\n", self.date(date), self.quote(narration)),
+        }
+    }
+
+    fn metadata(&self, key: &str, value: &str) -> String {
+        match self.dialect {
+            Dialect::Beancount => format!("  {}: {}\n", key, self.quote(value)),
+            Dialect::Ledger => format!("  ; {}: {}\n", key, self.quote(value)),
+        }
+    }
+
+    fn posting(&self, posting: &Posting) -> String {
+        let mut line = format!(
+            "  {}  {} {}",
+            posting.account,
+            posting.amount.normalize(),
+            posting.currency
+        );
+        if let Some((price, currency)) = &posting.total_price {
+            line.push_str(&format!(" @@ {} {}", price.normalize(), currency));
+        }
+        line.push('\n');
+        line
+    }
+
+    fn commodity(&self, symbol: &str) -> Option<String> {
+        match self.dialect {
+            Dialect::Beancount => {
+                let symbol = symbol.to_uppercase();
+                let valid = symbol.len() <= 24
+                    && symbol.starts_with(|c: char| c.is_ascii_uppercase())
+                    && symbol.ends_with(|c: char| c.is_ascii_alphanumeric())
+                    && symbol
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || "'._-".contains(c));
+                valid.then_some(symbol)
+            }
+            Dialect::Ledger => Some(format!("\"{}\"", symbol.replace('"', ""))),
+        }
+    }
+
+    fn postings(&mut self, ctx: &ExportContext, row: &ExportTransaction) -> Vec<Posting> {
+        let txn = &row.txn;
+        let currency = txn.currency_code.as_str();
+
+        if txn.txn_type == "transfer" {
+            return self.transfer_postings(txn);
+        }
+
+        let mut postings = Vec::new();
+        if let Some(from) = txn.from_account_id {
+            postings.push(Posting::new(self.account_name(from), -txn.amount, currency));
+        }
+        if let Some(to) = txn.to_account_id {
+            postings.push(Posting::new(self.account_name(to), txn.amount, currency));
+        }
+        let net: Decimal = postings.iter().map(|p| p.amount).sum();
+        let sign = if net > Decimal::ZERO {
+            Decimal::NEGATIVE_ONE
+        } else {
+            Decimal::ONE
+        };
+
+        let root = match txn.txn_type.as_str() {
+            "income" => "Income",
+            "adjustment" => {
+                let account = self.named_account(ADJUSTMENTS);
+                postings.push(Posting::new(account, -net, currency));
+                return postings;
+            }
+            _ => "Expenses",
+        };
+        let fallback = if txn.txn_type == "refund" {
+            row.ref_category.as_deref().or(row.category.as_deref())
+        } else {
+            row.category.as_deref()
+        };
+
+        if row.splits.is_empty() {
+            let account = self.category_account(root, fallback);
+            postings.push(Posting::new(account, -net, currency));
+        } else {
+            for split in &row.splits {
+                let category = ctx.category(Some(split.category_id));
+                let account = self.category_account(root, category.as_deref());
+                postings.push(Posting::new(account, split.amount * sign, currency));
+            }
+        }
+
+        postings
+    }
+
+    fn transfer_postings(&mut self, txn: &transaction::Model) -> Vec<Posting> {
+        let currency = txn.currency_code.as_str();
+        let mut postings = Vec::new();
+
+        if let Some(from) = txn.from_account_id {
+            postings.push(Posting::new(self.account_name(from), -txn.amount, currency));
+        }
+
+        let Some(to) = txn.to_account_id else {
+            return postings;
+        };
+        let to_name = self.account_name(to);
+
+        match (&txn.to_currency_code, txn.to_amount) {
+            (Some(to_currency), Some(to_amount)) if to_currency != currency => {
+                let mut posting = Posting::new(to_name, to_amount, to_currency);
+                posting.total_price = Some((txn.amount, currency.to_string()));
+                postings.push(posting);
+            }
+            _ => {
+                let credited = txn.to_amount.unwrap_or(txn.amount);
+                postings.push(Posting::new(to_name, credited, currency));
+                if credited != txn.amount {
+                    let fees = self.named_account(TRANSFER_FEES);
+                    postings.push(Posting::new(fees, txn.amount - credited, currency));
+                }
+            }
+        }
+
+        postings
+    }
+}
+
+impl ExportWriter for PlainTextWriter {
+    fn begin(&mut self, ctx: &ExportContext) -> String {
+        let mut out = format!(
+            "; Life OS export generated at {}\n\n",
+            ctx.generated_at.to_rfc3339()
+        );
+        if self.dialect == Dialect::Beancount {
+            out.push_str("option \"title\" \"Life OS\"\n\n");
+        }
+
+        let mut taken = HashSet::new();
+        for account in &ctx.accounts {
+            let root = if LIABILITY_TYPES.contains(&account.r#type.as_str()) {
+                "Liabilities"
+            } else {
+                "Assets"
+            };
+            let base = self
+                .component(&account.name)
+                .unwrap_or_else(|| "Account".to_string());
+            let mut name = format!("{}:{}", root, base);
+            if !taken.insert(name.clone()) {
+                name = format!("{}:{}-{}", root, base, self.short_id(account.id));
+                taken.insert(name.clone());
+            }
+            self.account_names.insert(account.id, name);
+        }
+
+        for account in &ctx.accounts {
+            let name = &self.account_names[&account.id];
+            match self.dialect {
+                Dialect::Beancount => {
+                    out.push_str(&format!("{} open {}\n", self.date(ctx.start_date), name));
+                    out.push_str(&self.metadata("id", &account.id.to_string()));
+                }
+                Dialect::Ledger => {
+                    out.push_str(&format!("account {}\n", name));
+                    out.push_str(&format!("  ; id: {}\n", account.id));
+                }
+            }
+        }
+        out.push('\n');
+
+        for account in &ctx.accounts {
+            if account.opening_balance.is_zero() {
+                continue;
+            }
+            let equity = self.named_account(OPENING_BALANCES);
+            out.push_str(&self.header(ctx.start_date, None, "Opening balance"));
+            out.push_str(&self.posting(&Posting::new(
+                self.account_names[&account.id].clone(),
+                account.opening_balance,
+                &account.currency_code,
+            )));
+            out.push_str(&self.posting(&Posting::new(
+                equity,
+                -account.opening_balance,
+                &account.currency_code,
+            )));
+            out.push('\n');
+        }
+
+        out
+    }
+
+    fn transactions(&mut self, ctx: &ExportContext, rows: &[ExportTransaction]) -> String {
+        let mut out = String::new();
+        for row in rows {
+            let txn = &row.txn;
+            let narration = txn
+                .note
+                .clone()
+                .or_else(|| row.category.clone())
+                .unwrap_or_else(|| txn.txn_type.clone());
+
+            out.push_str(&self.header(
+                txn.occurred_at.with_timezone(&Utc).date_naive(),
+                txn.merchant.as_deref(),
+                &narration,
+            ));
+            out.push_str(&self.metadata("id", &txn.id.to_string()));
+            out.push_str(&self.metadata("type", &txn.txn_type));
+            if let Some(ref_id) = txn.ref_transaction_id {
+                out.push_str(&self.metadata("ref", &ref_id.to_string()));
+            }
+            if let Some(external_id) = &txn.external_id {
+                out.push_str(&self.metadata("external_id", external_id));
+            }
+            if let Some(category) = &row.category {
+                out.push_str(&self.metadata("category", category));
+            }
+            if !row.tags.is_empty() {
+                out.push_str(&self.metadata("tags", &row.tags.join(", ")));
+            }
+
+            for posting in self.postings(ctx, row) {
+                out.push_str(&self.posting(&posting));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn finish(&mut self, ctx: &ExportContext) -> String {
+        let mut out = String::new();
+
+        for holding in &ctx.holdings {
+            let date = holding.updated_at.with_timezone(&Utc).date_naive();
+            let account = self.account_name(holding.account_id);
+            let commodity = self.commodity(&holding.symbol);
+
+            if let (Some(price), Some(commodity)) = (holding.last_price, &commodity) {
+                let price_date = holding
+                    .last_price_at
+                    .map(|dt| dt.with_timezone(&Utc).date_naive())
+                    .unwrap_or(date);
+                match self.dialect {
+                    Dialect::Beancount => out.push_str(&format!(
+                        "{} price {} {} {}\n",
+                        self.date(price_date),
+                        commodity,
+                        price.normalize(),
+                        holding.currency_code
+                    )),
+                    Dialect::Ledger => out.push_str(&format!(
+                        "P {} {} {} {}\n",
+                        self.date(price_date),
+                        commodity,
+                        price.normalize(),
+                        holding.currency_code
+                    )),
+                }
+            }
+
+            let market_value = holding
+                .market_value
+                .map(|v| format!(" {} {}", v.normalize(), holding.currency_code))
+                .unwrap_or_default();
+            match self.dialect {
+                Dialect::Beancount => out.push_str(&format!(
+                    "{} custom \"holding\" {} {} {}{}\n",
+                    self.date(date),
+                    account,
+                    self.quote(&holding.symbol),
+                    self.quote(&holding.quantity.normalize().to_string()),
+                    market_value
+                )),
+                Dialect::Ledger => out.push_str(&format!(
+                    "; holding {} {} {} ({}){}\n",
+                    account,
+                    holding.symbol,
+                    holding.quantity.normalize(),
+                    holding.asset_type,
+                    market_value
+                )),
+            }
+        }
+
+        if self.dialect == Dialect::Beancount && !self.used_accounts.is_empty() {
+            out.push('\n');
+            for account in &self.used_accounts {
+                out.push_str(&format!("{} open {}\n", self.date(ctx.start_date), account));
+            }
+        }
+
+        out
+    }
+}
@@ -0,0 +1,323 @@
+use chrono::{Days, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbBackend, EntityTrait, FromQueryResult, QueryFilter,
+    QueryOrder, Statement,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::entities::{account, prelude::*};
+use crate::errors::ServiceError;
+use crate::services::account::LIABILITY_TYPES;
+use crate::services::category::CategoryTree;
+use crate::services::notify::Notifier;
+use crate::services::recurring::scheduled_between;
+use crate::services::report::start_of_day;
+
+const DEFAULT_FORECAST_DAYS: u64 = 30;
+const MAX_FORECAST_DAYS: u64 = 365;
+
+const DEFAULT_HISTORY_DAYS: u64 = 90;
+const MAX_HISTORY_DAYS: u64 = 730;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ForecastQuery {
+    pub days: Option<u64>,
+    pub history_days: Option<u64>,
+    pub account_id: Option<Uuid>,
+    pub notify: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScheduledItem {
+    pub date: NaiveDate,
+    pub recurring_id: Uuid,
+    pub name: String,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategorySpending {
+    pub category_id: Option<Uuid>,
+    pub category: Option<String>,
+    pub daily_average: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ForecastPoint {
+    pub date: NaiveDate,
+    pub balance: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountForecast {
+    pub account_id: Uuid,
+    pub name: String,
+    pub currency_code: String,
+    pub current_balance: Decimal,
+    pub projected_balance: Decimal,
+    pub lowest_balance: Decimal,
+    pub lowest_date: NaiveDate,
+    pub negative_on: Option<NaiveDate>,
+    pub scheduled: Vec<ScheduledItem>,
+    pub spending: Vec<CategorySpending>,
+    pub points: Vec<ForecastPoint>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ForecastWarning {
+    pub account_id: Uuid,
+    pub name: String,
+    pub currency_code: String,
+    pub date: NaiveDate,
+    pub balance: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ForecastResponse {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub history_start: NaiveDate,
+    pub accounts: Vec<AccountForecast>,
+    pub warnings: Vec<ForecastWarning>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct SpendingRow {
+    account_id: Uuid,
+    category_id: Option<Uuid>,
+    amount: Decimal,
+}
+
+fn validate_days(
+    value: Option<u64>,
+    default: u64,
+    max: u64,
+    field: &str,
+) -> Result<u64, ServiceError> {
+    let days = value.unwrap_or(default);
+    if days == 0 || days > max {
+        return Err(ServiceError::Validation(format!(
+            "{} must be between 1 and {}",
+            field, max
+        )));
+    }
+    Ok(days)
+}
+
+/// Expense totals per account and category over `[start, end)`, in the
+/// account's currency. Splits count under their own category; transactions
+/// posted from recurring templates are left out, since the schedule already
+/// projects them.
+async fn historical_spending(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<SpendingRow>, ServiceError> {
+    let sql = r#"SELECT t.from_account_id AS account_id,
+                        COALESCE(s.category_id, t.category_id) AS category_id,
+                        SUM(COALESCE(s.amount, t.amount)) AS amount
+                 FROM "transaction" t
+                 JOIN account a ON a.id = t.from_account_id AND a.currency_code = t.currency_code
+                 LEFT JOIN transaction_split s ON s.transaction_id = t.id
+                 WHERE t.user_id = $1 AND t.txn_type = 'expense'
+                   AND t.occurred_at >= $2 AND t.occurred_at < $3
+                   AND NOT EXISTS (
+                       SELECT 1 FROM recurring_occurrence o WHERE o.transaction_id = t.id
+                   )
+                 GROUP BY 1, 2"#;
+
+    let rows = SpendingRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        [
+            user_id.into(),
+            start_of_day(start).into(),
+            start_of_day(end).into(),
+        ],
+    ))
+    .all(db)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Projects each account's end-of-day balance from today through `days`
+/// ahead. Scheduled recurring transactions land on their posting date, and
+/// every day after today is charged the account's average daily expense per
+/// category over the last `history_days`. Asset accounts projected to drop
+/// below zero are reported as warnings and, with `notify`, sent through the
+/// notifier.
+pub async fn get_forecast(
+    db: &DatabaseConnection,
+    notifier: &dyn Notifier,
+    user_id: Uuid,
+    query: ForecastQuery,
+) -> Result<ForecastResponse, ServiceError> {
+    let days = validate_days(query.days, DEFAULT_FORECAST_DAYS, MAX_FORECAST_DAYS, "days")?;
+    let history_days = validate_days(
+        query.history_days,
+        DEFAULT_HISTORY_DAYS,
+        MAX_HISTORY_DAYS,
+        "history_days",
+    )?;
+
+    let today = Utc::now().date_naive();
+    let out_of_range = || ServiceError::Validation("Forecast range out of range".to_string());
+    let end = today
+        .checked_add_days(Days::new(days))
+        .ok_or_else(out_of_range)?;
+    let history_start = today
+        .checked_sub_days(Days::new(history_days))
+        .ok_or_else(out_of_range)?;
+
+    let mut select = Account::find()
+        .filter(account::Column::UserId.eq(user_id))
+        .filter(account::Column::DeletedAt.is_null());
+    if let Some(account_id) = query.account_id {
+        select = select.filter(account::Column::Id.eq(account_id));
+    }
+    let accounts = select
+        .order_by_asc(account::Column::CreatedAt)
+        .all(db)
+        .await?;
+    if query.account_id.is_some() && accounts.is_empty() {
+        return Err(ServiceError::NotFound);
+    }
+
+    let mut scheduled: HashMap<Uuid, Vec<ScheduledItem>> = HashMap::new();
+    for (template, date) in scheduled_between(db, user_id, today, end).await? {
+        let mut post = |account_id: Uuid, amount: Decimal| {
+            scheduled
+                .entry(account_id)
+                .or_default()
+                .push(ScheduledItem {
+                    date,
+                    recurring_id: template.id,
+                    name: template.name.clone(),
+                    amount,
+                });
+        };
+        if let Some(from_id) = template.from_account_id {
+            post(from_id, -template.amount);
+        }
+        if let Some(to_id) = template.to_account_id {
+            post(to_id, template.to_amount.unwrap_or(template.amount));
+        }
+    }
+
+    let categories = CategoryTree::load(db, user_id).await?;
+    let window = Decimal::from(history_days);
+    let mut spending: HashMap<Uuid, Vec<CategorySpending>> = HashMap::new();
+    for row in historical_spending(db, user_id, history_start, today).await? {
+        spending
+            .entry(row.account_id)
+            .or_default()
+            .push(CategorySpending {
+                category_id: row.category_id,
+                category: row.category_id.and_then(|id| categories.path(id)),
+                daily_average: row.amount / window,
+            });
+    }
+
+    let mut forecasts = Vec::with_capacity(accounts.len());
+    let mut warnings = Vec::new();
+    for account in accounts {
+        let items = scheduled.remove(&account.id).unwrap_or_default();
+        let mut categories = spending.remove(&account.id).unwrap_or_default();
+        categories.sort_by_key(|c| std::cmp::Reverse(c.daily_average));
+        let daily_spend: Decimal = categories.iter().map(|c| c.daily_average).sum();
+
+        let mut balance = account.balance;
+        let mut points = Vec::with_capacity(days as usize + 1);
+        let mut pending = items.iter().peekable();
+        let mut date = today;
+        loop {
+            if date > today {
+                balance -= daily_spend;
+            }
+            while let Some(item) = pending.next_if(|item| item.date == date) {
+                balance += item.amount;
+            }
+            points.push(ForecastPoint {
+                date,
+                balance: balance.round_dp(4),
+            });
+            match date.succ_opt() {
+                Some(next) if next <= end => date = next,
+                _ => break,
+            }
+        }
+
+        let lowest = points
+            .iter()
+            .min_by(|a, b| a.balance.cmp(&b.balance).then(b.date.cmp(&a.date)))
+            .map(|p| (p.balance, p.date))
+            .unwrap_or((account.balance, today));
+        let negative_on = if LIABILITY_TYPES.contains(&account.r#type.as_str()) {
+            None
+        } else {
+            points.iter().find(|p| p.balance < Decimal::ZERO)
+        };
+        if let Some(point) = negative_on {
+            warnings.push(ForecastWarning {
+                account_id: account.id,
+                name: account.name.clone(),
+                currency_code: account.currency_code.clone(),
+                date: point.date,
+                balance: point.balance,
+            });
+        }
+
+        forecasts.push(AccountForecast {
+            account_id: account.id,
+            negative_on: negative_on.map(|p| p.date),
+            name: account.name,
+            currency_code: account.currency_code,
+            current_balance: account.balance,
+            projected_balance: balance.round_dp(4),
+            lowest_balance: lowest.0,
+            lowest_date: lowest.1,
+            scheduled: items,
+            spending: categories
+                .into_iter()
+                .map(|c| CategorySpending {
+                    daily_average: c.daily_average.round_dp(4),
+                    ..c
+                })
+                .collect(),
+            points,
+        });
+    }
+
+    if query.notify.unwrap_or(false) && !warnings.is_empty() {
+        let message = warnings
+            .iter()
+            .map(|w| {
+                format!(
+                    "Account '{}' is projected to go negative on {}: {} {}",
+                    w.name,
+                    w.date,
+                    w.balance.round_dp(2),
+                    w.currency_code
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if let Err(e) = notifier.send(&message).await {
+            warn!(user_id = %user_id, error = %e, "Failed to send forecast warning");
+        }
+    }
+
+    Ok(ForecastResponse {
+        start: today,
+        end,
+        history_start,
+        accounts: forecasts,
+        warnings,
+    })
+}
@@ -0,0 +1,250 @@
+use ::csv::{ReaderBuilder, StringRecord, Trim};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use super::{validate_rate, write_rates, UpsertRateRequest};
+use crate::errors::ServiceError;
+use crate::services::currency::Currencies;
+use crate::services::import::LineError;
+
+pub const FORMAT_ECB: &str = "ecb";
+pub const FORMAT_CSV: &str = "csv";
+
+const ECB_BASE_CURRENCY: &str = "EUR";
+
+#[derive(Debug, Deserialize)]
+pub struct IngestRatesRequest {
+    pub format: String,
+    pub content: String,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IngestRatesResponse {
+    pub format: String,
+    pub parsed: usize,
+    pub written: usize,
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    pub errors: Vec<LineError>,
+}
+
+/// A parsed row and the line it came from.
+type ParsedRate = Result<(usize, UpsertRateRequest), LineError>;
+
+fn parse_date(raw: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", raw))
+}
+
+fn parse_rate(raw: &str) -> Result<Decimal, String> {
+    Decimal::from_str(raw.trim()).map_err(|_| format!("Invalid rate '{}'", raw))
+}
+
+/// Value of `name="..."` (or single-quoted) inside a tag's attribute text.
+fn attribute<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    attrs.split_whitespace().find_map(|token| {
+        let value = token
+            .trim_end_matches('/')
+            .strip_prefix(name)?
+            .strip_prefix('=')?;
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        value[1..].strip_suffix(quote)
+    })
+}
+
+/// Parses the ECB reference rate XML (`eurofxref-daily.xml`,
+/// `eurofxref-hist.xml`): dated `<Cube time="..">` elements each holding
+/// `<Cube currency=".." rate=".."/>` quotes against EUR.
+fn parse_ecb(content: &str, source: &str) -> Result<Vec<ParsedRate>, ServiceError> {
+    let mut rates = Vec::new();
+    let mut date: Option<Result<NaiveDate, String>> = None;
+    let mut found = false;
+    let (mut line, mut scanned) = (1, 0);
+
+    for (offset, _) in content.match_indices("<Cube") {
+        line += content[scanned..offset].matches('\n').count();
+        scanned = offset;
+
+        let rest = &content[offset + "<Cube".len()..];
+        if !rest.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            continue;
+        }
+        let attrs = rest.find('>').map(|end| &rest[..end]).ok_or_else(|| {
+            ServiceError::Validation(format!("Unterminated <Cube> tag on line {}", line))
+        })?;
+        found = true;
+
+        if let Some(time) = attribute(attrs, "time") {
+            date = Some(parse_date(time));
+            continue;
+        }
+        let (Some(currency), Some(rate)) = (attribute(attrs, "currency"), attribute(attrs, "rate"))
+        else {
+            continue;
+        };
+
+        let parsed = match &date {
+            None => Err("Rate outside a dated <Cube time=\"..\"> element".to_string()),
+            Some(Err(e)) => Err(e.clone()),
+            Some(Ok(date)) => parse_rate(rate).map(|rate| UpsertRateRequest {
+                rate_date: *date,
+                base_currency: ECB_BASE_CURRENCY.to_string(),
+                quote_currency: currency.to_string(),
+                rate,
+                source: Some(source.to_string()),
+            }),
+        };
+        rates.push(
+            parsed
+                .map(|rate| (line, rate))
+                .map_err(|error| LineError { line, error }),
+        );
+    }
+
+    if !found {
+        return Err(ServiceError::Validation(
+            "No ECB <Cube> elements found".to_string(),
+        ));
+    }
+    Ok(rates)
+}
+
+fn column(headers: &StringRecord, names: &[&str]) -> Option<usize> {
+    headers
+        .iter()
+        .position(|h| names.iter().any(|n| h.eq_ignore_ascii_case(n)))
+}
+
+/// Parses a CSV with a header row naming `date`, `base`, `quote` and `rate`
+/// columns (the `rate_date`, `base_currency` and `quote_currency` spellings
+/// also work). An optional `source` column overrides the import's source.
+fn parse_csv(content: &str, source: &str) -> Result<Vec<ParsedRate>, ServiceError> {
+    let mut reader = ReaderBuilder::new()
+        .flexible(true)
+        .trim(Trim::All)
+        .from_reader(content.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|e| ServiceError::Validation(format!("Invalid CSV header: {}", e)))?
+        .clone();
+
+    let required = |names: &[&str]| {
+        column(&headers, names)
+            .ok_or_else(|| ServiceError::Validation(format!("Missing CSV column: {}", names[0])))
+    };
+    let date_col = required(&["date", "rate_date"])?;
+    let base_col = required(&["base", "base_currency"])?;
+    let quote_col = required(&["quote", "quote_currency"])?;
+    let rate_col = required(&["rate"])?;
+    let source_col = column(&headers, &["source"]);
+
+    let mut rates = Vec::new();
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| p.line() as usize).unwrap_or(0);
+                rates.push(Err(LineError {
+                    line,
+                    error: format!("Invalid CSV row: {}", e),
+                }));
+                continue;
+            }
+        };
+        if record.iter().all(|v| v.is_empty()) {
+            continue;
+        }
+
+        let line = record.position().map(|p| p.line() as usize).unwrap_or(0);
+        let field = |i: usize| record.get(i).unwrap_or_default();
+        let parsed = parse_date(field(date_col)).and_then(|rate_date| {
+            Ok(UpsertRateRequest {
+                rate_date,
+                base_currency: field(base_col).to_string(),
+                quote_currency: field(quote_col).to_string(),
+                rate: parse_rate(field(rate_col))?,
+                source: Some(
+                    source_col
+                        .map(field)
+                        .filter(|s| !s.is_empty())
+                        .unwrap_or(source)
+                        .to_string(),
+                ),
+            })
+        });
+        rates.push(
+            parsed
+                .map(|rate| (line, rate))
+                .map_err(|error| LineError { line, error }),
+        );
+    }
+
+    Ok(rates)
+}
+
+/// Loads rates from an ECB XML or CSV file into the rate store. Rows that
+/// fail to parse or validate are reported and skipped; the rest are
+/// upserted, so loading the same file again changes nothing.
+pub async fn ingest_rates<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    req: IngestRatesRequest,
+) -> Result<IngestRatesResponse, ServiceError> {
+    let format = req.format.trim().to_lowercase();
+    let source = req
+        .source
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| format.clone());
+    let parsed = match format.as_str() {
+        FORMAT_ECB => parse_ecb(&req.content, &source)?,
+        FORMAT_CSV => parse_csv(&req.content, &source)?,
+        _ => {
+            return Err(ServiceError::Validation(format!(
+                "Invalid format: {}. Must be one of: {}, {}",
+                format, FORMAT_ECB, FORMAT_CSV
+            )))
+        }
+    };
+
+    let currencies = Currencies::load_for_rates(db).await?;
+    let mut models = Vec::with_capacity(parsed.len());
+    let mut errors = Vec::new();
+    let (mut start, mut end) = (None::<NaiveDate>, None::<NaiveDate>);
+    for row in parsed {
+        let rate = row.and_then(|(line, rate)| {
+            let date = rate.rate_date;
+            validate_rate(rate, &currencies)
+                .map(|model| (date, model))
+                .map_err(|e| LineError {
+                    line,
+                    error: e.status_and_message().1,
+                })
+        });
+        match rate {
+            Ok((date, model)) => {
+                start = Some(start.map_or(date, |s| s.min(date)));
+                end = Some(end.map_or(date, |e| e.max(date)));
+                models.push(model);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    let parsed = models.len();
+    let tx = db.begin().await?;
+    let written = write_rates(&tx, models).await?;
+    tx.commit().await?;
+
+    Ok(IngestRatesResponse {
+        format,
+        parsed,
+        written,
+        start,
+        end,
+        errors,
+    })
+}
@@ -0,0 +1,582 @@
+pub mod ingest;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, Order, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{fx_rate, prelude::*};
+use crate::errors::ServiceError;
+use crate::services::account::AccountResponse;
+use crate::services::holdings::HoldingsResponse;
+use crate::services::currency::Currencies;
+use crate::services::transaction::TransactionResponse;
+
+/// Currency used to bridge pairs with no direct quote. ECB reference rates
+/// are all quoted against it.
+const PIVOT_CURRENCY: &str = "EUR";
+
+pub(crate) const SOURCE_MANUAL: &str = "manual";
+
+pub(crate) const RATE_SCALE: u32 = 10;
+
+const DEFAULT_RATE_LIMIT: u64 = 100;
+const MAX_RATE_LIMIT: u64 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertRateRequest {
+    pub rate_date: NaiveDate,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: Decimal,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FxRateResponse {
+    pub id: Uuid,
+    pub rate_date: NaiveDate,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: Decimal,
+    pub source: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<fx_rate::Model> for FxRateResponse {
+    fn from(model: fx_rate::Model) -> Self {
+        Self {
+            id: model.id,
+            rate_date: model.rate_date,
+            base_currency: model.base_currency,
+            quote_currency: model.quote_currency,
+            rate: model.rate,
+            source: model.source,
+            updated_at: model.updated_at.with_timezone(&Utc),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct FxRateQuery {
+    pub base_currency: Option<String>,
+    pub quote_currency: Option<String>,
+    pub source: Option<String>,
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConvertQuery {
+    pub amount: Decimal,
+    pub from: String,
+    pub to: String,
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConversionResponse {
+    pub amount: Decimal,
+    pub from: String,
+    pub to: String,
+    pub date: NaiveDate,
+    pub converted: Decimal,
+    pub rate: Decimal,
+    pub rate_date: NaiveDate,
+    pub via: Option<String>,
+}
+
+/// Opt-in flag for list endpoints that can add amounts converted to the
+/// user's base currency.
+#[derive(Debug, Default, Deserialize)]
+pub struct ConvertParams {
+    pub convert: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConvertedAmount {
+    pub currency_code: String,
+    pub amount: Decimal,
+    pub rate: Decimal,
+    pub rate_date: NaiveDate,
+}
+
+/// A response item with its amount converted to the base currency. The
+/// `converted` field is left out when conversion was not requested or no
+/// rate is available.
+#[derive(Debug, Serialize)]
+pub struct Converted<T> {
+    #[serde(flatten)]
+    pub item: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub converted: Option<ConvertedAmount>,
+}
+
+/// The amount a response item carries, its currency and the date it should
+/// be valued at.
+pub trait Convertible {
+    fn money(&self) -> (Decimal, &str, NaiveDate);
+}
+
+impl Convertible for AccountResponse {
+    fn money(&self) -> (Decimal, &str, NaiveDate) {
+        (self.balance, &self.currency_code, Utc::now().date_naive())
+    }
+}
+
+impl Convertible for TransactionResponse {
+    fn money(&self) -> (Decimal, &str, NaiveDate) {
+        (
+            self.amount,
+            &self.currency_code,
+            self.occurred_at.date_naive(),
+        )
+    }
+}
+
+impl Convertible for HoldingsResponse {
+    fn money(&self) -> (Decimal, &str, NaiveDate) {
+        let value = self
+            .market_value
+            .or_else(|| self.last_price.map(|p| (p * self.quantity).round_dp(4)))
+            .unwrap_or(self.cost_basis_total);
+        (value, &self.currency_code, Utc::now().date_naive())
+    }
+}
+
+/// How a cross-currency transfer's implied rate compares with the reference
+/// rate on the day it happened. Amounts are in the destination currency;
+/// `cost` is what the conversion lost against the reference, negative when
+/// it did better.
+#[derive(Debug, Clone, Serialize)]
+pub struct FxCost {
+    pub reference_rate: Decimal,
+    pub reference_date: NaiveDate,
+    pub reference_amount: Decimal,
+    pub cost: Decimal,
+    pub cost_percent: Decimal,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Rate {
+    pub rate: Decimal,
+    pub rate_date: NaiveDate,
+}
+
+/// Looks up conversion rates, caching every pair and date it has resolved.
+/// A pair uses the quote nearest to the requested date in either direction;
+/// pairs with no quote of their own are triangulated through the pivot.
+/// Converted amounts are rounded to the target currency's minor units.
+pub(crate) struct FxConverter {
+    cache: HashMap<(String, String, NaiveDate), Option<(Rate, bool)>>,
+    currencies: Currencies,
+}
+
+impl FxConverter {
+    pub(crate) fn new(currencies: Currencies) -> Self {
+        Self {
+            cache: HashMap::new(),
+            currencies,
+        }
+    }
+
+    async fn direct<C: ConnectionTrait>(
+        db: &C,
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+    ) -> Result<Option<Rate>, ServiceError> {
+        let quote = FxRate::find()
+            .filter(
+                Condition::any()
+                    .add(
+                        Condition::all()
+                            .add(fx_rate::Column::BaseCurrency.eq(from))
+                            .add(fx_rate::Column::QuoteCurrency.eq(to)),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(fx_rate::Column::BaseCurrency.eq(to))
+                            .add(fx_rate::Column::QuoteCurrency.eq(from)),
+                    ),
+            )
+            .order_by(
+                Expr::cust_with_values("ABS(rate_date - $1::date)", [date]),
+                Order::Asc,
+            )
+            .order_by_desc(fx_rate::Column::RateDate)
+            .order_by_desc(fx_rate::Column::UpdatedAt)
+            .one(db)
+            .await?;
+
+        Ok(quote.map(|q| Rate {
+            rate: if q.base_currency == from {
+                q.rate
+            } else {
+                (Decimal::ONE / q.rate).round_dp(RATE_SCALE)
+            },
+            rate_date: q.rate_date,
+        }))
+    }
+
+    /// Rate to multiply `from` amounts by to get `to` amounts, along with
+    /// whether it was triangulated. For triangulated rates `rate_date` is the
+    /// leg furthest from `date`.
+    async fn resolve<C: ConnectionTrait>(
+        &mut self,
+        db: &C,
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+    ) -> Result<Option<(Rate, bool)>, ServiceError> {
+        if from == to {
+            return Ok(Some((
+                Rate {
+                    rate: Decimal::ONE,
+                    rate_date: date,
+                },
+                false,
+            )));
+        }
+        let key = (from.to_string(), to.to_string(), date);
+        if let Some(cached) = self.cache.get(&key) {
+            return Ok(*cached);
+        }
+
+        let mut resolved = Self::direct(db, from, to, date).await?.map(|r| (r, false));
+        if resolved.is_none() && from != PIVOT_CURRENCY && to != PIVOT_CURRENCY {
+            let first = Self::direct(db, from, PIVOT_CURRENCY, date).await?;
+            let second = Self::direct(db, PIVOT_CURRENCY, to, date).await?;
+            if let (Some(first), Some(second)) = (first, second) {
+                let rate_date = [first.rate_date, second.rate_date]
+                    .into_iter()
+                    .max_by_key(|d| (*d - date).num_days().abs())
+                    .unwrap_or(date);
+                resolved = Some((
+                    Rate {
+                        rate: (first.rate * second.rate).round_dp(RATE_SCALE),
+                        rate_date,
+                    },
+                    true,
+                ));
+            }
+        }
+
+        self.cache.insert(key, resolved);
+        Ok(resolved)
+    }
+
+    pub(crate) async fn rate<C: ConnectionTrait>(
+        &mut self,
+        db: &C,
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+    ) -> Result<Option<Rate>, ServiceError> {
+        Ok(self.resolve(db, from, to, date).await?.map(|(r, _)| r))
+    }
+
+    pub(crate) async fn convert<C: ConnectionTrait>(
+        &mut self,
+        db: &C,
+        amount: Decimal,
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+    ) -> Result<Option<ConvertedAmount>, ServiceError> {
+        Ok(self
+            .rate(db, from, to, date)
+            .await?
+            .map(|r| ConvertedAmount {
+                currency_code: to.to_string(),
+                amount: self.currencies.round(amount * r.rate, to),
+                rate: r.rate,
+                rate_date: r.rate_date,
+            }))
+    }
+
+    /// Like `convert`, but a missing rate is a validation error.
+    pub(crate) async fn require<C: ConnectionTrait>(
+        &mut self,
+        db: &C,
+        amount: Decimal,
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+    ) -> Result<Decimal, ServiceError> {
+        self.convert(db, amount, from, to, date)
+            .await?
+            .map(|c| c.amount)
+            .ok_or_else(|| no_rate(from, to, date))
+    }
+
+    /// Prices a conversion of `amount` into `to_amount` against the
+    /// reference rate, or `None` when there is no rate for the pair.
+    pub(crate) async fn cost<C: ConnectionTrait>(
+        &mut self,
+        db: &C,
+        amount: Decimal,
+        from: &str,
+        to_amount: Decimal,
+        to: &str,
+        date: NaiveDate,
+    ) -> Result<Option<FxCost>, ServiceError> {
+        let Some(rate) = self.rate(db, from, to, date).await? else {
+            return Ok(None);
+        };
+        let reference_amount = self.currencies.round(amount * rate.rate, to);
+        let cost = reference_amount - to_amount;
+        Ok(Some(FxCost {
+            reference_rate: rate.rate,
+            reference_date: rate.rate_date,
+            reference_amount,
+            cost,
+            cost_percent: percent_of(cost, reference_amount),
+        }))
+    }
+}
+
+pub(crate) fn percent_of(part: Decimal, whole: Decimal) -> Decimal {
+    if whole.is_zero() {
+        Decimal::ZERO
+    } else {
+        (part * Decimal::ONE_HUNDRED / whole).round_dp(2)
+    }
+}
+
+fn no_rate(from: &str, to: &str, date: NaiveDate) -> ServiceError {
+    ServiceError::Validation(format!("No FX rate from {} to {} near {}", from, to, date))
+}
+
+fn validate_rate(
+    req: UpsertRateRequest,
+    currencies: &Currencies,
+) -> Result<fx_rate::ActiveModel, ServiceError> {
+    let base = currencies.normalize(&req.base_currency)?;
+    let quote = currencies.normalize(&req.quote_currency)?;
+    if base == quote {
+        return Err(ServiceError::Validation(
+            "Base and quote currency must differ".to_string(),
+        ));
+    }
+    if req.rate <= Decimal::ZERO {
+        return Err(ServiceError::Validation(
+            "Rate must be positive".to_string(),
+        ));
+    }
+    let source = req
+        .source
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| SOURCE_MANUAL.to_string());
+    if source.len() > 32 {
+        return Err(ServiceError::Validation(
+            "Source must be at most 32 characters".to_string(),
+        ));
+    }
+
+    let now = Utc::now();
+    Ok(fx_rate::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        rate_date: Set(req.rate_date),
+        base_currency: Set(base),
+        quote_currency: Set(quote),
+        rate: Set(req.rate.round_dp(RATE_SCALE)),
+        source: Set(source),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
+    })
+}
+
+fn upsert_conflict() -> OnConflict {
+    OnConflict::columns([
+        fx_rate::Column::RateDate,
+        fx_rate::Column::BaseCurrency,
+        fx_rate::Column::QuoteCurrency,
+        fx_rate::Column::Source,
+    ])
+    .update_columns([fx_rate::Column::Rate, fx_rate::Column::UpdatedAt])
+    .to_owned()
+}
+
+/// Writes validated rates. An existing row for the same date, pair and
+/// source is only touched when its rate differs, so replaying the same data
+/// writes nothing. Returns the number of rows inserted or changed.
+async fn write_rates<C: ConnectionTrait>(
+    db: &C,
+    models: Vec<fx_rate::ActiveModel>,
+) -> Result<usize, ServiceError> {
+    // Postgres rejects an upsert that touches the same row twice; the last
+    // occurrence of a key wins.
+    let mut unique = HashMap::new();
+    for model in models {
+        let key = (
+            model.rate_date.try_as_ref().copied(),
+            model.base_currency.try_as_ref().cloned(),
+            model.quote_currency.try_as_ref().cloned(),
+            model.source.try_as_ref().cloned(),
+        );
+        unique.insert(key, model);
+    }
+    let models: Vec<fx_rate::ActiveModel> = unique.into_values().collect();
+
+    let mut conflict = upsert_conflict();
+    conflict.action_and_where(
+        Expr::col((FxRate, fx_rate::Column::Rate)).ne(Expr::cust("excluded.rate")),
+    );
+
+    let mut written = 0;
+    for chunk in models.chunks(1000) {
+        written += FxRate::insert_many(chunk.to_vec())
+            .on_conflict(conflict.clone())
+            .exec_without_returning(db)
+            .await?;
+    }
+
+    Ok(written as usize)
+}
+
+/// Inserts rates, replacing any existing rate for the same date, pair and
+/// source. Returns the number of rows inserted or changed.
+pub async fn upsert_rates<C: ConnectionTrait>(
+    db: &C,
+    rates: Vec<UpsertRateRequest>,
+) -> Result<usize, ServiceError> {
+    let currencies = Currencies::load_for_rates(db).await?;
+    let models = rates
+        .into_iter()
+        .map(|rate| validate_rate(rate, &currencies))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    write_rates(db, models).await
+}
+
+pub async fn upsert_rate(
+    db: &DatabaseConnection,
+    req: UpsertRateRequest,
+) -> Result<FxRateResponse, ServiceError> {
+    let currencies = Currencies::load_for_rates(db).await?;
+    let model = FxRate::insert(validate_rate(req, &currencies)?)
+        .on_conflict(upsert_conflict())
+        .exec_with_returning(db)
+        .await?;
+
+    Ok(FxRateResponse::from(model))
+}
+
+pub async fn list_rates(
+    db: &DatabaseConnection,
+    query: FxRateQuery,
+) -> Result<Vec<FxRateResponse>, ServiceError> {
+    let currencies = Currencies::load_for_rates(db).await?;
+    let mut select = FxRate::find();
+    if let Some(base) = query.base_currency {
+        select = select.filter(fx_rate::Column::BaseCurrency.eq(currencies.normalize(&base)?));
+    }
+    if let Some(quote) = query.quote_currency {
+        select = select.filter(fx_rate::Column::QuoteCurrency.eq(currencies.normalize(&quote)?));
+    }
+    if let Some(source) = query.source {
+        select = select.filter(fx_rate::Column::Source.eq(source.trim().to_lowercase()));
+    }
+    if let Some(start) = query.start {
+        select = select.filter(fx_rate::Column::RateDate.gte(start));
+    }
+    if let Some(end) = query.end {
+        select = select.filter(fx_rate::Column::RateDate.lte(end));
+    }
+
+    let rates = select
+        .order_by_desc(fx_rate::Column::RateDate)
+        .order_by_asc(fx_rate::Column::BaseCurrency)
+        .order_by_asc(fx_rate::Column::QuoteCurrency)
+        .limit(
+            query
+                .limit
+                .unwrap_or(DEFAULT_RATE_LIMIT)
+                .min(MAX_RATE_LIMIT),
+        )
+        .all(db)
+        .await?;
+
+    Ok(rates.into_iter().map(FxRateResponse::from).collect())
+}
+
+pub async fn convert_amount(
+    db: &DatabaseConnection,
+    query: ConvertQuery,
+) -> Result<ConversionResponse, ServiceError> {
+    let currencies = Currencies::load_for_rates(db).await?;
+    let from = currencies.normalize(&query.from)?;
+    let to = currencies.normalize(&query.to)?;
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+
+    let (rate, triangulated) = FxConverter::new(currencies.clone())
+        .resolve(db, &from, &to, date)
+        .await?
+        .ok_or_else(|| no_rate(&from, &to, date))?;
+
+    Ok(ConversionResponse {
+        amount: query.amount,
+        converted: currencies.round(query.amount * rate.rate, &to),
+        rate: rate.rate,
+        rate_date: rate.rate_date,
+        via: triangulated.then(|| PIVOT_CURRENCY.to_string()),
+        from,
+        to,
+        date,
+    })
+}
+
+/// The user's base currency, required by any converted output.
+pub(crate) async fn base_currency<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+) -> Result<String, ServiceError> {
+    User::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound)?
+        .base_currency
+        .ok_or_else(|| ServiceError::Validation("Base currency is not set".to_string()))
+}
+
+/// Wraps list items, adding their amount in the user's base currency when
+/// `params.convert` is set. Items without a usable rate are returned as is.
+pub async fn with_conversion<T: Convertible>(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    items: Vec<T>,
+    params: &ConvertParams,
+) -> Result<Vec<Converted<T>>, ServiceError> {
+    if !params.convert.unwrap_or(false) {
+        return Ok(items
+            .into_iter()
+            .map(|item| Converted {
+                item,
+                converted: None,
+            })
+            .collect());
+    }
+
+    let base = base_currency(db, user_id).await?;
+    let mut converter = FxConverter::new(Currencies::load(db, user_id).await?);
+    let mut converted = Vec::with_capacity(items.len());
+    for item in items {
+        let (amount, currency, date) = item.money();
+        let amount = converter.convert(db, amount, currency, &base, date).await?;
+        converted.push(Converted {
+            item,
+            converted: amount,
+        });
+    }
+
+    Ok(converted)
+}
@@ -0,0 +1,530 @@
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{
+    sea_query::{Expr, Func},
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+use crate::entities::{account, prelude::*, savings_goal, savings_goal_account, transaction};
+use crate::errors::ServiceError;
+use crate::services::currency::validate_currency_code;
+use crate::utils::nullable::double_option;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateGoalRequest {
+    pub name: String,
+    pub target_amount: Decimal,
+    pub currency_code: String,
+    pub start_date: Option<NaiveDate>,
+    pub deadline: Option<NaiveDate>,
+    pub account_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateGoalRequest {
+    pub name: Option<String>,
+    pub target_amount: Option<Decimal>,
+    pub start_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub deadline: Option<Option<NaiveDate>>,
+    pub account_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub target_amount: Decimal,
+    pub currency_code: String,
+    pub start_date: NaiveDate,
+    pub deadline: Option<NaiveDate>,
+    pub account_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl GoalResponse {
+    fn new(model: savings_goal::Model, account_ids: Vec<Uuid>) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            target_amount: model.target_amount,
+            currency_code: model.currency_code,
+            start_date: model.start_date,
+            deadline: model.deadline,
+            account_ids,
+            created_at: model.created_at.with_timezone(&Utc),
+            updated_at: model.updated_at.with_timezone(&Utc),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalAccountBalance {
+    pub account_id: Uuid,
+    pub name: String,
+    pub balance: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalProgressResponse {
+    pub goal_id: Uuid,
+    pub name: String,
+    pub currency_code: String,
+    pub target_amount: Decimal,
+    pub current_amount: Decimal,
+    pub remaining: Decimal,
+    pub percent_complete: Decimal,
+    pub completed: bool,
+    pub accounts: Vec<GoalAccountBalance>,
+    pub transferred_in: Decimal,
+    pub transferred_out: Decimal,
+    pub net_contributions: Decimal,
+    pub average_monthly_contribution: Decimal,
+    pub deadline: Option<NaiveDate>,
+    pub months_remaining: Option<u32>,
+    pub required_monthly_contribution: Option<Decimal>,
+    pub projected_completion: Option<NaiveDate>,
+    pub on_track: Option<bool>,
+}
+
+fn validate_name(name: &str) -> Result<String, ServiceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ServiceError::Validation(
+            "Goal name cannot be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > 100 {
+        return Err(ServiceError::Validation(
+            "Goal name too long (max 100 chars)".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_target(amount: Decimal) -> Result<(), ServiceError> {
+    if amount <= Decimal::ZERO {
+        return Err(ServiceError::Validation(
+            "Goal target amount must be positive".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_dates(start_date: NaiveDate, deadline: Option<NaiveDate>) -> Result<(), ServiceError> {
+    if deadline.is_some_and(|d| d <= start_date) {
+        return Err(ServiceError::Validation(
+            "Goal deadline must be after its start date".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Whole calendar months from `from` to `to`, not counting a trailing
+/// partial month.
+fn months_between(from: NaiveDate, to: NaiveDate) -> u32 {
+    if to <= from {
+        return 0;
+    }
+    let mut months = (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32;
+    if to.day() < from.day() {
+        months -= 1;
+    }
+    months.max(0) as u32
+}
+
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN))
+}
+
+async fn load_owned_goal<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    goal_id: Uuid,
+) -> Result<savings_goal::Model, ServiceError> {
+    let goal = SavingsGoal::find_by_id(goal_id)
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound)?;
+
+    if goal.user_id != user_id {
+        return Err(ServiceError::Forbidden);
+    }
+
+    Ok(goal)
+}
+
+async fn linked_account_ids<C: ConnectionTrait>(
+    db: &C,
+    goal_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<Uuid>>, ServiceError> {
+    let links = SavingsGoalAccount::find()
+        .filter(savings_goal_account::Column::GoalId.is_in(goal_ids.iter().copied()))
+        .order_by_asc(savings_goal_account::Column::AccountId)
+        .all(db)
+        .await?;
+
+    let mut by_goal: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for link in links {
+        by_goal
+            .entry(link.goal_id)
+            .or_default()
+            .push(link.account_id);
+    }
+    Ok(by_goal)
+}
+
+/// Checks that every linked account belongs to the user, is open and holds
+/// the goal's currency. Returns the ids de-duplicated.
+async fn validate_accounts<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    currency_code: &str,
+    account_ids: &[Uuid],
+) -> Result<Vec<Uuid>, ServiceError> {
+    let ids: Vec<Uuid> = account_ids
+        .iter()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    if ids.is_empty() {
+        return Err(ServiceError::Validation(
+            "A goal must be linked to at least one account".to_string(),
+        ));
+    }
+
+    let accounts: HashMap<Uuid, account::Model> = Account::find()
+        .filter(account::Column::Id.is_in(ids.iter().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|a| (a.id, a))
+        .collect();
+
+    for id in &ids {
+        let account = accounts
+            .get(id)
+            .filter(|a| a.deleted_at.is_none())
+            .ok_or_else(|| ServiceError::Validation(format!("Account {} not found", id)))?;
+        if account.user_id != user_id {
+            return Err(ServiceError::Forbidden);
+        }
+        if account.currency_code != currency_code {
+            return Err(ServiceError::Validation(format!(
+                "Account {} is in {}, but the goal is in {}",
+                id, account.currency_code, currency_code
+            )));
+        }
+    }
+
+    Ok(ids)
+}
+
+async fn replace_links<C: ConnectionTrait>(
+    db: &C,
+    goal_id: Uuid,
+    account_ids: &[Uuid],
+) -> Result<(), ServiceError> {
+    SavingsGoalAccount::delete_many()
+        .filter(savings_goal_account::Column::GoalId.eq(goal_id))
+        .exec(db)
+        .await?;
+
+    let links = account_ids
+        .iter()
+        .map(|account_id| savings_goal_account::ActiveModel {
+            goal_id: Set(goal_id),
+            account_id: Set(*account_id),
+        });
+    SavingsGoalAccount::insert_many(links).exec(db).await?;
+    Ok(())
+}
+
+/// Sum of transfers that cross the boundary of the linked accounts since the
+/// goal started, as `(into, out_of)`. Transfers between two linked accounts
+/// move money within the goal and are ignored.
+async fn transfer_totals<C: ConnectionTrait>(
+    db: &C,
+    goal: &savings_goal::Model,
+    account_ids: &[Uuid],
+) -> Result<(Decimal, Decimal), ServiceError> {
+    let since = start_of_day(goal.start_date);
+
+    let transferred_in: Option<Decimal> = Transaction::find()
+        .select_only()
+        .column_as(
+            Expr::expr(Func::coalesce([
+                Expr::col(transaction::Column::ToAmount).into(),
+                Expr::col(transaction::Column::Amount).into(),
+            ]))
+            .sum(),
+            "total",
+        )
+        .filter(transaction::Column::UserId.eq(goal.user_id))
+        .filter(transaction::Column::TxnType.eq("transfer"))
+        .filter(transaction::Column::OccurredAt.gte(since))
+        .filter(transaction::Column::ToAccountId.is_in(account_ids.iter().copied()))
+        .filter(
+            Condition::any()
+                .add(transaction::Column::FromAccountId.is_null())
+                .add(transaction::Column::FromAccountId.is_not_in(account_ids.iter().copied())),
+        )
+        .into_tuple()
+        .one(db)
+        .await?
+        .flatten();
+
+    let transferred_out: Option<Decimal> = Transaction::find()
+        .select_only()
+        .column_as(Expr::col(transaction::Column::Amount).sum(), "total")
+        .filter(transaction::Column::UserId.eq(goal.user_id))
+        .filter(transaction::Column::TxnType.eq("transfer"))
+        .filter(transaction::Column::OccurredAt.gte(since))
+        .filter(transaction::Column::FromAccountId.is_in(account_ids.iter().copied()))
+        .filter(
+            Condition::any()
+                .add(transaction::Column::ToAccountId.is_null())
+                .add(transaction::Column::ToAccountId.is_not_in(account_ids.iter().copied())),
+        )
+        .into_tuple()
+        .one(db)
+        .await?
+        .flatten();
+
+    Ok((
+        transferred_in.unwrap_or_default(),
+        transferred_out.unwrap_or_default(),
+    ))
+}
+
+async fn compute_progress<C: ConnectionTrait>(
+    db: &C,
+    goal: savings_goal::Model,
+    account_ids: &[Uuid],
+    today: NaiveDate,
+) -> Result<GoalProgressResponse, ServiceError> {
+    let accounts = Account::find()
+        .filter(account::Column::Id.is_in(account_ids.iter().copied()))
+        .filter(account::Column::DeletedAt.is_null())
+        .filter(account::Column::CurrencyCode.eq(goal.currency_code.clone()))
+        .order_by_asc(account::Column::Name)
+        .all(db)
+        .await?;
+    let ids: Vec<Uuid> = accounts.iter().map(|a| a.id).collect();
+
+    let current_amount: Decimal = accounts.iter().map(|a| a.balance).sum();
+    let remaining = (goal.target_amount - current_amount).max(Decimal::ZERO);
+    let completed = remaining.is_zero();
+    let percent_complete =
+        (current_amount.max(Decimal::ZERO) * Decimal::ONE_HUNDRED / goal.target_amount).round_dp(2);
+
+    let (transferred_in, transferred_out) = if ids.is_empty() {
+        (Decimal::ZERO, Decimal::ZERO)
+    } else {
+        transfer_totals(db, &goal, &ids).await?
+    };
+    let net_contributions = transferred_in - transferred_out;
+    let months_elapsed = months_between(goal.start_date, today).max(1);
+    let average_monthly_contribution =
+        (net_contributions / Decimal::from(months_elapsed)).round_dp(2);
+
+    let months_remaining = goal.deadline.map(|d| {
+        let months = months_between(today, d);
+        if months == 0 && d >= today {
+            1
+        } else {
+            months
+        }
+    });
+    let required_monthly_contribution = months_remaining.map(|months| {
+        if completed {
+            Decimal::ZERO
+        } else if months == 0 {
+            remaining
+        } else {
+            (remaining / Decimal::from(months)).round_dp(2)
+        }
+    });
+
+    let projected_completion = if completed {
+        None
+    } else if average_monthly_contribution > Decimal::ZERO {
+        let months = (remaining / average_monthly_contribution)
+            .ceil()
+            .try_into()
+            .unwrap_or(u32::MAX);
+        today.checked_add_months(Months::new(months))
+    } else {
+        None
+    };
+    let on_track = goal
+        .deadline
+        .map(|deadline| completed || projected_completion.is_some_and(|date| date <= deadline));
+
+    Ok(GoalProgressResponse {
+        goal_id: goal.id,
+        name: goal.name,
+        currency_code: goal.currency_code,
+        target_amount: goal.target_amount,
+        current_amount,
+        remaining,
+        percent_complete,
+        completed,
+        accounts: accounts
+            .into_iter()
+            .map(|a| GoalAccountBalance {
+                account_id: a.id,
+                name: a.name,
+                balance: a.balance,
+            })
+            .collect(),
+        transferred_in,
+        transferred_out,
+        net_contributions,
+        average_monthly_contribution,
+        deadline: goal.deadline,
+        months_remaining,
+        required_monthly_contribution,
+        projected_completion,
+        on_track,
+    })
+}
+
+pub async fn create_goal(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: CreateGoalRequest,
+) -> Result<GoalResponse, ServiceError> {
+    let name = validate_name(&req.name)?;
+    validate_target(req.target_amount)?;
+    let currency_code = validate_currency_code(db, user_id, &req.currency_code).await?;
+    let start_date = req.start_date.unwrap_or_else(|| Utc::now().date_naive());
+    validate_dates(start_date, req.deadline)?;
+
+    let tx = db.begin().await?;
+    let account_ids = validate_accounts(&tx, user_id, &currency_code, &req.account_ids).await?;
+
+    let now = Utc::now().into();
+    let goal = savings_goal::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        name: Set(name),
+        target_amount: Set(req.target_amount),
+        currency_code: Set(currency_code),
+        start_date: Set(start_date),
+        deadline: Set(req.deadline),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    let model = goal.insert(&tx).await?;
+    replace_links(&tx, model.id, &account_ids).await?;
+    tx.commit().await?;
+
+    Ok(GoalResponse::new(model, account_ids))
+}
+
+pub async fn get_goal(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    goal_id: Uuid,
+) -> Result<GoalResponse, ServiceError> {
+    let goal = load_owned_goal(db, user_id, goal_id).await?;
+    let mut links = linked_account_ids(db, &[goal.id]).await?;
+    let account_ids = links.remove(&goal.id).unwrap_or_default();
+    Ok(GoalResponse::new(goal, account_ids))
+}
+
+pub async fn list_goals(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<GoalResponse>, ServiceError> {
+    let goals = SavingsGoal::find()
+        .filter(savings_goal::Column::UserId.eq(user_id))
+        .order_by_asc(savings_goal::Column::Name)
+        .all(db)
+        .await?;
+    let ids: Vec<Uuid> = goals.iter().map(|g| g.id).collect();
+    let mut links = linked_account_ids(db, &ids).await?;
+
+    Ok(goals
+        .into_iter()
+        .map(|g| {
+            let account_ids = links.remove(&g.id).unwrap_or_default();
+            GoalResponse::new(g, account_ids)
+        })
+        .collect())
+}
+
+pub async fn update_goal(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    goal_id: Uuid,
+    req: UpdateGoalRequest,
+) -> Result<GoalResponse, ServiceError> {
+    let existing = load_owned_goal(db, user_id, goal_id).await?;
+    let start_date = req.start_date.unwrap_or(existing.start_date);
+    let deadline = req.deadline.unwrap_or(existing.deadline);
+    validate_dates(start_date, deadline)?;
+
+    let tx = db.begin().await?;
+    let account_ids = match req.account_ids {
+        Some(ids) => {
+            let ids = validate_accounts(&tx, user_id, &existing.currency_code, &ids).await?;
+            replace_links(&tx, goal_id, &ids).await?;
+            ids
+        }
+        None => linked_account_ids(&tx, &[goal_id])
+            .await?
+            .remove(&goal_id)
+            .unwrap_or_default(),
+    };
+
+    let mut goal: savings_goal::ActiveModel = existing.into();
+    if let Some(name) = req.name {
+        goal.name = Set(validate_name(&name)?);
+    }
+    if let Some(target_amount) = req.target_amount {
+        validate_target(target_amount)?;
+        goal.target_amount = Set(target_amount);
+    }
+    goal.start_date = Set(start_date);
+    goal.deadline = Set(deadline);
+    goal.updated_at = Set(Utc::now().into());
+
+    let model = goal.update(&tx).await?;
+    tx.commit().await?;
+
+    Ok(GoalResponse::new(model, account_ids))
+}
+
+pub async fn delete_goal(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    goal_id: Uuid,
+) -> Result<(), ServiceError> {
+    let goal = load_owned_goal(db, user_id, goal_id).await?;
+    let active: savings_goal::ActiveModel = goal.into();
+    active.delete(db).await?;
+    Ok(())
+}
+
+pub async fn get_goal_progress(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    goal_id: Uuid,
+    date: Option<NaiveDate>,
+) -> Result<GoalProgressResponse, ServiceError> {
+    let goal = load_owned_goal(db, user_id, goal_id).await?;
+    let account_ids = linked_account_ids(db, &[goal.id])
+        .await?
+        .remove(&goal.id)
+        .unwrap_or_default();
+    let today = date.unwrap_or_else(|| Utc::now().date_naive());
+    compute_progress(db, goal, &account_ids, today).await
+}
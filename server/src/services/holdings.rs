@@ -1,13 +1,15 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    Set,
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::entities::{holdings, prelude::*};
 use crate::errors::ServiceError;
+use crate::services::currency::validate_currency_code;
 
 #[derive(Debug, Deserialize)]
 pub struct CreateHoldingsRequest {
@@ -72,6 +74,18 @@ impl From<holdings::Model> for HoldingsResponse {
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionUpdate {
+    pub asset_type: String,
+    pub symbol: String,
+    pub name: Option<String>,
+    pub quantity: Decimal,
+    pub currency_code: String,
+    pub last_price: Option<Decimal>,
+    pub last_price_at: Option<DateTime<Utc>>,
+    pub market_value: Option<Decimal>,
+}
+
 const VALID_ASSET_TYPES: &[&str] = &["stock", "fund", "crypto", "bond", "cash", "other"];
 
 fn validate_asset_type(t: &str) -> Result<(), ServiceError> {
@@ -81,13 +95,13 @@ fn validate_asset_type(t: &str) -> Result<(), ServiceError> {
     Ok(())
 }
 
-fn validate_currency_code(code: &str) -> Result<(), ServiceError> {
-    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
-        return Err(ServiceError::Validation(
-            "Currency code must be 3 letters".to_string(),
-        ));
-    }
-    Ok(())
+/// Best available valuation: the stored market value, else quantity at the
+/// last price, else what was paid.
+pub(crate) fn current_value(holding: &holdings::Model) -> Decimal {
+    holding
+        .market_value
+        .or_else(|| holding.last_price.map(|p| (p * holding.quantity).round_dp(4)))
+        .unwrap_or(holding.cost_basis_total)
 }
 
 async fn load_owned_holdings(
@@ -159,8 +173,7 @@ pub async fn create_holdings(
     let asset_type = req.asset_type.trim().to_lowercase();
     validate_asset_type(&asset_type)?;
 
-    let currency = req.currency_code.trim().to_uppercase();
-    validate_currency_code(&currency)?;
+    let currency = validate_currency_code(db, user_id, &req.currency_code).await?;
 
     let symbol = req.symbol.trim().to_uppercase();
     if symbol.is_empty() {
@@ -196,6 +209,76 @@ pub async fn create_holdings(
     }
 }
 
+pub(crate) async fn upsert_position<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    account_id: Uuid,
+    position: PositionUpdate,
+) -> Result<HoldingsResponse, ServiceError> {
+    let asset_type = position.asset_type.trim().to_lowercase();
+    validate_asset_type(&asset_type)?;
+
+    let currency = validate_currency_code(db, user_id, &position.currency_code).await?;
+
+    let symbol = position.symbol.trim().to_uppercase();
+    if symbol.is_empty() {
+        return Err(ServiceError::Validation("Symbol cannot be empty".to_string()));
+    }
+
+    if position.quantity < Decimal::ZERO {
+        return Err(ServiceError::Validation("Quantity cannot be negative".to_string()));
+    }
+
+    let existing = Holdings::find()
+        .filter(holdings::Column::UserId.eq(user_id))
+        .filter(holdings::Column::AccountId.eq(account_id))
+        .filter(holdings::Column::AssetType.eq(asset_type.clone()))
+        .filter(holdings::Column::Symbol.eq(symbol.clone()))
+        .one(db)
+        .await?;
+
+    let now = Utc::now().into();
+    let model = match existing {
+        Some(holding) => {
+            let mut active: holdings::ActiveModel = holding.into();
+            active.quantity = Set(position.quantity);
+            if position.name.is_some() {
+                active.name = Set(position.name);
+            }
+            if position.last_price.is_some() {
+                active.last_price = Set(position.last_price);
+                active.last_price_at = Set(position.last_price_at.map(|dt| dt.into()));
+            }
+            if position.market_value.is_some() {
+                active.market_value = Set(position.market_value);
+            }
+            active.updated_at = Set(now);
+            active.update(db).await?
+        }
+        None => {
+            let holding = holdings::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                user_id: Set(user_id),
+                account_id: Set(account_id),
+                asset_type: Set(asset_type),
+                symbol: Set(symbol),
+                name: Set(position.name),
+                quantity: Set(position.quantity),
+                cost_basis_total: Set(Decimal::ZERO),
+                currency_code: Set(currency),
+                last_price: Set(position.last_price),
+                last_price_at: Set(position.last_price_at.map(|dt| dt.into())),
+                market_value: Set(position.market_value),
+                created_at: Set(now),
+                updated_at: Set(now),
+            };
+            holding.insert(db).await?
+        }
+    };
+
+    Ok(HoldingsResponse::from(model))
+}
+
 pub async fn get_holdings(
     db: &DatabaseConnection,
     user_id: Uuid,
@@ -0,0 +1,151 @@
+use ::csv::{ReaderBuilder, StringRecord, Trim};
+use rust_decimal::Decimal;
+
+use super::{parse_amount, parse_date, LineError, StatementLine};
+use crate::entities::import_profile;
+use crate::errors::ServiceError;
+
+struct ColumnMap {
+    date: usize,
+    amount: Option<usize>,
+    debit: Option<usize>,
+    credit: Option<usize>,
+    merchant: Option<usize>,
+    note: Option<usize>,
+}
+
+fn resolve_column(
+    headers: Option<&StringRecord>,
+    column: &str,
+) -> Result<usize, ServiceError> {
+    let wanted = column.trim();
+
+    if let Some(headers) = headers {
+        if let Some(idx) = headers
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(wanted))
+        {
+            return Ok(idx);
+        }
+    }
+
+    wanted.parse::<usize>().map_err(|_| {
+        ServiceError::Validation(format!("Column '{}' not found in CSV", wanted))
+    })
+}
+
+fn resolve_optional(
+    headers: Option<&StringRecord>,
+    column: Option<&String>,
+) -> Result<Option<usize>, ServiceError> {
+    column.map(|c| resolve_column(headers, c)).transpose()
+}
+
+fn field(record: &StringRecord, idx: Option<usize>) -> Option<String> {
+    idx.and_then(|i| record.get(i))
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_string())
+}
+
+fn parse_record(
+    profile: &import_profile::Model,
+    columns: &ColumnMap,
+    record: &StringRecord,
+) -> Result<(chrono::DateTime<chrono::Utc>, Decimal), String> {
+    let raw_date = field(record, Some(columns.date)).ok_or("Missing date")?;
+    let occurred_at = parse_date(&raw_date, &profile.date_format)?;
+
+    let separator = profile.decimal_separator.chars().next().unwrap_or('.');
+    let amount = if columns.amount.is_some() {
+        field(record, columns.amount)
+            .map(|raw| parse_amount(&raw, separator))
+            .transpose()?
+            .ok_or("Missing amount")?
+    } else {
+        let debit = field(record, columns.debit)
+            .map(|raw| parse_amount(&raw, separator))
+            .transpose()?;
+        let credit = field(record, columns.credit)
+            .map(|raw| parse_amount(&raw, separator))
+            .transpose()?;
+
+        if debit.is_none() && credit.is_none() {
+            return Err("Missing debit and credit amount".to_string());
+        }
+
+        credit.unwrap_or(Decimal::ZERO).abs() - debit.unwrap_or(Decimal::ZERO).abs()
+    };
+
+    Ok((occurred_at, amount))
+}
+
+pub fn parse(
+    profile: &import_profile::Model,
+    content: &str,
+) -> Result<Vec<Result<StatementLine, LineError>>, ServiceError> {
+    let delimiter = profile.delimiter.as_bytes().first().copied().unwrap_or(b',');
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(profile.has_header)
+        .flexible(true)
+        .trim(Trim::All)
+        .from_reader(content.as_bytes());
+
+    let headers = if profile.has_header {
+        Some(
+            reader
+                .headers()
+                .map_err(|e| ServiceError::Validation(format!("Invalid CSV header: {}", e)))?
+                .clone(),
+        )
+    } else {
+        None
+    };
+
+    let columns = ColumnMap {
+        date: resolve_column(headers.as_ref(), &profile.date_column)?,
+        amount: resolve_optional(headers.as_ref(), profile.amount_column.as_ref())?,
+        debit: resolve_optional(headers.as_ref(), profile.debit_column.as_ref())?,
+        credit: resolve_optional(headers.as_ref(), profile.credit_column.as_ref())?,
+        merchant: resolve_optional(headers.as_ref(), profile.merchant_column.as_ref())?,
+        note: resolve_optional(headers.as_ref(), profile.note_column.as_ref())?,
+    };
+
+    let mut lines = Vec::new();
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| p.line() as usize).unwrap_or(0);
+                lines.push(Err(LineError {
+                    line,
+                    error: format!("Invalid CSV row: {}", e),
+                }));
+                continue;
+            }
+        };
+
+        if record.iter().all(|v| v.trim().is_empty()) {
+            continue;
+        }
+
+        let line = record.position().map(|p| p.line() as usize).unwrap_or(0);
+        let parsed = parse_record(profile, &columns, &record).map(|(occurred_at, amount)| {
+            StatementLine {
+                line,
+                occurred_at,
+                amount,
+                merchant: field(&record, columns.merchant),
+                note: field(&record, columns.note),
+                category: None,
+                external_id: None,
+            }
+        });
+
+        lines.push(parsed.map_err(|error| LineError { line, error }));
+    }
+
+    Ok(lines)
+}
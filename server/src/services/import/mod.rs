@@ -0,0 +1,422 @@
+pub mod csv;
+pub mod ofx;
+pub mod profile;
+pub mod qif;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::entities::{account, prelude::*, transaction as txn_entity};
+use crate::errors::{BatchItemError, ServiceError};
+use crate::services::account::load_owned_account;
+use crate::services::holdings::{self, HoldingsResponse, PositionUpdate};
+use crate::services::rule::RuleSet;
+use crate::services::transaction::{
+    self, CreateTransactionRequest, TransactionResponse,
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementLine {
+    pub line: usize,
+    pub occurred_at: DateTime<Utc>,
+    pub amount: Decimal,
+    pub merchant: Option<String>,
+    pub note: Option<String>,
+    pub category: Option<String>,
+    pub external_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LineError {
+    pub line: usize,
+    pub error: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportCsvRequest {
+    pub profile_id: Uuid,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportStatementRequest {
+    pub account_id: Uuid,
+    pub content: String,
+    pub date_format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportPreviewRow {
+    pub index: usize,
+    pub line: usize,
+    pub txn_type: Option<String>,
+    pub amount: Option<Decimal>,
+    pub currency_code: String,
+    pub occurred_at: Option<DateTime<Utc>>,
+    pub merchant: Option<String>,
+    pub note: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub external_id: Option<String>,
+    pub duplicate: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportPreviewResponse {
+    pub account_id: Uuid,
+    pub valid_count: usize,
+    pub error_count: usize,
+    pub duplicate_count: usize,
+    pub rows: Vec<ImportPreviewRow>,
+    pub positions: Vec<PositionUpdate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportResultResponse {
+    pub account_id: Uuid,
+    pub transactions: Vec<TransactionResponse>,
+    pub skipped_duplicates: usize,
+    pub holdings: Vec<HoldingsResponse>,
+}
+
+pub(crate) fn parse_amount(raw: &str, decimal_separator: char) -> Result<Decimal, String> {
+    let trimmed = raw.trim();
+    let negative_parens = trimmed.starts_with('(') && trimmed.ends_with(')');
+
+    let mut normalized = String::with_capacity(trimmed.len());
+    for c in trimmed.chars() {
+        if c.is_ascii_digit() || c == '-' || c == '+' {
+            normalized.push(c);
+        } else if c == decimal_separator {
+            normalized.push('.');
+        }
+    }
+
+    let value = Decimal::from_str(&normalized)
+        .map_err(|_| format!("Invalid amount: {}", raw))?;
+
+    Ok(if negative_parens { -value.abs() } else { value })
+}
+
+pub(crate) fn parse_date(raw: &str, format: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(dt) = NaiveDateTime::parse_from_str(raw, format) {
+        return Ok(Utc.from_utc_datetime(&dt));
+    }
+
+    NaiveDate::parse_from_str(raw, format)
+        .map(|d| Utc.from_utc_datetime(&d.and_time(chrono::NaiveTime::MIN)))
+        .map_err(|_| format!("Invalid date '{}' for format '{}'", raw, format))
+}
+
+pub(crate) fn to_create_request(
+    line: &StatementLine,
+    account_id: Uuid,
+    currency_code: &str,
+) -> CreateTransactionRequest {
+    let outflow = line.amount < Decimal::ZERO;
+
+    CreateTransactionRequest {
+        from_account_id: if outflow { Some(account_id) } else { None },
+        to_account_id: if outflow { None } else { Some(account_id) },
+        txn_type: if outflow { "expense" } else { "income" }.to_string(),
+        amount: line.amount.abs(),
+        currency_code: currency_code.to_string(),
+        to_amount: None,
+        to_currency_code: None,
+        settled_amount: None,
+        category: line.category.clone(),
+        category_id: None,
+        note: line.note.clone(),
+        occurred_at: line.occurred_at,
+        ref_transaction_id: None,
+        merchant: line.merchant.clone(),
+        external_id: line.external_id.clone(),
+        splits: None,
+        tags: None,
+        allow_duplicate: None,
+    }
+}
+
+async fn existing_external_ids(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    account_id: Uuid,
+    lines: &[Result<StatementLine, LineError>],
+) -> Result<HashSet<String>, ServiceError> {
+    let ids: Vec<String> = lines
+        .iter()
+        .filter_map(|l| l.as_ref().ok()?.external_id.clone())
+        .collect();
+
+    if ids.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let existing: Vec<Option<String>> = Transaction::find()
+        .select_only()
+        .column(txn_entity::Column::ExternalId)
+        .filter(txn_entity::Column::UserId.eq(user_id))
+        .filter(
+            Condition::any()
+                .add(txn_entity::Column::FromAccountId.eq(account_id))
+                .add(txn_entity::Column::ToAccountId.eq(account_id)),
+        )
+        .filter(txn_entity::Column::ExternalId.is_in(ids))
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    Ok(existing.into_iter().flatten().collect())
+}
+
+async fn flag_duplicates(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    account: &account::Model,
+    lines: &[Result<StatementLine, LineError>],
+) -> Result<Vec<bool>, ServiceError> {
+    let mut seen = existing_external_ids(db, user_id, account.id, lines).await?;
+
+    let mut flags = Vec::with_capacity(lines.len());
+    for parsed in lines {
+        let Ok(line) = parsed else {
+            flags.push(false);
+            continue;
+        };
+
+        if let Some(id) = &line.external_id {
+            if !seen.insert(id.clone()) {
+                flags.push(true);
+                continue;
+            }
+        }
+
+        let req = to_create_request(line, account.id, &account.currency_code);
+        // Lines that fail validation are reported when committed, not here.
+        let duplicate = match transaction::find_duplicate_for(db, user_id, req, &[]).await {
+            Ok(duplicate) => duplicate.is_some(),
+            Err(ServiceError::Validation(_)) => false,
+            Err(e) => return Err(e),
+        };
+        flags.push(duplicate);
+    }
+
+    Ok(flags)
+}
+
+pub(crate) async fn preview_lines(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    account_id: Uuid,
+    lines: Vec<Result<StatementLine, LineError>>,
+    positions: Vec<PositionUpdate>,
+) -> Result<ImportPreviewResponse, ServiceError> {
+    let account = load_owned_account(db, user_id, account_id).await?;
+    let duplicates = flag_duplicates(db, user_id, &account, &lines).await?;
+    let rules = RuleSet::load(db, user_id).await?;
+
+    let mut rows = Vec::with_capacity(lines.len());
+    for (index, (parsed, duplicate)) in lines.into_iter().zip(duplicates).enumerate() {
+        let row = match parsed {
+            Ok(line) => {
+                let mut req = to_create_request(&line, account.id, &account.currency_code);
+                rules.apply(&mut req);
+                let category = req
+                    .category
+                    .clone()
+                    .or_else(|| req.category_id.and_then(|id| rules.category_path(id)));
+                let error = if duplicate {
+                    None
+                } else {
+                    transaction::validate_transaction_request(db, user_id, req.clone())
+                        .await
+                        .err()
+                        .map(|e| e.status_and_message().1)
+                };
+
+                ImportPreviewRow {
+                    index,
+                    line: line.line,
+                    txn_type: Some(req.txn_type),
+                    amount: Some(req.amount),
+                    currency_code: req.currency_code,
+                    occurred_at: Some(line.occurred_at),
+                    merchant: line.merchant,
+                    note: req.note,
+                    category,
+                    tags: req.tags.unwrap_or_default(),
+                    external_id: line.external_id,
+                    duplicate,
+                    error,
+                }
+            }
+            Err(e) => ImportPreviewRow {
+                index,
+                line: e.line,
+                txn_type: None,
+                amount: None,
+                currency_code: account.currency_code.clone(),
+                occurred_at: None,
+                merchant: None,
+                note: None,
+                category: None,
+                tags: Vec::new(),
+                external_id: None,
+                duplicate: false,
+                error: Some(e.error),
+            },
+        };
+        rows.push(row);
+    }
+
+    let error_count = rows.iter().filter(|r| r.error.is_some()).count();
+    let duplicate_count = rows.iter().filter(|r| r.duplicate).count();
+
+    Ok(ImportPreviewResponse {
+        account_id: account.id,
+        valid_count: rows.len() - error_count - duplicate_count,
+        error_count,
+        duplicate_count,
+        rows,
+        positions,
+    })
+}
+
+pub(crate) async fn commit_lines(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    account_id: Uuid,
+    lines: Vec<Result<StatementLine, LineError>>,
+    positions: Vec<PositionUpdate>,
+) -> Result<ImportResultResponse, ServiceError> {
+    let account = load_owned_account(db, user_id, account_id).await?;
+    let duplicates = flag_duplicates(db, user_id, &account, &lines).await?;
+
+    let mut reqs = Vec::with_capacity(lines.len());
+    let mut errors = Vec::new();
+    let mut skipped_duplicates = 0;
+    for (index, (parsed, duplicate)) in lines.into_iter().zip(duplicates).enumerate() {
+        match parsed {
+            Ok(_) if duplicate => skipped_duplicates += 1,
+            Ok(line) => reqs.push(CreateTransactionRequest {
+                allow_duplicate: Some(true),
+                ..to_create_request(&line, account.id, &account.currency_code)
+            }),
+            Err(e) => errors.push(BatchItemError {
+                index,
+                error: format!("Line {}: {}", e.line, e.error),
+            }),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(ServiceError::Batch(errors));
+    }
+
+    let tx = db.begin().await?;
+    let transactions = if reqs.is_empty() {
+        Vec::new()
+    } else {
+        transaction::insert_transactions(&tx, user_id, reqs).await?
+    };
+
+    let mut holdings = Vec::with_capacity(positions.len());
+    for position in positions {
+        holdings.push(holdings::upsert_position(&tx, user_id, account.id, position).await?);
+    }
+    tx.commit().await?;
+
+    Ok(ImportResultResponse {
+        account_id: account.id,
+        transactions,
+        skipped_duplicates,
+        holdings,
+    })
+}
+
+pub async fn preview_csv(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: ImportCsvRequest,
+) -> Result<ImportPreviewResponse, ServiceError> {
+    let profile = profile::load_owned_profile(db, user_id, req.profile_id).await?;
+    let lines = csv::parse(&profile, &req.content)?;
+    preview_lines(db, user_id, profile.account_id, lines, Vec::new()).await
+}
+
+pub async fn import_csv(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: ImportCsvRequest,
+) -> Result<ImportResultResponse, ServiceError> {
+    let profile = profile::load_owned_profile(db, user_id, req.profile_id).await?;
+    let lines = csv::parse(&profile, &req.content)?;
+    commit_lines(db, user_id, profile.account_id, lines, Vec::new()).await
+}
+
+async fn parse_ofx_for_account(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: &ImportStatementRequest,
+) -> Result<ofx::OfxStatement, ServiceError> {
+    let account = load_owned_account(db, user_id, req.account_id).await?;
+    let statement = ofx::parse(&req.content, &account.currency_code)?;
+
+    if let Some(currency) = &statement.currency_code {
+        if currency != &account.currency_code {
+            return Err(ServiceError::Validation(format!(
+                "Statement currency {} does not match account currency {}",
+                currency, account.currency_code
+            )));
+        }
+    }
+
+    Ok(statement)
+}
+
+pub async fn preview_ofx(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: ImportStatementRequest,
+) -> Result<ImportPreviewResponse, ServiceError> {
+    let statement = parse_ofx_for_account(db, user_id, &req).await?;
+    preview_lines(db, user_id, req.account_id, statement.lines, statement.positions).await
+}
+
+pub async fn import_ofx(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: ImportStatementRequest,
+) -> Result<ImportResultResponse, ServiceError> {
+    let statement = parse_ofx_for_account(db, user_id, &req).await?;
+    commit_lines(db, user_id, req.account_id, statement.lines, statement.positions).await
+}
+
+pub async fn preview_qif(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: ImportStatementRequest,
+) -> Result<ImportPreviewResponse, ServiceError> {
+    let date_format = req.date_format.as_deref().unwrap_or(qif::DEFAULT_DATE_FORMAT);
+    let lines = qif::parse(&req.content, date_format);
+    preview_lines(db, user_id, req.account_id, lines, Vec::new()).await
+}
+
+pub async fn import_qif(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: ImportStatementRequest,
+) -> Result<ImportResultResponse, ServiceError> {
+    let date_format = req.date_format.as_deref().unwrap_or(qif::DEFAULT_DATE_FORMAT);
+    let lines = qif::parse(&req.content, date_format);
+    commit_lines(db, user_id, req.account_id, lines, Vec::new()).await
+}
@@ -0,0 +1,322 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use super::{parse_amount, LineError, StatementLine};
+use crate::errors::ServiceError;
+use crate::services::holdings::PositionUpdate;
+
+#[derive(Debug, Default)]
+struct Node {
+    tag: String,
+    line: usize,
+    text: Option<String>,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn child(&self, tag: &str) -> Option<&Node> {
+        self.children.iter().find(|c| c.tag == tag)
+    }
+
+    fn value(&self, tag: &str) -> Option<&str> {
+        self.child(tag)
+            .and_then(|c| c.text.as_deref())
+            .filter(|v| !v.is_empty())
+    }
+
+    fn path(&self, tags: &[&str]) -> Option<&Node> {
+        tags.iter().try_fold(self, |node, tag| node.child(tag))
+    }
+
+    fn collect<'a>(&'a self, tag: &str, out: &mut Vec<&'a Node>) {
+        for child in &self.children {
+            if child.tag == tag {
+                out.push(child);
+            }
+            child.collect(tag, out);
+        }
+    }
+}
+
+pub struct OfxStatement {
+    pub currency_code: Option<String>,
+    pub lines: Vec<Result<StatementLine, LineError>>,
+    pub positions: Vec<PositionUpdate>,
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn parse_tree(content: &str) -> Result<Node, ServiceError> {
+    let start = content
+        .find("<OFX>")
+        .or_else(|| content.find("<ofx>"))
+        .ok_or(ServiceError::Validation("Missing <OFX> root element".to_string()))?;
+
+    let mut stack = vec![Node {
+        tag: "#ROOT".to_string(),
+        ..Default::default()
+    }];
+
+    // Line numbers are counted incrementally; tags are visited in order.
+    let mut line = 1;
+    let mut counted = 0;
+    let mut pos = start;
+    while let Some(rel) = content[pos..].find('<') {
+        let open = pos + rel;
+        let close = content[open..]
+            .find('>')
+            .map(|i| open + i)
+            .ok_or(ServiceError::Validation("Unterminated OFX tag".to_string()))?;
+        let raw_tag = content[open + 1..close].trim();
+        let text_end = content[close + 1..]
+            .find('<')
+            .map(|i| close + 1 + i)
+            .unwrap_or(content.len());
+        let text = content[close + 1..text_end].trim();
+        pos = close + 1;
+
+        if raw_tag.starts_with('?') || raw_tag.starts_with('!') {
+            continue;
+        }
+
+        if let Some(name) = raw_tag.strip_prefix('/') {
+            let name = name.trim().to_uppercase();
+            if !stack.iter().skip(1).any(|n| n.tag == name) {
+                continue;
+            }
+            while stack.len() > 1 {
+                let node = stack.pop().expect("checked length");
+                let done = node.tag == name;
+                stack.last_mut().expect("root never popped").children.push(node);
+                if done {
+                    break;
+                }
+            }
+            continue;
+        }
+
+        line += content[counted..open].matches('\n').count();
+        counted = open;
+        let node = Node {
+            tag: raw_tag
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches('/')
+                .to_uppercase(),
+            line,
+            ..Default::default()
+        };
+
+        if !text.is_empty() {
+            let closing = format!("</{}>", node.tag);
+            if content
+                .get(text_end..text_end + closing.len())
+                .is_some_and(|tail| tail.eq_ignore_ascii_case(&closing))
+            {
+                pos = text_end + closing.len();
+            }
+            stack.last_mut().expect("root never popped").children.push(Node {
+                text: Some(decode_entities(text)),
+                ..node
+            });
+        } else if raw_tag.ends_with('/') {
+            stack.last_mut().expect("root never popped").children.push(node);
+        } else {
+            stack.push(node);
+        }
+    }
+
+    while stack.len() > 1 {
+        let node = stack.pop().expect("checked length");
+        stack.last_mut().expect("root never popped").children.push(node);
+    }
+
+    Ok(stack.pop().expect("root"))
+}
+
+pub(crate) fn parse_ofx_date(raw: &str) -> Result<DateTime<Utc>, String> {
+    let (stamp, zone) = match raw.find('[') {
+        Some(i) => (&raw[..i], Some(raw[i + 1..].trim_end_matches(']'))),
+        None => (raw, None),
+    };
+    let digits: String = stamp
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect();
+
+    if digits.len() < 8 {
+        return Err(format!("Invalid OFX date: {}", raw));
+    }
+
+    let date = NaiveDate::parse_from_str(&digits[..8], "%Y%m%d")
+        .map_err(|_| format!("Invalid OFX date: {}", raw))?;
+    let time = if digits.len() >= 14 {
+        NaiveTime::parse_from_str(&digits[8..14], "%H%M%S")
+            .map_err(|_| format!("Invalid OFX time: {}", raw))?
+    } else {
+        NaiveTime::MIN
+    };
+    let naive = NaiveDateTime::new(date, time);
+
+    let offset_hours = zone
+        .and_then(|z| z.split(':').next())
+        .and_then(|h| h.trim().parse::<f64>().ok())
+        .unwrap_or(0.0);
+    let offset = FixedOffset::east_opt((offset_hours * 3600.0) as i32)
+        .ok_or(format!("Invalid OFX timezone: {}", raw))?;
+
+    offset
+        .from_local_datetime(&naive)
+        .single()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or(format!("Invalid OFX date: {}", raw))
+}
+
+fn ofx_amount(raw: &str) -> Result<Decimal, String> {
+    let separator = if raw.contains(',') && !raw.contains('.') { ',' } else { '.' };
+    parse_amount(raw, separator)
+}
+
+fn parse_stmttrn(node: &Node, line: usize) -> Result<StatementLine, String> {
+    let posted = node.value("DTPOSTED").ok_or("Missing DTPOSTED")?;
+    let amount = node.value("TRNAMT").ok_or("Missing TRNAMT")?;
+
+    let merchant = node
+        .value("NAME")
+        .or_else(|| node.child("PAYEE").and_then(|p| p.value("NAME")))
+        .map(|v| v.to_string());
+
+    Ok(StatementLine {
+        line,
+        occurred_at: parse_ofx_date(posted)?,
+        amount: ofx_amount(amount)?,
+        merchant,
+        note: node.value("MEMO").map(|v| v.to_string()),
+        category: None,
+        external_id: node.value("FITID").map(|v| v.to_string()),
+    })
+}
+
+fn position_asset_type(tag: &str) -> &'static str {
+    match tag {
+        "POSSTOCK" => "stock",
+        "POSMF" => "fund",
+        "POSDEBT" => "bond",
+        _ => "other",
+    }
+}
+
+fn parse_positions(root: &Node, currency_code: &str) -> Result<Vec<PositionUpdate>, ServiceError> {
+    let mut securities = Vec::new();
+    root.collect("SECINFO", &mut securities);
+    let tickers: HashMap<&str, (Option<&str>, Option<&str>)> = securities
+        .iter()
+        .filter_map(|info| {
+            let id = info.path(&["SECID"])?.value("UNIQUEID")?;
+            Some((id, (info.value("TICKER"), info.value("SECNAME"))))
+        })
+        .collect();
+
+    let mut lists = Vec::new();
+    root.collect("INVPOSLIST", &mut lists);
+
+    let mut positions = Vec::new();
+    for list in lists {
+        for pos in &list.children {
+            let Some(inv) = pos.child("INVPOS") else {
+                continue;
+            };
+            let unique_id = inv
+                .path(&["SECID"])
+                .and_then(|s| s.value("UNIQUEID"))
+                .ok_or(ServiceError::Validation("Position missing SECID".to_string()))?;
+            let (ticker, name) = tickers.get(unique_id).copied().unwrap_or((None, None));
+
+            let units = inv
+                .value("UNITS")
+                .map(ofx_amount)
+                .transpose()
+                .map_err(ServiceError::Validation)?
+                .unwrap_or(Decimal::ZERO);
+            let price = inv
+                .value("UNITPRICE")
+                .map(ofx_amount)
+                .transpose()
+                .map_err(ServiceError::Validation)?;
+            let market_value = inv
+                .value("MKTVAL")
+                .map(ofx_amount)
+                .transpose()
+                .map_err(ServiceError::Validation)?;
+            let price_at = inv
+                .value("DTPRICEASOF")
+                .map(parse_ofx_date)
+                .transpose()
+                .map_err(ServiceError::Validation)?;
+
+            positions.push(PositionUpdate {
+                asset_type: position_asset_type(&pos.tag).to_string(),
+                symbol: ticker.unwrap_or(unique_id).to_string(),
+                name: name.map(|n| n.to_string()),
+                quantity: units.abs(),
+                currency_code: inv
+                    .path(&["CURRENCY"])
+                    .and_then(|c| c.value("CURSYM"))
+                    .unwrap_or(currency_code)
+                    .to_string(),
+                last_price: price,
+                last_price_at: price_at,
+                market_value: market_value.map(|v| v.abs()),
+            });
+        }
+    }
+
+    Ok(positions)
+}
+
+pub fn parse(content: &str, account_currency: &str) -> Result<OfxStatement, ServiceError> {
+    let root = parse_tree(content)?;
+
+    let mut currencies = Vec::new();
+    root.collect("CURDEF", &mut currencies);
+    let currency_code = currencies
+        .first()
+        .and_then(|c| c.text.as_deref())
+        .map(|c| c.trim().to_uppercase());
+
+    let mut transactions = Vec::new();
+    root.collect("STMTTRN", &mut transactions);
+
+    let parsed = transactions
+        .into_iter()
+        .map(|node| {
+            parse_stmttrn(node, node.line).map_err(|error| LineError {
+                line: node.line,
+                error,
+            })
+        })
+        .collect();
+
+    let positions = parse_positions(
+        &root,
+        currency_code.as_deref().unwrap_or(account_currency),
+    )?;
+
+    Ok(OfxStatement {
+        currency_code,
+        lines: parsed,
+        positions,
+    })
+}
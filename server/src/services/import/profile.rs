@@ -0,0 +1,302 @@
+use chrono::{format::{Item, StrftimeItems}, DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    SqlErr,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entities::{import_profile, prelude::*};
+use crate::errors::ServiceError;
+use crate::services::account::load_owned_account;
+use crate::utils::nullable::double_option;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateImportProfileRequest {
+    pub name: String,
+    pub account_id: Uuid,
+    pub delimiter: Option<String>,
+    pub has_header: Option<bool>,
+    pub date_column: String,
+    pub date_format: String,
+    pub amount_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub merchant_column: Option<String>,
+    pub note_column: Option<String>,
+    pub decimal_separator: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateImportProfileRequest {
+    pub name: Option<String>,
+    pub account_id: Option<Uuid>,
+    pub delimiter: Option<String>,
+    pub has_header: Option<bool>,
+    pub date_column: Option<String>,
+    pub date_format: Option<String>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub amount_column: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub debit_column: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub credit_column: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub merchant_column: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub note_column: Option<Option<String>>,
+    pub decimal_separator: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportProfileResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub account_id: Uuid,
+    pub delimiter: String,
+    pub has_header: bool,
+    pub date_column: String,
+    pub date_format: String,
+    pub amount_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub merchant_column: Option<String>,
+    pub note_column: Option<String>,
+    pub decimal_separator: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<import_profile::Model> for ImportProfileResponse {
+    fn from(model: import_profile::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            account_id: model.account_id,
+            delimiter: model.delimiter,
+            has_header: model.has_header,
+            date_column: model.date_column,
+            date_format: model.date_format,
+            amount_column: model.amount_column,
+            debit_column: model.debit_column,
+            credit_column: model.credit_column,
+            merchant_column: model.merchant_column,
+            note_column: model.note_column,
+            decimal_separator: model.decimal_separator,
+            created_at: model.created_at.with_timezone(&Utc),
+            updated_at: model.updated_at.with_timezone(&Utc),
+        }
+    }
+}
+
+fn normalize_column(column: Option<String>) -> Option<String> {
+    column
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+}
+
+fn validate_profile(profile: &import_profile::ActiveModel) -> Result<(), ServiceError> {
+    let name = profile.name.as_ref();
+    if name.trim().is_empty() {
+        return Err(ServiceError::Validation("Profile name cannot be empty".to_string()));
+    }
+
+    let delimiter = profile.delimiter.as_ref();
+    if delimiter.len() != 1 || !delimiter.is_ascii() {
+        return Err(ServiceError::Validation(
+            "Delimiter must be a single ASCII character".to_string(),
+        ));
+    }
+
+    let separator = profile.decimal_separator.as_ref();
+    if separator != "." && separator != "," {
+        return Err(ServiceError::Validation(
+            "Decimal separator must be '.' or ','".to_string(),
+        ));
+    }
+
+    if separator == delimiter {
+        return Err(ServiceError::Validation(
+            "Decimal separator cannot equal the delimiter".to_string(),
+        ));
+    }
+
+    if profile.date_column.as_ref().trim().is_empty() {
+        return Err(ServiceError::Validation("Date column cannot be empty".to_string()));
+    }
+
+    let date_format = profile.date_format.as_ref();
+    if date_format.trim().is_empty()
+        || StrftimeItems::new(date_format).any(|item| matches!(item, Item::Error))
+    {
+        return Err(ServiceError::Validation(format!(
+            "Invalid date format: {}",
+            date_format
+        )));
+    }
+
+    let has_amount = profile.amount_column.as_ref().is_some();
+    let has_debit = profile.debit_column.as_ref().is_some();
+    let has_credit = profile.credit_column.as_ref().is_some();
+
+    match (has_amount, has_debit, has_credit) {
+        (true, false, false) | (false, true, true) => Ok(()),
+        _ => Err(ServiceError::Validation(
+            "Profile must map either amount_column or both debit_column and credit_column"
+                .to_string(),
+        )),
+    }
+}
+
+pub(crate) async fn load_owned_profile(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    profile_id: Uuid,
+) -> Result<import_profile::Model, ServiceError> {
+    let profile = ImportProfile::find_by_id(profile_id)
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound)?;
+
+    if profile.user_id != user_id {
+        return Err(ServiceError::Forbidden);
+    }
+
+    Ok(profile)
+}
+
+pub async fn create_profile(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: CreateImportProfileRequest,
+) -> Result<ImportProfileResponse, ServiceError> {
+    load_owned_account(db, user_id, req.account_id).await?;
+
+    let now = Utc::now().into();
+    let profile = import_profile::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        account_id: Set(req.account_id),
+        name: Set(req.name.trim().to_string()),
+        delimiter: Set(req.delimiter.unwrap_or_else(|| ",".to_string())),
+        has_header: Set(req.has_header.unwrap_or(true)),
+        date_column: Set(req.date_column.trim().to_string()),
+        date_format: Set(req.date_format.trim().to_string()),
+        amount_column: Set(normalize_column(req.amount_column)),
+        debit_column: Set(normalize_column(req.debit_column)),
+        credit_column: Set(normalize_column(req.credit_column)),
+        merchant_column: Set(normalize_column(req.merchant_column)),
+        note_column: Set(normalize_column(req.note_column)),
+        decimal_separator: Set(req.decimal_separator.unwrap_or_else(|| ".".to_string())),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    validate_profile(&profile)?;
+
+    match profile.insert(db).await {
+        Ok(model) => Ok(ImportProfileResponse::from(model)),
+        Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => Err(
+            ServiceError::Conflict("Import profile with same name already exists".to_string()),
+        ),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn get_profile(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    profile_id: Uuid,
+) -> Result<ImportProfileResponse, ServiceError> {
+    let profile = load_owned_profile(db, user_id, profile_id).await?;
+    Ok(ImportProfileResponse::from(profile))
+}
+
+pub async fn list_profiles(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<ImportProfileResponse>, ServiceError> {
+    let profiles = ImportProfile::find()
+        .filter(import_profile::Column::UserId.eq(user_id))
+        .order_by_asc(import_profile::Column::Name)
+        .all(db)
+        .await?;
+
+    Ok(profiles.into_iter().map(ImportProfileResponse::from).collect())
+}
+
+pub async fn update_profile(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    profile_id: Uuid,
+    req: UpdateImportProfileRequest,
+) -> Result<ImportProfileResponse, ServiceError> {
+    let profile = load_owned_profile(db, user_id, profile_id).await?;
+
+    if let Some(account_id) = req.account_id {
+        load_owned_account(db, user_id, account_id).await?;
+    }
+
+    let mut active: import_profile::ActiveModel = profile.into();
+
+    if let Some(name) = req.name {
+        active.name = Set(name.trim().to_string());
+    }
+    if let Some(account_id) = req.account_id {
+        active.account_id = Set(account_id);
+    }
+    if let Some(delimiter) = req.delimiter {
+        active.delimiter = Set(delimiter);
+    }
+    if let Some(has_header) = req.has_header {
+        active.has_header = Set(has_header);
+    }
+    if let Some(date_column) = req.date_column {
+        active.date_column = Set(date_column.trim().to_string());
+    }
+    if let Some(date_format) = req.date_format {
+        active.date_format = Set(date_format.trim().to_string());
+    }
+    if let Some(column) = req.amount_column {
+        active.amount_column = Set(normalize_column(column));
+    }
+    if let Some(column) = req.debit_column {
+        active.debit_column = Set(normalize_column(column));
+    }
+    if let Some(column) = req.credit_column {
+        active.credit_column = Set(normalize_column(column));
+    }
+    if let Some(column) = req.merchant_column {
+        active.merchant_column = Set(normalize_column(column));
+    }
+    if let Some(column) = req.note_column {
+        active.note_column = Set(normalize_column(column));
+    }
+    if let Some(separator) = req.decimal_separator {
+        active.decimal_separator = Set(separator);
+    }
+    active.updated_at = Set(Utc::now().into());
+
+    validate_profile(&active)?;
+
+    match active.update(db).await {
+        Ok(model) => Ok(ImportProfileResponse::from(model)),
+        Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => Err(
+            ServiceError::Conflict("Import profile with same name already exists".to_string()),
+        ),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn delete_profile(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    profile_id: Uuid,
+) -> Result<(), ServiceError> {
+    let profile = load_owned_profile(db, user_id, profile_id).await?;
+
+    let active: import_profile::ActiveModel = profile.into();
+    active.delete(db).await?;
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use super::{parse_amount, parse_date, LineError, StatementLine};
+
+pub const DEFAULT_DATE_FORMAT: &str = "%m/%d/%Y";
+
+#[derive(Default)]
+struct Record {
+    line: usize,
+    date: Option<String>,
+    amount: Option<String>,
+    payee: Option<String>,
+    memo: Option<String>,
+    category: Option<String>,
+}
+
+impl Record {
+    fn is_empty(&self) -> bool {
+        self.date.is_none()
+            && self.amount.is_none()
+            && self.payee.is_none()
+            && self.memo.is_none()
+            && self.category.is_none()
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Whether the year field of `raw` has at most two digits, as in Quicken's
+/// `3/ 1'26`. chrono's `%Y` would read such a year as 0026.
+fn has_short_year(raw: &str, format: &str) -> bool {
+    let Some(position) = format
+        .split('%')
+        .skip(1)
+        .position(|spec| spec.starts_with('Y'))
+    else {
+        return false;
+    };
+    raw.split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .nth(position)
+        .is_some_and(|year| year.len() <= 2)
+}
+
+fn parse_qif_date(raw: &str, format: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    let normalized = raw.replace('\'', "/").replace(' ', "");
+    if has_short_year(&normalized, format) {
+        let short = format.replace("%Y", "%y");
+        return parse_date(&normalized, &short).or_else(|_| parse_date(&normalized, format));
+    }
+    parse_date(&normalized, format)
+}
+
+fn parse_record(record: Record, date_format: &str) -> Result<StatementLine, LineError> {
+    let line = record.line;
+    let fail = |error: String| LineError { line, error };
+
+    let raw_date = record.date.ok_or_else(|| fail("Missing date".to_string()))?;
+    let occurred_at = parse_qif_date(&raw_date, date_format).map_err(fail)?;
+    let raw_amount = record
+        .amount
+        .ok_or_else(|| fail("Missing amount".to_string()))?;
+    let amount = parse_amount(&raw_amount, '.').map_err(fail)?;
+
+    let category = record
+        .category
+        .filter(|c| !(c.starts_with('[') && c.ends_with(']')));
+
+    Ok(StatementLine {
+        line,
+        occurred_at,
+        amount,
+        merchant: record.payee,
+        note: record.memo,
+        category,
+        external_id: None,
+    })
+}
+
+pub fn parse(content: &str, date_format: &str) -> Vec<Result<StatementLine, LineError>> {
+    let mut lines = Vec::new();
+    let mut current = Record::default();
+
+    for (idx, raw) in content.lines().enumerate() {
+        let raw = raw.trim_end_matches('\r');
+        let Some(code) = raw.chars().next() else {
+            continue;
+        };
+        let value = &raw[code.len_utf8()..];
+
+        if current.is_empty() {
+            current.line = idx + 1;
+        }
+
+        match code {
+            '!' => continue,
+            '^' => {
+                let record = std::mem::take(&mut current);
+                if !record.is_empty() {
+                    lines.push(parse_record(record, date_format));
+                }
+            }
+            'D' => current.date = non_empty(value),
+            'T' | 'U' if current.amount.is_none() => current.amount = non_empty(value),
+            'P' => current.payee = non_empty(value),
+            'M' => current.memo = non_empty(value),
+            'L' => current.category = non_empty(value),
+            _ => {}
+        }
+    }
+
+    if !current.is_empty() {
+        lines.push(parse_record(current, date_format));
+    }
+
+    lines
+}
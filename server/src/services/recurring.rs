@@ -0,0 +1,1049 @@
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use rust_decimal::Decimal;
+use sea_orm::{
+    sea_query::{LockBehavior, LockType},
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection,
+    DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::entities::{prelude::*, recurring_occurrence, recurring_transaction};
+use crate::errors::ServiceError;
+use crate::services::tag;
+use crate::services::transaction::{self, CreateTransactionRequest};
+use crate::utils::nullable::double_option;
+
+const VALID_FREQUENCIES: &[&str] = &["weekly", "monthly", "yearly"];
+
+const DEFAULT_PREVIEW_COUNT: usize = 10;
+const MAX_PREVIEW_COUNT: usize = 100;
+
+/// Upper bound when walking a schedule to find a given date.
+const MAX_SCAN_OCCURRENCES: i32 = 5000;
+
+const STATUS_POSTED: &str = "posted";
+const STATUS_SKIPPED: &str = "skipped";
+const STATUS_POSTPONED: &str = "postponed";
+const STATUS_FAILED: &str = "failed";
+
+/// Failed occurrences are retried on later runs until they reach this many
+/// attempts.
+const MAX_ATTEMPTS: i32 = 5;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateRecurringRequest {
+    pub name: String,
+    pub from_account_id: Option<Uuid>,
+    pub to_account_id: Option<Uuid>,
+    pub txn_type: String,
+    pub amount: Decimal,
+    pub currency_code: String,
+    pub to_amount: Option<Decimal>,
+    pub to_currency_code: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub merchant: Option<String>,
+    pub note: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub frequency: String,
+    pub interval: Option<i32>,
+    pub day_of_month: Option<i16>,
+    pub weekday: Option<i16>,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub max_occurrences: Option<i32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateRecurringRequest {
+    pub name: Option<String>,
+    pub amount: Option<Decimal>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub to_amount: Option<Option<Decimal>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub category_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub merchant: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub note: Option<Option<String>>,
+    pub tags: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub end_date: Option<Option<NaiveDate>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub max_occurrences: Option<Option<i32>>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecurringResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub from_account_id: Option<Uuid>,
+    pub to_account_id: Option<Uuid>,
+    pub txn_type: String,
+    pub amount: Decimal,
+    pub currency_code: String,
+    pub to_amount: Option<Decimal>,
+    pub to_currency_code: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub merchant: Option<String>,
+    pub note: Option<String>,
+    pub tags: Vec<String>,
+    pub frequency: String,
+    pub interval: i32,
+    pub day_of_month: Option<i16>,
+    pub weekday: Option<i16>,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub max_occurrences: Option<i32>,
+    pub occurrence_count: i32,
+    pub next_occurrence: Option<NaiveDate>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<recurring_transaction::Model> for RecurringResponse {
+    fn from(model: recurring_transaction::Model) -> Self {
+        Self {
+            id: model.id,
+            tags: serde_json::from_value(model.tags).unwrap_or_default(),
+            name: model.name,
+            from_account_id: model.from_account_id,
+            to_account_id: model.to_account_id,
+            txn_type: model.txn_type,
+            amount: model.amount,
+            currency_code: model.currency_code,
+            to_amount: model.to_amount,
+            to_currency_code: model.to_currency_code,
+            category_id: model.category_id,
+            merchant: model.merchant,
+            note: model.note,
+            frequency: model.frequency,
+            interval: model.interval,
+            day_of_month: model.day_of_month,
+            weekday: model.weekday,
+            start_date: model.start_date,
+            end_date: model.end_date,
+            max_occurrences: model.max_occurrences,
+            occurrence_count: model.occurrence_count,
+            next_occurrence: model.next_occurrence,
+            active: model.active,
+            created_at: model.created_at.with_timezone(&Utc),
+            updated_at: model.updated_at.with_timezone(&Utc),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SkipOccurrenceRequest {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PostponeOccurrenceRequest {
+    pub date: NaiveDate,
+    pub to: NaiveDate,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OccurrenceResponse {
+    pub date: NaiveDate,
+    pub status: String,
+    pub postponed_to: Option<NaiveDate>,
+    pub transaction_id: Option<Uuid>,
+    pub error: Option<String>,
+}
+
+impl From<recurring_occurrence::Model> for OccurrenceResponse {
+    fn from(model: recurring_occurrence::Model) -> Self {
+        Self {
+            date: model.occurrence_date,
+            status: model.status,
+            postponed_to: model.postponed_to,
+            transaction_id: model.transaction_id,
+            error: model.error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunRecurringResponse {
+    pub posted: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Generates the n-th occurrence of a template. Occurrences are computed from
+/// the start date rather than the previous one, so month-end clamping never drifts.
+struct Schedule {
+    frequency: Frequency,
+    interval: u32,
+    day_of_month: Option<u32>,
+    weekday: Option<Weekday>,
+    start_date: NaiveDate,
+    end_date: Option<NaiveDate>,
+    max_occurrences: Option<i32>,
+}
+
+fn clamp_day(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    (1..=day.min(31))
+        .rev()
+        .find_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+}
+
+impl Schedule {
+    fn from_model(model: &recurring_transaction::Model) -> Self {
+        let frequency = match model.frequency.as_str() {
+            "weekly" => Frequency::Weekly,
+            "yearly" => Frequency::Yearly,
+            _ => Frequency::Monthly,
+        };
+
+        Self {
+            frequency,
+            interval: model.interval.max(1) as u32,
+            day_of_month: model.day_of_month.map(|d| d as u32),
+            weekday: model.weekday.and_then(|d| Weekday::try_from(d as u8).ok()),
+            start_date: model.start_date,
+            end_date: model.end_date,
+            max_occurrences: model.max_occurrences,
+        }
+    }
+
+    fn nth(&self, n: i32) -> Option<NaiveDate> {
+        if n < 0 || self.max_occurrences.is_some_and(|max| n >= max) {
+            return None;
+        }
+        let step = (n as u32).checked_mul(self.interval)?;
+
+        let date = match self.frequency {
+            Frequency::Weekly => {
+                let weekday = self.weekday.unwrap_or(self.start_date.weekday());
+                let offset = (7 + weekday.num_days_from_monday()
+                    - self.start_date.weekday().num_days_from_monday())
+                    % 7;
+                self.start_date
+                    .checked_add_signed(Duration::days(offset as i64 + step as i64 * 7))?
+            }
+            Frequency::Monthly => {
+                let day = self.day_of_month.unwrap_or(self.start_date.day());
+                let first = self.start_date.with_day(1)?;
+                let in_start_month = clamp_day(first.year(), first.month(), day)? < self.start_date;
+                let month = first.checked_add_months(Months::new(step + in_start_month as u32))?;
+                clamp_day(month.year(), month.month(), day)?
+            }
+            Frequency::Yearly => {
+                let year = self.start_date.year().checked_add(step as i32)?;
+                clamp_day(year, self.start_date.month(), self.start_date.day())?
+            }
+        };
+
+        match self.end_date {
+            Some(end) if date > end => None,
+            _ => Some(date),
+        }
+    }
+
+    /// Index of the scheduled occurrence on `date`, searching from `from`.
+    fn index_of(&self, date: NaiveDate, from: i32) -> Option<i32> {
+        (from..from.saturating_add(MAX_SCAN_OCCURRENCES))
+            .map_while(|n| self.nth(n).map(|d| (n, d)))
+            .take_while(|(_, d)| *d <= date)
+            .find(|(_, d)| *d == date)
+            .map(|(n, _)| n)
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, ServiceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ServiceError::Validation("Name cannot be empty".to_string()));
+    }
+    if name.chars().count() > 100 {
+        return Err(ServiceError::Validation(
+            "Name too long (max 100 chars)".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_limits(
+    start_date: NaiveDate,
+    end_date: Option<NaiveDate>,
+    max_occurrences: Option<i32>,
+) -> Result<(), ServiceError> {
+    if end_date.is_some_and(|end| end < start_date) {
+        return Err(ServiceError::Validation(
+            "end_date cannot be before start_date".to_string(),
+        ));
+    }
+    if max_occurrences.is_some_and(|max| max < 1) {
+        return Err(ServiceError::Validation(
+            "max_occurrences must be at least 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_schedule(req: &CreateRecurringRequest) -> Result<(), ServiceError> {
+    let frequency = req.frequency.as_str();
+    if !VALID_FREQUENCIES.contains(&frequency) {
+        return Err(ServiceError::Validation(format!(
+            "Invalid frequency: {}. Must be one of: {}",
+            req.frequency,
+            VALID_FREQUENCIES.join(", ")
+        )));
+    }
+
+    if req.interval.is_some_and(|i| !(1..=365).contains(&i)) {
+        return Err(ServiceError::Validation(
+            "interval must be between 1 and 365".to_string(),
+        ));
+    }
+
+    match req.day_of_month {
+        Some(_) if frequency != "monthly" => {
+            return Err(ServiceError::Validation(
+                "day_of_month only applies to monthly templates".to_string(),
+            ));
+        }
+        Some(day) if !(1..=31).contains(&day) => {
+            return Err(ServiceError::Validation(
+                "day_of_month must be between 1 and 31".to_string(),
+            ));
+        }
+        _ => {}
+    }
+
+    match req.weekday {
+        Some(_) if frequency != "weekly" => {
+            return Err(ServiceError::Validation(
+                "weekday only applies to weekly templates".to_string(),
+            ));
+        }
+        Some(day) if !(0..=6).contains(&day) => {
+            return Err(ServiceError::Validation(
+                "weekday must be between 0 (Monday) and 6 (Sunday)".to_string(),
+            ));
+        }
+        _ => {}
+    }
+
+    validate_limits(req.start_date, req.end_date, req.max_occurrences)
+}
+
+fn occurred_at(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN))
+}
+
+fn to_create_request(
+    template: &recurring_transaction::Model,
+    date: NaiveDate,
+) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        from_account_id: template.from_account_id,
+        to_account_id: template.to_account_id,
+        txn_type: template.txn_type.clone(),
+        amount: template.amount,
+        currency_code: template.currency_code.clone(),
+        to_amount: template.to_amount,
+        to_currency_code: template.to_currency_code.clone(),
+        settled_amount: None,
+        category: None,
+        category_id: template.category_id,
+        note: template.note.clone(),
+        occurred_at: occurred_at(date),
+        ref_transaction_id: None,
+        merchant: template.merchant.clone(),
+        external_id: None,
+        splits: None,
+        tags: serde_json::from_value(template.tags.clone()).ok(),
+        allow_duplicate: None,
+    }
+}
+
+async fn validate_template<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    user_id: Uuid,
+    template: &recurring_transaction::Model,
+) -> Result<(), ServiceError> {
+    let req = to_create_request(template, template.start_date);
+    transaction::validate_transaction_request(db, user_id, req).await
+}
+
+async fn load_owned_recurring<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    recurring_id: Uuid,
+) -> Result<recurring_transaction::Model, ServiceError> {
+    let recurring = RecurringTransaction::find_by_id(recurring_id)
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound)?;
+
+    if recurring.user_id != user_id {
+        return Err(ServiceError::Forbidden);
+    }
+
+    Ok(recurring)
+}
+
+pub async fn create_recurring(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: CreateRecurringRequest,
+) -> Result<RecurringResponse, ServiceError> {
+    let name = validate_name(&req.name)?;
+    validate_schedule(&req)?;
+    let tags = tag::normalize_tags(req.tags.as_deref().unwrap_or_default())?;
+
+    let now = Utc::now();
+    let mut model = recurring_transaction::Model {
+        id: Uuid::new_v4(),
+        user_id,
+        name,
+        from_account_id: req.from_account_id,
+        to_account_id: req.to_account_id,
+        txn_type: req.txn_type.trim().to_lowercase(),
+        amount: req.amount,
+        currency_code: req.currency_code.trim().to_uppercase(),
+        to_amount: req.to_amount,
+        to_currency_code: req.to_currency_code.map(|c| c.trim().to_uppercase()),
+        category_id: req.category_id,
+        merchant: req
+            .merchant
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty()),
+        note: req.note.filter(|n| !n.trim().is_empty()),
+        tags: serde_json::json!(tags),
+        frequency: req.frequency,
+        interval: req.interval.unwrap_or(1),
+        day_of_month: req.day_of_month,
+        weekday: req.weekday,
+        start_date: req.start_date,
+        end_date: req.end_date,
+        max_occurrences: req.max_occurrences,
+        occurrence_count: 0,
+        next_occurrence: None,
+        active: true,
+        created_at: now.into(),
+        updated_at: now.into(),
+    };
+    model.next_occurrence = Schedule::from_model(&model).nth(0);
+
+    validate_template(db, user_id, &model).await?;
+
+    let active = recurring_transaction::ActiveModel::from(model).reset_all();
+    let recurring = active.insert(db).await?;
+
+    Ok(RecurringResponse::from(recurring))
+}
+
+pub async fn get_recurring(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    recurring_id: Uuid,
+) -> Result<RecurringResponse, ServiceError> {
+    let recurring = load_owned_recurring(db, user_id, recurring_id).await?;
+    Ok(RecurringResponse::from(recurring))
+}
+
+pub async fn list_recurring(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<RecurringResponse>, ServiceError> {
+    let templates = RecurringTransaction::find()
+        .filter(recurring_transaction::Column::UserId.eq(user_id))
+        .order_by_asc(recurring_transaction::Column::Name)
+        .all(db)
+        .await?;
+
+    Ok(templates.into_iter().map(RecurringResponse::from).collect())
+}
+
+pub async fn update_recurring(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    recurring_id: Uuid,
+    req: UpdateRecurringRequest,
+) -> Result<RecurringResponse, ServiceError> {
+    let tx = db.begin().await?;
+    let mut model = load_owned_recurring(&tx, user_id, recurring_id).await?;
+    let was_active = model.active;
+
+    if let Some(name) = req.name {
+        model.name = validate_name(&name)?;
+    }
+    if let Some(amount) = req.amount {
+        model.amount = amount;
+    }
+    if let Some(to_amount) = req.to_amount {
+        model.to_amount = to_amount;
+    }
+    if let Some(category_id) = req.category_id {
+        model.category_id = category_id;
+    }
+    if let Some(merchant) = req.merchant {
+        model.merchant = merchant
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty());
+    }
+    if let Some(note) = req.note {
+        model.note = note.filter(|n| !n.trim().is_empty());
+    }
+    if let Some(tags) = req.tags {
+        model.tags = serde_json::json!(tag::normalize_tags(&tags)?);
+    }
+    if let Some(end_date) = req.end_date {
+        model.end_date = end_date;
+    }
+    if let Some(max_occurrences) = req.max_occurrences {
+        model.max_occurrences = max_occurrences;
+    }
+    if let Some(active) = req.active {
+        model.active = active;
+    }
+    validate_limits(model.start_date, model.end_date, model.max_occurrences)?;
+    validate_template(&tx, user_id, &model).await?;
+
+    // Occurrences missed while paused are not back-filled on resume.
+    let schedule = Schedule::from_model(&model);
+    if model.active && !was_active {
+        let today = Utc::now().date_naive();
+        while schedule
+            .nth(model.occurrence_count)
+            .is_some_and(|d| d < today)
+        {
+            model.occurrence_count += 1;
+        }
+    }
+    model.next_occurrence = schedule.nth(model.occurrence_count);
+    model.updated_at = Utc::now().into();
+
+    let active = recurring_transaction::ActiveModel::from(model).reset_all();
+    let recurring = active.update(&tx).await?;
+    tx.commit().await?;
+
+    Ok(RecurringResponse::from(recurring))
+}
+
+pub async fn delete_recurring(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    recurring_id: Uuid,
+) -> Result<(), ServiceError> {
+    let recurring = load_owned_recurring(db, user_id, recurring_id).await?;
+    let active: recurring_transaction::ActiveModel = recurring.into();
+    active.delete(db).await?;
+    Ok(())
+}
+
+async fn find_occurrence<C: ConnectionTrait>(
+    db: &C,
+    recurring_id: Uuid,
+    date: NaiveDate,
+) -> Result<Option<recurring_occurrence::Model>, ServiceError> {
+    Ok(RecurringOccurrence::find()
+        .filter(recurring_occurrence::Column::RecurringId.eq(recurring_id))
+        .filter(recurring_occurrence::Column::OccurrenceDate.eq(date))
+        .one(db)
+        .await?)
+}
+
+/// Records a skip or postpone for an occurrence that has not been posted yet,
+/// including one that failed to post.
+async fn set_exception(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    recurring_id: Uuid,
+    date: NaiveDate,
+    status: &str,
+    postponed_to: Option<NaiveDate>,
+) -> Result<OccurrenceResponse, ServiceError> {
+    let tx = db.begin().await?;
+    let recurring = load_owned_recurring(&tx, user_id, recurring_id).await?;
+    let now = Utc::now();
+
+    let occurrence = match find_occurrence(&tx, recurring_id, date).await? {
+        Some(existing) if existing.transaction_id.is_some() || existing.status == STATUS_POSTED => {
+            return Err(ServiceError::Conflict(format!(
+                "Occurrence on {} has already been posted",
+                date
+            )));
+        }
+        // Failed occurrences can still be skipped or moved; that starts their
+        // retries over.
+        Some(existing) => {
+            let mut active: recurring_occurrence::ActiveModel = existing.into();
+            active.status = Set(status.to_string());
+            active.postponed_to = Set(postponed_to);
+            active.error = Set(None);
+            active.attempts = Set(0);
+            active.updated_at = Set(now.into());
+            active.update(&tx).await?
+        }
+        None => {
+            let schedule = Schedule::from_model(&recurring);
+            if schedule
+                .index_of(date, recurring.occurrence_count)
+                .is_none()
+            {
+                return Err(ServiceError::Validation(format!(
+                    "{} is not an upcoming occurrence",
+                    date
+                )));
+            }
+            recurring_occurrence::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                recurring_id: Set(recurring_id),
+                occurrence_date: Set(date),
+                status: Set(status.to_string()),
+                postponed_to: Set(postponed_to),
+                transaction_id: Set(None),
+                error: Set(None),
+                attempts: Set(0),
+                created_at: Set(now.into()),
+                updated_at: Set(now.into()),
+            }
+            .insert(&tx)
+            .await?
+        }
+    };
+
+    tx.commit().await?;
+    Ok(OccurrenceResponse::from(occurrence))
+}
+
+pub async fn skip_occurrence(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    recurring_id: Uuid,
+    req: SkipOccurrenceRequest,
+) -> Result<OccurrenceResponse, ServiceError> {
+    set_exception(db, user_id, recurring_id, req.date, STATUS_SKIPPED, None).await
+}
+
+pub async fn postpone_occurrence(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    recurring_id: Uuid,
+    req: PostponeOccurrenceRequest,
+) -> Result<OccurrenceResponse, ServiceError> {
+    if req.to <= req.date {
+        return Err(ServiceError::Validation(
+            "Postponed date must be after the occurrence date".to_string(),
+        ));
+    }
+    set_exception(
+        db,
+        user_id,
+        recurring_id,
+        req.date,
+        STATUS_POSTPONED,
+        Some(req.to),
+    )
+    .await
+}
+
+/// Removes a skip or postpone so the occurrence posts on its scheduled date again.
+pub async fn restore_occurrence(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    recurring_id: Uuid,
+    date: NaiveDate,
+) -> Result<(), ServiceError> {
+    let recurring = load_owned_recurring(db, user_id, recurring_id).await?;
+    let occurrence = find_occurrence(db, recurring_id, date)
+        .await?
+        .ok_or(ServiceError::NotFound)?;
+
+    let pending = occurrence.transaction_id.is_none()
+        && (occurrence.status == STATUS_SKIPPED || occurrence.status == STATUS_POSTPONED);
+    let upcoming = recurring.next_occurrence.is_some_and(|next| date >= next);
+    if !pending || !upcoming {
+        return Err(ServiceError::Conflict(format!(
+            "Occurrence on {} has already been processed",
+            date
+        )));
+    }
+
+    let active: recurring_occurrence::ActiveModel = occurrence.into();
+    active.delete(db).await?;
+    Ok(())
+}
+
+pub async fn list_occurrences(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    recurring_id: Uuid,
+) -> Result<Vec<OccurrenceResponse>, ServiceError> {
+    load_owned_recurring(db, user_id, recurring_id).await?;
+
+    let occurrences = RecurringOccurrence::find()
+        .filter(recurring_occurrence::Column::RecurringId.eq(recurring_id))
+        .order_by_asc(recurring_occurrence::Column::OccurrenceDate)
+        .all(db)
+        .await?;
+
+    Ok(occurrences
+        .into_iter()
+        .map(OccurrenceResponse::from)
+        .collect())
+}
+
+/// Lists the next occurrences with any skip/postpone applied, including
+/// postponed ones that have not been posted yet.
+pub async fn preview_occurrences(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    recurring_id: Uuid,
+    count: Option<usize>,
+) -> Result<Vec<OccurrenceResponse>, ServiceError> {
+    let recurring = load_owned_recurring(db, user_id, recurring_id).await?;
+    let count = count
+        .unwrap_or(DEFAULT_PREVIEW_COUNT)
+        .clamp(1, MAX_PREVIEW_COUNT);
+
+    let mut exceptions: HashMap<NaiveDate, recurring_occurrence::Model> =
+        RecurringOccurrence::find()
+            .filter(recurring_occurrence::Column::RecurringId.eq(recurring_id))
+            .filter(recurring_occurrence::Column::TransactionId.is_null())
+            .filter(recurring_occurrence::Column::Status.is_in([STATUS_SKIPPED, STATUS_POSTPONED]))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|o| (o.occurrence_date, o))
+            .collect();
+
+    let schedule = Schedule::from_model(&recurring);
+    let mut preview: Vec<OccurrenceResponse> = (recurring.occurrence_count..)
+        .map_while(|n| schedule.nth(n))
+        .take(count)
+        .map(|date| match exceptions.remove(&date) {
+            Some(exception) => OccurrenceResponse::from(exception),
+            None => OccurrenceResponse {
+                date,
+                status: "scheduled".to_string(),
+                postponed_to: None,
+                transaction_id: None,
+                error: None,
+            },
+        })
+        .collect();
+
+    // Postponed occurrences whose scheduled date has already passed.
+    preview.extend(
+        exceptions
+            .into_values()
+            .filter(|o| o.status == STATUS_POSTPONED)
+            .filter(|o| {
+                recurring
+                    .next_occurrence
+                    .is_none_or(|next| o.occurrence_date < next)
+            })
+            .map(OccurrenceResponse::from),
+    );
+    preview.sort_by_key(|o| o.postponed_to.unwrap_or(o.date));
+
+    Ok(preview)
+}
+
+/// Occurrences of the user's active templates that will post on or before
+/// `end`, paired with the date they will post. Skips and postponements are
+/// applied; occurrences already due but not yet posted are placed on `start`.
+pub(crate) async fn scheduled_between<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<(recurring_transaction::Model, NaiveDate)>, ServiceError> {
+    let templates = RecurringTransaction::find()
+        .filter(recurring_transaction::Column::UserId.eq(user_id))
+        .filter(recurring_transaction::Column::Active.eq(true))
+        .all(db)
+        .await?;
+    if templates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut exceptions: HashMap<(Uuid, NaiveDate), recurring_occurrence::Model> =
+        RecurringOccurrence::find()
+            .filter(
+                recurring_occurrence::Column::RecurringId
+                    .is_in(templates.iter().map(|t| t.id).collect::<Vec<_>>()),
+            )
+            .filter(recurring_occurrence::Column::TransactionId.is_null())
+            .filter(recurring_occurrence::Column::Status.is_in([STATUS_SKIPPED, STATUS_POSTPONED]))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|o| ((o.recurring_id, o.occurrence_date), o))
+            .collect();
+
+    let mut scheduled = Vec::new();
+    for template in templates {
+        let mut dates = Vec::new();
+        let schedule = Schedule::from_model(&template);
+        let upcoming = (template.occurrence_count..)
+            .take(MAX_SCAN_OCCURRENCES as usize)
+            .map_while(|n| schedule.nth(n))
+            .take_while(|d| *d <= end);
+        for date in upcoming {
+            match exceptions.remove(&(template.id, date)) {
+                Some(o) if o.status == STATUS_SKIPPED => {}
+                Some(o) => dates.extend(o.postponed_to),
+                None => dates.push(date),
+            }
+        }
+
+        // Postponed occurrences whose scheduled date has already passed.
+        dates.extend(
+            exceptions
+                .extract_if(|(id, date), o| {
+                    *id == template.id
+                        && o.status == STATUS_POSTPONED
+                        && template.next_occurrence.is_none_or(|next| *date < next)
+                })
+                .filter_map(|(_, o)| o.postponed_to),
+        );
+
+        for date in dates {
+            let date = date.max(start);
+            if date <= end {
+                scheduled.push((template.clone(), date));
+            }
+        }
+    }
+    scheduled.sort_by_key(|(_, date)| *date);
+
+    Ok(scheduled)
+}
+
+/// Occurrences due again: postponed ones whose new date has come and failed
+/// ones with attempts left.
+fn pending_occurrences(today: NaiveDate) -> Condition {
+    Condition::all()
+        .add(recurring_occurrence::Column::TransactionId.is_null())
+        .add(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(recurring_occurrence::Column::Status.eq(STATUS_POSTPONED))
+                        .add(recurring_occurrence::Column::PostponedTo.lte(today)),
+                )
+                .add(
+                    Condition::all()
+                        .add(recurring_occurrence::Column::Status.eq(STATUS_FAILED))
+                        .add(recurring_occurrence::Column::Attempts.lt(MAX_ATTEMPTS)),
+                ),
+        )
+}
+
+/// Creates the transaction for an occurrence. The duplicate check ignores the
+/// template's own earlier postings, which look alike by design.
+async fn create_posting(
+    tx: &DatabaseTransaction,
+    template: &recurring_transaction::Model,
+    mut req: CreateTransactionRequest,
+) -> Result<Uuid, ServiceError> {
+    let posted: Vec<Uuid> = RecurringOccurrence::find()
+        .select_only()
+        .column(recurring_occurrence::Column::TransactionId)
+        .filter(recurring_occurrence::Column::RecurringId.eq(template.id))
+        .filter(recurring_occurrence::Column::TransactionId.is_not_null())
+        .into_tuple::<Option<Uuid>>()
+        .all(tx)
+        .await?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let duplicate =
+        transaction::find_duplicate_for(tx, template.user_id, req.clone(), &posted).await?;
+    if let Some(duplicate) = duplicate {
+        return Err(ServiceError::Conflict(format!(
+            "Possible duplicate of transaction {}",
+            duplicate
+        )));
+    }
+
+    req.allow_duplicate = Some(true);
+    Ok(transaction::create_transaction(tx, template.user_id, req)
+        .await?
+        .id)
+}
+
+/// Posts one occurrence through `create_transaction`. Failures are recorded on
+/// the occurrence instead of aborting the run.
+async fn post_occurrence(
+    tx: &DatabaseTransaction,
+    template: &recurring_transaction::Model,
+    date: NaiveDate,
+    existing: Option<recurring_occurrence::Model>,
+) -> Result<bool, ServiceError> {
+    let post_date = existing
+        .as_ref()
+        .and_then(|o| o.postponed_to)
+        .unwrap_or(date);
+    let req = to_create_request(template, post_date);
+    let attempts = existing.as_ref().map_or(0, |o| o.attempts) + 1;
+
+    let savepoint = tx.begin().await?;
+    let (status, transaction_id, error) = match create_posting(&savepoint, template, req).await {
+        Ok(id) => {
+            savepoint.commit().await?;
+            (STATUS_POSTED, Some(id), None)
+        }
+        Err(e) => {
+            savepoint.rollback().await?;
+            let message = e.status_and_message().1;
+            warn!(
+                recurring_id = %template.id,
+                date = %date,
+                attempts,
+                error = %message,
+                "Failed to post recurring transaction"
+            );
+            (STATUS_FAILED, None, Some(message))
+        }
+    };
+
+    let now = Utc::now();
+    match existing {
+        Some(existing) => {
+            let mut active: recurring_occurrence::ActiveModel = existing.into();
+            active.status = Set(status.to_string());
+            active.transaction_id = Set(transaction_id);
+            active.error = Set(error);
+            active.attempts = Set(attempts);
+            active.updated_at = Set(now.into());
+            active.update(tx).await?;
+        }
+        None => {
+            recurring_occurrence::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                recurring_id: Set(template.id),
+                occurrence_date: Set(date),
+                status: Set(status.to_string()),
+                postponed_to: Set(None),
+                transaction_id: Set(transaction_id),
+                error: Set(error),
+                attempts: Set(attempts),
+                created_at: Set(now.into()),
+                updated_at: Set(now.into()),
+            }
+            .insert(tx)
+            .await?;
+        }
+    }
+
+    Ok(status == STATUS_POSTED)
+}
+
+/// Materializes every due occurrence of one template. The template row is
+/// locked so concurrent runners never post the same occurrence twice.
+async fn process_template(
+    db: &DatabaseConnection,
+    recurring_id: Uuid,
+    today: NaiveDate,
+    result: &mut RunRecurringResponse,
+) -> Result<(), ServiceError> {
+    let tx = db.begin().await?;
+    let template = RecurringTransaction::find_by_id(recurring_id)
+        .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+        .one(&tx)
+        .await?;
+    let Some(template) = template.filter(|t| t.active) else {
+        tx.rollback().await?;
+        return Ok(());
+    };
+
+    // Loaded first so occurrences failing in this run wait for the next one.
+    let pending = RecurringOccurrence::find()
+        .filter(recurring_occurrence::Column::RecurringId.eq(template.id))
+        .filter(pending_occurrences(today))
+        .order_by_asc(recurring_occurrence::Column::OccurrenceDate)
+        .all(&tx)
+        .await?;
+
+    let schedule = Schedule::from_model(&template);
+    let mut count = template.occurrence_count;
+    let mut next = template.next_occurrence;
+    while let Some(date) = next.filter(|d| *d <= today) {
+        if find_occurrence(&tx, template.id, date).await?.is_none() {
+            if post_occurrence(&tx, &template, date, None).await? {
+                result.posted += 1;
+            } else {
+                result.failed += 1;
+            }
+        }
+        count += 1;
+        next = schedule.nth(count);
+    }
+
+    for occurrence in pending {
+        let date = occurrence.occurrence_date;
+        if post_occurrence(&tx, &template, date, Some(occurrence)).await? {
+            result.posted += 1;
+        } else {
+            result.failed += 1;
+        }
+    }
+
+    if count != template.occurrence_count {
+        let mut active: recurring_transaction::ActiveModel = template.into();
+        active.occurrence_count = Set(count);
+        active.next_occurrence = Set(next);
+        active.updated_at = Set(Utc::now().into());
+        active.update(&tx).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Posts all occurrences due on or before `today`, optionally for one user only.
+pub async fn materialize_due(
+    db: &DatabaseConnection,
+    user_id: Option<Uuid>,
+    today: NaiveDate,
+) -> Result<RunRecurringResponse, ServiceError> {
+    let mut due = RecurringTransaction::find()
+        .select_only()
+        .column(recurring_transaction::Column::Id)
+        .filter(recurring_transaction::Column::Active.eq(true))
+        .filter(recurring_transaction::Column::NextOccurrence.lte(today));
+    if let Some(user_id) = user_id {
+        due = due.filter(recurring_transaction::Column::UserId.eq(user_id));
+    }
+    let mut ids: BTreeSet<Uuid> = due.into_tuple().all(db).await?.into_iter().collect();
+
+    let mut pending = RecurringOccurrence::find()
+        .select_only()
+        .column(recurring_occurrence::Column::RecurringId)
+        .inner_join(RecurringTransaction)
+        .filter(pending_occurrences(today));
+    if let Some(user_id) = user_id {
+        pending = pending.filter(recurring_transaction::Column::UserId.eq(user_id));
+    }
+    ids.extend(pending.into_tuple::<Uuid>().all(db).await?);
+
+    let mut result = RunRecurringResponse {
+        posted: 0,
+        failed: 0,
+    };
+    for id in ids {
+        process_template(db, id, today, &mut result).await?;
+    }
+
+    Ok(result)
+}
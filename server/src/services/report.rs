@@ -0,0 +1,1084 @@
+use chrono::{DateTime, Datelike, Days, Duration, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{
+    sea_query::{Alias, Expr},
+    ColumnTrait, Condition, DatabaseConnection, DbBackend, EntityTrait, FromQueryResult, JoinType,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, RelationTrait, Statement, Value,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::entities::{account, holdings, prelude::*, transaction};
+use crate::errors::ServiceError;
+use crate::services::account::{balance_history, LIABILITY_TYPES};
+use crate::services::category::CategoryTree;
+use crate::services::currency::Currencies;
+use crate::services::fx::{base_currency, percent_of, FxConverter, FxCost, RATE_SCALE};
+use crate::services::holdings::current_value;
+use crate::services::snapshot::snapshots_on;
+use crate::services::transaction::{filter_condition, TransactionQuery};
+
+const VALID_GROUP_BY: &[&str] = &["day", "week", "month", "year"];
+
+/// Upper bound on buckets in a single report, to keep `group_by=day` over
+/// long ranges in check.
+const MAX_BUCKETS: usize = 1000;
+
+const DEFAULT_TOP_MERCHANTS: usize = 10;
+const MAX_TOP_MERCHANTS: usize = 100;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SummaryQuery {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub group_by: Option<String>,
+    pub currency_code: Option<String>,
+    pub convert: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SummaryBucket {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub currency_code: String,
+    pub income: Decimal,
+    pub expense: Decimal,
+    pub net: Decimal,
+    pub savings_rate: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SummaryTotal {
+    pub currency_code: String,
+    pub income: Decimal,
+    pub expense: Decimal,
+    pub net: Decimal,
+    pub savings_rate: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SummaryReportResponse {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub group_by: String,
+    pub buckets: Vec<SummaryBucket>,
+    pub totals: Vec<SummaryTotal>,
+    pub converted: Option<ConvertedSummary>,
+}
+
+/// Summary buckets summed across currencies in the user's base currency,
+/// each bucket converted at the rate for its last day.
+#[derive(Debug, Serialize)]
+pub struct ConvertedSummary {
+    pub currency_code: String,
+    pub buckets: Vec<SummaryBucket>,
+    pub total: SummaryTotal,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct NetWorthQuery {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub interval: Option<String>,
+    pub convert: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NetWorthPoint {
+    pub date: NaiveDate,
+    pub currency_code: String,
+    pub assets: Decimal,
+    pub liabilities: Decimal,
+    pub net_worth: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NetWorthResponse {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub interval: String,
+    pub points: Vec<NetWorthPoint>,
+    pub converted: Option<Vec<NetWorthPoint>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FxCostQuery {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub convert: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FxCostTransaction {
+    pub transaction_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    pub from_account_id: Option<Uuid>,
+    pub to_account_id: Option<Uuid>,
+    pub currency_code: String,
+    pub amount: Decimal,
+    pub to_currency_code: String,
+    pub to_amount: Decimal,
+    pub implied_rate: Decimal,
+    pub fx_cost: Option<FxCost>,
+}
+
+/// Cross-currency transfers for one currency pair. Reference amounts and
+/// costs only cover the transfers that had a reference rate; `unpriced`
+/// counts the rest.
+#[derive(Debug, Default, Serialize)]
+pub struct FxCostPair {
+    pub currency_code: String,
+    pub to_currency_code: String,
+    pub count: usize,
+    pub unpriced: usize,
+    pub amount: Decimal,
+    pub to_amount: Decimal,
+    pub implied_rate: Decimal,
+    pub reference_amount: Decimal,
+    pub cost: Decimal,
+    pub cost_percent: Decimal,
+}
+
+/// Total conversion cost in the user's base currency, each transfer's cost
+/// converted at the rate for the day it happened.
+#[derive(Debug, Serialize)]
+pub struct ConvertedFxCost {
+    pub currency_code: String,
+    pub cost: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FxCostResponse {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub pairs: Vec<FxCostPair>,
+    pub transactions: Vec<FxCostTransaction>,
+    pub converted: Option<ConvertedFxCost>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BreakdownQuery {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub txn_type: Option<String>,
+    pub currency_code: Option<String>,
+    pub account_id: Option<Uuid>,
+    pub category: Option<String>,
+    pub keyword: Option<String>,
+    pub tags: Option<String>,
+    pub tag_mode: Option<String>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub top: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BreakdownTotal {
+    pub currency_code: String,
+    pub amount: Decimal,
+    pub previous_amount: Decimal,
+    pub change: Decimal,
+    pub change_percent: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryBreakdownItem {
+    pub currency_code: String,
+    pub category_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+    pub category: Option<String>,
+    pub amount: Decimal,
+    pub total: Decimal,
+    pub share: Decimal,
+    pub previous_total: Decimal,
+    pub change: Decimal,
+    pub change_percent: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MerchantBreakdownItem {
+    pub currency_code: String,
+    pub merchant: Option<String>,
+    pub amount: Decimal,
+    pub count: i64,
+    pub share: Decimal,
+    pub previous_amount: Decimal,
+    pub change: Decimal,
+    pub change_percent: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BreakdownReportResponse {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub previous_start: NaiveDate,
+    pub previous_end: NaiveDate,
+    pub txn_type: String,
+    pub totals: Vec<BreakdownTotal>,
+    pub categories: Vec<CategoryBreakdownItem>,
+    pub merchants: Vec<MerchantBreakdownItem>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct CategoryAmountRow {
+    currency_code: String,
+    category_id: Option<Uuid>,
+    amount: Decimal,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct MerchantAmountRow {
+    currency_code: String,
+    merchant: Option<String>,
+    amount: Decimal,
+    count: i64,
+}
+
+/// Per-currency amounts for one period, keyed by category and by merchant.
+#[derive(Default)]
+struct PeriodBreakdown {
+    categories: HashMap<(String, Option<Uuid>), Decimal>,
+    merchants: HashMap<(String, Option<String>), (Decimal, i64)>,
+}
+
+impl PeriodBreakdown {
+    fn total(&self, currency_code: &str) -> Decimal {
+        self.categories
+            .iter()
+            .filter(|((c, _), _)| c == currency_code)
+            .map(|(_, amount)| *amount)
+            .sum()
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct SummaryRow {
+    period_start: NaiveDate,
+    currency_code: String,
+    income: Decimal,
+    expense: Decimal,
+}
+
+fn bucket_start(group_by: &str, date: NaiveDate) -> NaiveDate {
+    match group_by {
+        "day" => date,
+        "week" => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        "year" => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap_or(date),
+        _ => NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap_or(date),
+    }
+}
+
+fn next_bucket(group_by: &str, start: NaiveDate) -> NaiveDate {
+    let next = match group_by {
+        "day" => start.checked_add_days(Days::new(1)),
+        "week" => start.checked_add_days(Days::new(7)),
+        "year" => start.checked_add_months(Months::new(12)),
+        _ => start.checked_add_months(Months::new(1)),
+    };
+    next.unwrap_or(NaiveDate::MAX)
+}
+
+/// Start of every bucket overlapping `[start, end]`.
+fn bucket_starts(
+    group_by: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<NaiveDate>, ServiceError> {
+    let mut buckets = Vec::new();
+    let mut cursor = bucket_start(group_by, start);
+    while cursor <= end {
+        if buckets.len() == MAX_BUCKETS {
+            return Err(ServiceError::Validation(format!(
+                "Range too large for {} buckets (max {})",
+                group_by, MAX_BUCKETS
+            )));
+        }
+        buckets.push(cursor);
+        cursor = next_bucket(group_by, cursor);
+    }
+    Ok(buckets)
+}
+
+pub(crate) fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN))
+}
+
+fn change_percent(current: Decimal, previous: Decimal) -> Option<Decimal> {
+    (!previous.is_zero())
+        .then(|| ((current - previous) * Decimal::ONE_HUNDRED / previous.abs()).round_dp(2))
+}
+
+fn share(amount: Decimal, total: Decimal) -> Decimal {
+    if total.is_zero() {
+        Decimal::ZERO
+    } else {
+        (amount * Decimal::ONE_HUNDRED / total).round_dp(2)
+    }
+}
+
+fn savings_rate(income: Decimal, net: Decimal) -> Option<Decimal> {
+    (income > Decimal::ZERO).then(|| (net * Decimal::ONE_HUNDRED / income).round_dp(2))
+}
+
+fn validate_range(start: NaiveDate, end: NaiveDate) -> Result<(), ServiceError> {
+    if start > end {
+        return Err(ServiceError::Validation(
+            "start must not be after end".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_group_by(group_by: Option<String>) -> Result<String, ServiceError> {
+    let group_by = group_by.unwrap_or_else(|| "month".to_string());
+    if !VALID_GROUP_BY.contains(&group_by.as_str()) {
+        return Err(ServiceError::Validation(format!(
+            "Invalid group_by: {}. Must be one of: {}",
+            group_by,
+            VALID_GROUP_BY.join(", ")
+        )));
+    }
+    Ok(group_by)
+}
+
+/// Income and expense per bucket and currency. Transfers are left out;
+/// refunds count against the type of the transaction they reference, signed
+/// by the direction the money moved.
+pub async fn get_summary(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    query: SummaryQuery,
+) -> Result<SummaryReportResponse, ServiceError> {
+    validate_range(query.start, query.end)?;
+    let group_by = validate_group_by(query.group_by)?;
+
+    let buckets = bucket_starts(&group_by, query.start, query.end)?;
+
+    let from = start_of_day(query.start);
+    let to = start_of_day(query.end.succ_opt().unwrap_or(query.end));
+    let mut values: Vec<Value> = vec![user_id.into(), from.into(), to.into()];
+    let currency_filter = match query.currency_code {
+        Some(code) => {
+            values.push(code.trim().to_uppercase().into());
+            "AND t.currency_code = $4"
+        }
+        None => "",
+    };
+
+    let unit = group_by.as_str();
+    let sql = format!(
+        r#"SELECT (date_trunc('{unit}', x.occurred_at AT TIME ZONE 'UTC'))::date AS period_start,
+                  x.currency_code,
+                  SUM(x.income) AS income,
+                  SUM(x.expense) AS expense
+           FROM (
+               SELECT t.occurred_at, t.currency_code,
+                      CASE WHEN t.txn_type = 'income' THEN t.amount ELSE 0 END AS income,
+                      CASE WHEN t.txn_type = 'expense' THEN t.amount ELSE 0 END AS expense
+               FROM "transaction" t
+               WHERE t.user_id = $1 AND t.txn_type IN ('income', 'expense')
+                 AND t.occurred_at >= $2 AND t.occurred_at < $3 {currency_filter}
+               UNION ALL
+               SELECT t.occurred_at, t.currency_code,
+                      CASE WHEN r.txn_type = 'income' THEN
+                          CASE WHEN t.to_account_id IS NULL THEN -t.amount ELSE t.amount END
+                      ELSE 0 END AS income,
+                      CASE WHEN r.txn_type = 'expense' THEN
+                          CASE WHEN t.to_account_id IS NULL THEN t.amount ELSE -t.amount END
+                      ELSE 0 END AS expense
+               FROM "transaction" t
+               JOIN "transaction" r ON r.id = t.ref_transaction_id
+               WHERE t.user_id = $1 AND t.txn_type = 'refund'
+                 AND r.txn_type IN ('income', 'expense')
+                 AND t.occurred_at >= $2 AND t.occurred_at < $3 {currency_filter}
+           ) x
+           GROUP BY 1, 2"#
+    );
+
+    let rows = SummaryRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        values,
+    ))
+    .all(db)
+    .await?;
+
+    let currencies: BTreeSet<String> = rows.iter().map(|r| r.currency_code.clone()).collect();
+    let amounts: BTreeMap<(NaiveDate, String), (Decimal, Decimal)> = rows
+        .into_iter()
+        .map(|r| ((r.period_start, r.currency_code), (r.income, r.expense)))
+        .collect();
+
+    let base = match query.convert {
+        Some(true) => Some(base_currency(db, user_id).await?),
+        _ => None,
+    };
+    let mut converter = FxConverter::new(Currencies::load(db, user_id).await?);
+    let mut converted = Vec::new();
+
+    let mut totals: BTreeMap<String, (Decimal, Decimal)> = BTreeMap::new();
+    let mut summary = Vec::with_capacity(buckets.len() * currencies.len());
+    for start in &buckets {
+        let period_end = next_bucket(&group_by, *start)
+            .pred_opt()
+            .unwrap_or(*start)
+            .min(query.end);
+        if let Some(base) = &base {
+            let (mut income, mut expense) = (Decimal::ZERO, Decimal::ZERO);
+            for currency in &currencies {
+                let Some((i, e)) = amounts.get(&(*start, currency.clone())).copied() else {
+                    continue;
+                };
+                income += converter.require(db, i, currency, base, period_end).await?;
+                expense += converter.require(db, e, currency, base, period_end).await?;
+            }
+            let net = income - expense;
+            converted.push(SummaryBucket {
+                period_start: (*start).max(query.start),
+                period_end,
+                currency_code: base.clone(),
+                income,
+                expense,
+                net,
+                savings_rate: savings_rate(income, net),
+            });
+        }
+        for currency in &currencies {
+            let (income, expense) = amounts
+                .get(&(*start, currency.clone()))
+                .copied()
+                .unwrap_or_default();
+            let total = totals.entry(currency.clone()).or_default();
+            total.0 += income;
+            total.1 += expense;
+
+            let net = income - expense;
+            summary.push(SummaryBucket {
+                period_start: (*start).max(query.start),
+                period_end,
+                currency_code: currency.clone(),
+                income,
+                expense,
+                net,
+                savings_rate: savings_rate(income, net),
+            });
+        }
+    }
+
+    let converted = base.map(|currency_code| {
+        let income: Decimal = converted.iter().map(|b| b.income).sum();
+        let expense: Decimal = converted.iter().map(|b| b.expense).sum();
+        let net = income - expense;
+        ConvertedSummary {
+            total: SummaryTotal {
+                currency_code: currency_code.clone(),
+                income,
+                expense,
+                net,
+                savings_rate: savings_rate(income, net),
+            },
+            currency_code,
+            buckets: converted,
+        }
+    });
+
+    Ok(SummaryReportResponse {
+        start: query.start,
+        end: query.end,
+        group_by,
+        buckets: summary,
+        totals: totals
+            .into_iter()
+            .map(|(currency_code, (income, expense))| {
+                let net = income - expense;
+                SummaryTotal {
+                    currency_code,
+                    income,
+                    expense,
+                    net,
+                    savings_rate: savings_rate(income, net),
+                }
+            })
+            .collect(),
+        converted,
+    })
+}
+
+/// Sums `txn_type` transactions matching `condition` in `[from, to)` by
+/// category and merchant. Split lines count against their own categories;
+/// refunds of matching transactions are netted against the original's
+/// category and merchant.
+async fn breakdown_period(
+    db: &DatabaseConnection,
+    condition: &Condition,
+    txn_type: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<PeriodBreakdown, ServiceError> {
+    let in_range = Condition::all()
+        .add(transaction::Column::OccurredAt.gte(start_of_day(from)))
+        .add(transaction::Column::OccurredAt.lt(start_of_day(to)));
+    let originals = Condition::all()
+        .add(condition.clone())
+        .add(transaction::Column::TxnType.eq(txn_type));
+    let refunds = Condition::all()
+        .add(transaction::Column::TxnType.eq("refund"))
+        .add(
+            transaction::Column::RefTransactionId.in_subquery(
+                Transaction::find()
+                    .select_only()
+                    .column(transaction::Column::Id)
+                    .filter(originals.clone())
+                    .into_query(),
+            ),
+        )
+        .add(in_range.clone());
+    // Money flowing back in reduces an expense; money flowing back out
+    // reduces an income.
+    let refund_amount = if txn_type == "income" {
+        r#"SUM(CASE WHEN "transaction"."to_account_id" IS NULL THEN -"transaction"."amount" ELSE "transaction"."amount" END)"#
+    } else {
+        r#"SUM(CASE WHEN "transaction"."to_account_id" IS NULL THEN "transaction"."amount" ELSE -"transaction"."amount" END)"#
+    };
+    let originals = originals.add(in_range);
+
+    let split_category =
+        r#"COALESCE("transaction_split"."category_id", "transaction"."category_id")"#;
+    let mut rows = Transaction::find()
+        .select_only()
+        .column(transaction::Column::CurrencyCode)
+        .column_as(Expr::cust(split_category), "category_id")
+        .column_as(
+            Expr::cust(r#"SUM(COALESCE("transaction_split"."amount", "transaction"."amount"))"#),
+            "amount",
+        )
+        .join(
+            JoinType::LeftJoin,
+            transaction::Relation::TransactionSplit.def(),
+        )
+        .filter(originals.clone())
+        .group_by(transaction::Column::CurrencyCode)
+        .group_by(Expr::cust(split_category))
+        .into_model::<CategoryAmountRow>()
+        .all(db)
+        .await?;
+    rows.extend(
+        Transaction::find()
+            .select_only()
+            .column(transaction::Column::CurrencyCode)
+            .column_as(Expr::cust(r#""original"."category_id""#), "category_id")
+            .column_as(Expr::cust(refund_amount), "amount")
+            .join_as(
+                JoinType::InnerJoin,
+                transaction::Relation::SelfRef.def(),
+                Alias::new("original"),
+            )
+            .filter(refunds.clone())
+            .group_by(transaction::Column::CurrencyCode)
+            .group_by(Expr::cust(r#""original"."category_id""#))
+            .into_model::<CategoryAmountRow>()
+            .all(db)
+            .await?,
+    );
+
+    let mut merchants = Transaction::find()
+        .select_only()
+        .column(transaction::Column::CurrencyCode)
+        .column(transaction::Column::Merchant)
+        .column_as(transaction::Column::Amount.sum(), "amount")
+        .column_as(transaction::Column::Id.count(), "count")
+        .filter(originals)
+        .group_by(transaction::Column::CurrencyCode)
+        .group_by(transaction::Column::Merchant)
+        .into_model::<MerchantAmountRow>()
+        .all(db)
+        .await?;
+    merchants.extend(
+        Transaction::find()
+            .select_only()
+            .column(transaction::Column::CurrencyCode)
+            .column_as(Expr::cust(r#""original"."merchant""#), "merchant")
+            .column_as(Expr::cust(refund_amount), "amount")
+            .column_as(Expr::cust("0::bigint"), "count")
+            .join_as(
+                JoinType::InnerJoin,
+                transaction::Relation::SelfRef.def(),
+                Alias::new("original"),
+            )
+            .filter(refunds)
+            .group_by(transaction::Column::CurrencyCode)
+            .group_by(Expr::cust(r#""original"."merchant""#))
+            .into_model::<MerchantAmountRow>()
+            .all(db)
+            .await?,
+    );
+
+    let mut period = PeriodBreakdown::default();
+    for row in rows {
+        *period
+            .categories
+            .entry((row.currency_code, row.category_id))
+            .or_default() += row.amount;
+    }
+    for row in merchants {
+        let entry = period
+            .merchants
+            .entry((row.currency_code, row.merchant))
+            .or_default();
+        entry.0 += row.amount;
+        entry.1 += row.count;
+    }
+    Ok(period)
+}
+
+/// Adds each category's direct amount to itself and all of its ancestors.
+fn rollup(
+    categories: &CategoryTree,
+    direct: &HashMap<(String, Option<Uuid>), Decimal>,
+) -> HashMap<(String, Option<Uuid>), Decimal> {
+    let mut totals: HashMap<(String, Option<Uuid>), Decimal> = HashMap::new();
+    for ((currency, category_id), amount) in direct {
+        let ids = match category_id {
+            Some(id) => categories.ancestors(*id),
+            None => Vec::new(),
+        };
+        if ids.is_empty() {
+            *totals.entry((currency.clone(), *category_id)).or_default() += *amount;
+        }
+        for id in ids {
+            *totals.entry((currency.clone(), Some(id))).or_default() += *amount;
+        }
+    }
+    totals
+}
+
+/// Breaks down one transaction type by category (with parent categories
+/// rolled up) and by merchant, compared to the equally long period just
+/// before `start`.
+pub async fn get_breakdown(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    query: BreakdownQuery,
+) -> Result<BreakdownReportResponse, ServiceError> {
+    validate_range(query.start, query.end)?;
+    let txn_type = query
+        .txn_type
+        .as_deref()
+        .unwrap_or("expense")
+        .trim()
+        .to_lowercase();
+    if txn_type != "expense" && txn_type != "income" {
+        return Err(ServiceError::Validation(format!(
+            "Invalid txn_type: {} (expected expense or income)",
+            txn_type
+        )));
+    }
+    let top = query
+        .top
+        .unwrap_or(DEFAULT_TOP_MERCHANTS)
+        .min(MAX_TOP_MERCHANTS);
+
+    let filter = TransactionQuery {
+        start: None,
+        end: None,
+        category: query.category,
+        account_id: query.account_id,
+        min_amount: query.min_amount,
+        max_amount: query.max_amount,
+        keyword: query.keyword,
+        txn_type: None,
+        tags: query.tags,
+        tag_mode: query.tag_mode,
+        limit: None,
+        offset: None,
+    };
+    let mut condition = filter_condition(db, user_id, &filter).await?;
+    if let Some(code) = &query.currency_code {
+        condition = condition.add(transaction::Column::CurrencyCode.eq(code.trim().to_uppercase()));
+    }
+
+    let days = (query.end - query.start).num_days() + 1;
+    let previous_end = query.start.pred_opt().unwrap_or(query.start);
+    let previous_start = query.start - Duration::days(days);
+    let end = query.end.succ_opt().unwrap_or(query.end);
+
+    let current = breakdown_period(db, &condition, &txn_type, query.start, end).await?;
+    let previous = breakdown_period(db, &condition, &txn_type, previous_start, query.start).await?;
+
+    let currencies: BTreeSet<String> = current
+        .categories
+        .keys()
+        .chain(previous.categories.keys())
+        .map(|(c, _)| c.clone())
+        .collect();
+    let totals: Vec<BreakdownTotal> = currencies
+        .iter()
+        .map(|currency| {
+            let amount = current.total(currency);
+            let previous_amount = previous.total(currency);
+            BreakdownTotal {
+                currency_code: currency.clone(),
+                amount,
+                previous_amount,
+                change: amount - previous_amount,
+                change_percent: change_percent(amount, previous_amount),
+            }
+        })
+        .collect();
+    let total_for = |currency: &str| {
+        totals
+            .iter()
+            .find(|t| t.currency_code == currency)
+            .map(|t| t.amount)
+            .unwrap_or_default()
+    };
+
+    let tree = CategoryTree::load(db, user_id).await?;
+    let current_totals = rollup(&tree, &current.categories);
+    let previous_totals = rollup(&tree, &previous.categories);
+    let keys: BTreeSet<&(String, Option<Uuid>)> = current_totals
+        .keys()
+        .chain(previous_totals.keys())
+        .collect();
+    let mut categories: Vec<CategoryBreakdownItem> = keys
+        .into_iter()
+        .map(|key| {
+            let (currency, category_id) = key;
+            let total = current_totals.get(key).copied().unwrap_or_default();
+            let previous_total = previous_totals.get(key).copied().unwrap_or_default();
+            CategoryBreakdownItem {
+                currency_code: currency.clone(),
+                category_id: *category_id,
+                parent_id: category_id
+                    .and_then(|id| tree.get(id))
+                    .and_then(|c| c.parent_id),
+                category: category_id.and_then(|id| tree.path(id)),
+                amount: current.categories.get(key).copied().unwrap_or_default(),
+                total,
+                share: share(total, total_for(currency)),
+                previous_total,
+                change: total - previous_total,
+                change_percent: change_percent(total, previous_total),
+            }
+        })
+        .collect();
+    categories.sort_by(|a, b| {
+        a.currency_code
+            .cmp(&b.currency_code)
+            .then(b.total.cmp(&a.total))
+            .then(a.category.cmp(&b.category))
+    });
+
+    let mut merchants: Vec<MerchantBreakdownItem> = current
+        .merchants
+        .iter()
+        .map(|(key, (amount, count))| {
+            let (currency, merchant) = key;
+            let previous_amount = previous
+                .merchants
+                .get(key)
+                .map(|(amount, _)| *amount)
+                .unwrap_or_default();
+            MerchantBreakdownItem {
+                currency_code: currency.clone(),
+                merchant: merchant.clone(),
+                amount: *amount,
+                count: *count,
+                share: share(*amount, total_for(currency)),
+                previous_amount,
+                change: *amount - previous_amount,
+                change_percent: change_percent(*amount, previous_amount),
+            }
+        })
+        .collect();
+    merchants.sort_by(|a, b| {
+        a.currency_code
+            .cmp(&b.currency_code)
+            .then(b.amount.cmp(&a.amount))
+            .then(a.merchant.cmp(&b.merchant))
+    });
+    let mut per_currency: HashMap<String, usize> = HashMap::new();
+    merchants.retain(|m| {
+        let seen = per_currency.entry(m.currency_code.clone()).or_default();
+        *seen += 1;
+        *seen <= top
+    });
+
+    Ok(BreakdownReportResponse {
+        start: query.start,
+        end: query.end,
+        previous_start,
+        previous_end,
+        txn_type,
+        totals,
+        categories,
+        merchants,
+    })
+}
+
+/// Assets, liabilities and net worth per currency at the end of each
+/// interval, read from balance snapshots where a day has them. Other days
+/// replay account balances from transactions and count holdings at their
+/// current value from the day they were added.
+pub async fn get_net_worth(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    query: NetWorthQuery,
+) -> Result<NetWorthResponse, ServiceError> {
+    validate_range(query.start, query.end)?;
+    let interval = validate_group_by(query.interval)?;
+    let dates: Vec<NaiveDate> = bucket_starts(&interval, query.start, query.end)?
+        .into_iter()
+        .map(|start| {
+            next_bucket(&interval, start)
+                .pred_opt()
+                .unwrap_or(start)
+                .min(query.end)
+        })
+        .collect();
+
+    let snapshots = snapshots_on(db, user_id, &dates).await?;
+    let mut totals: BTreeMap<(NaiveDate, String), (Decimal, Decimal)> = BTreeMap::new();
+    let mut add = |date: NaiveDate, currency_code: &str, amount: Decimal, liability: bool| {
+        let entry = totals.entry((date, currency_code.to_string())).or_default();
+        if liability {
+            entry.1 -= amount;
+        } else {
+            entry.0 += amount;
+        }
+    };
+
+    if !snapshots.is_empty() {
+        let liabilities: HashSet<Uuid> = Account::find()
+            .filter(account::Column::UserId.eq(user_id))
+            .filter(account::Column::Type.is_in(LIABILITY_TYPES.iter().copied()))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|a| a.id)
+            .collect();
+        for (date, rows) in &snapshots {
+            for row in rows {
+                match (row.holdings_id, row.balance, row.market_value) {
+                    (None, Some(balance), _) => add(
+                        *date,
+                        &row.currency_code,
+                        balance,
+                        liabilities.contains(&row.account_id),
+                    ),
+                    (Some(_), _, Some(value)) => add(*date, &row.currency_code, value, false),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // Days without snapshots are replayed from transactions.
+    let replay: Vec<NaiveDate> = dates
+        .iter()
+        .filter(|d| !snapshots.contains_key(d))
+        .copied()
+        .collect();
+    if !replay.is_empty() {
+        let history = balance_history(db, user_id, &replay).await?;
+        let holdings = Holdings::find()
+            .filter(holdings::Column::UserId.eq(user_id))
+            .all(db)
+            .await?;
+        let open_until: HashMap<Uuid, Option<NaiveDate>> = history
+            .iter()
+            .map(|(account, _)| {
+                (
+                    account.id,
+                    account
+                        .deleted_at
+                        .map(|d| d.with_timezone(&Utc).date_naive()),
+                )
+            })
+            .collect();
+
+        for (account, balances) in &history {
+            let liability = LIABILITY_TYPES.contains(&account.r#type.as_str());
+            for (date, balance) in replay.iter().zip(balances) {
+                if let Some(balance) = balance {
+                    add(*date, &account.currency_code, *balance, liability);
+                }
+            }
+        }
+        for holding in &holdings {
+            let added = holding.created_at.with_timezone(&Utc).date_naive();
+            let closed = open_until.get(&holding.account_id).copied().flatten();
+            let value = current_value(holding);
+            for date in &replay {
+                if *date >= added && closed.is_none_or(|d| d > *date) {
+                    add(*date, &holding.currency_code, value, false);
+                }
+            }
+        }
+    }
+
+    let converted = match query.convert {
+        Some(true) => {
+            let base = base_currency(db, user_id).await?;
+            let mut converter = FxConverter::new(Currencies::load(db, user_id).await?);
+            let mut by_date: BTreeMap<NaiveDate, (Decimal, Decimal)> =
+                dates.iter().map(|d| (*d, Default::default())).collect();
+            for ((date, currency), (assets, liabilities)) in &totals {
+                let entry = by_date.entry(*date).or_default();
+                entry.0 += converter
+                    .require(db, *assets, currency, &base, *date)
+                    .await?;
+                entry.1 += converter
+                    .require(db, *liabilities, currency, &base, *date)
+                    .await?;
+            }
+            Some(
+                by_date
+                    .into_iter()
+                    .map(|(date, (assets, liabilities))| NetWorthPoint {
+                        date,
+                        currency_code: base.clone(),
+                        assets,
+                        liabilities,
+                        net_worth: assets - liabilities,
+                    })
+                    .collect(),
+            )
+        }
+        _ => None,
+    };
+
+    Ok(NetWorthResponse {
+        start: query.start,
+        end: query.end,
+        interval,
+        converted,
+        points: totals
+            .into_iter()
+            .map(
+                |((date, currency_code), (assets, liabilities))| NetWorthPoint {
+                    date,
+                    currency_code,
+                    assets,
+                    liabilities,
+                    net_worth: assets - liabilities,
+                },
+            )
+            .collect(),
+    })
+}
+
+/// What cross-currency transfers lost against the reference rate on the day
+/// they happened, per transfer and per currency pair.
+pub async fn get_fx_cost(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    query: FxCostQuery,
+) -> Result<FxCostResponse, ServiceError> {
+    validate_range(query.start, query.end)?;
+
+    let transfers = Transaction::find()
+        .filter(transaction::Column::UserId.eq(user_id))
+        .filter(transaction::Column::ImpliedRate.is_not_null())
+        .filter(transaction::Column::OccurredAt.gte(start_of_day(query.start)))
+        .filter(
+            transaction::Column::OccurredAt
+                .lt(start_of_day(query.end.succ_opt().unwrap_or(query.end))),
+        )
+        .order_by_asc(transaction::Column::OccurredAt)
+        .order_by_asc(transaction::Column::Id)
+        .all(db)
+        .await?;
+
+    let mut converter = FxConverter::new(Currencies::load(db, user_id).await?);
+    let mut pairs: BTreeMap<(String, String), FxCostPair> = BTreeMap::new();
+    let mut transactions = Vec::new();
+    for txn in transfers {
+        let (Some(implied_rate), Some(to_amount), Some(to_currency_code)) =
+            (txn.implied_rate, txn.to_amount, txn.to_currency_code)
+        else {
+            continue;
+        };
+        let occurred_at = txn.occurred_at.with_timezone(&Utc);
+        let fx_cost = converter
+            .cost(
+                db,
+                txn.amount,
+                &txn.currency_code,
+                to_amount,
+                &to_currency_code,
+                occurred_at.date_naive(),
+            )
+            .await?;
+
+        let pair = pairs
+            .entry((txn.currency_code.clone(), to_currency_code.clone()))
+            .or_default();
+        pair.count += 1;
+        pair.amount += txn.amount;
+        pair.to_amount += to_amount;
+        match &fx_cost {
+            Some(cost) => {
+                pair.reference_amount += cost.reference_amount;
+                pair.cost += cost.cost;
+            }
+            None => pair.unpriced += 1,
+        }
+
+        transactions.push(FxCostTransaction {
+            transaction_id: txn.id,
+            occurred_at,
+            from_account_id: txn.from_account_id,
+            to_account_id: txn.to_account_id,
+            currency_code: txn.currency_code,
+            amount: txn.amount,
+            to_currency_code,
+            to_amount,
+            implied_rate,
+            fx_cost,
+        });
+    }
+
+    let converted = match query.convert {
+        Some(true) => {
+            let base = base_currency(db, user_id).await?;
+            let mut cost = Decimal::ZERO;
+            for txn in &transactions {
+                if let Some(fx_cost) = &txn.fx_cost {
+                    cost += converter
+                        .require(
+                            db,
+                            fx_cost.cost,
+                            &txn.to_currency_code,
+                            &base,
+                            txn.occurred_at.date_naive(),
+                        )
+                        .await?;
+                }
+            }
+            Some(ConvertedFxCost {
+                currency_code: base,
+                cost,
+            })
+        }
+        _ => None,
+    };
+
+    Ok(FxCostResponse {
+        start: query.start,
+        end: query.end,
+        pairs: pairs
+            .into_iter()
+            .map(|((currency_code, to_currency_code), pair)| FxCostPair {
+                currency_code,
+                to_currency_code,
+                implied_rate: (pair.to_amount / pair.amount).round_dp(RATE_SCALE),
+                cost_percent: percent_of(pair.cost, pair.reference_amount),
+                ..pair
+            })
+            .collect(),
+        transactions,
+        converted,
+    })
+}
@@ -0,0 +1,692 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::entities::{prelude::*, transaction, transaction_rule};
+use crate::errors::ServiceError;
+use crate::services::category::{kind_for_txn_type, CategoryTree};
+use crate::services::tag::{self, load_tags, set_transaction_tags};
+use crate::services::transaction::CreateTransactionRequest;
+
+const MAX_CONDITIONS: usize = 10;
+
+const APPLY_PAGE_SIZE: u64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleField {
+    Merchant,
+    Note,
+    Amount,
+    AccountId,
+    TxnType,
+    CurrencyCode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleOperator {
+    Contains,
+    Equals,
+    StartsWith,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleCondition {
+    pub field: RuleField,
+    pub op: RuleOperator,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleActions {
+    pub category_id: Option<Uuid>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRuleRequest {
+    pub name: String,
+    pub priority: Option<i32>,
+    pub enabled: Option<bool>,
+    pub conditions: Vec<RuleCondition>,
+    pub actions: RuleActions,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRuleRequest {
+    pub name: Option<String>,
+    pub priority: Option<i32>,
+    pub enabled: Option<bool>,
+    pub conditions: Option<Vec<RuleCondition>>,
+    pub actions: Option<RuleActions>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub priority: i32,
+    pub enabled: bool,
+    pub conditions: Vec<RuleCondition>,
+    pub actions: RuleActions,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<transaction_rule::Model> for RuleResponse {
+    fn from(model: transaction_rule::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            priority: model.priority,
+            enabled: model.enabled,
+            conditions: serde_json::from_value(model.conditions).unwrap_or_default(),
+            actions: serde_json::from_value(model.actions).unwrap_or_default(),
+            created_at: model.created_at.with_timezone(&Utc),
+            updated_at: model.updated_at.with_timezone(&Utc),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ApplyRulesRequest {
+    pub rule_ids: Option<Vec<Uuid>>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub account_id: Option<Uuid>,
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleApplyChange {
+    pub transaction_id: Uuid,
+    pub rule_ids: Vec<Uuid>,
+    pub category_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub added_tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApplyRulesResponse {
+    pub scanned: usize,
+    pub updated: usize,
+    pub dry_run: bool,
+    pub changes: Vec<RuleApplyChange>,
+}
+
+/// The transaction fields rules can match against.
+struct RuleTarget<'a> {
+    txn_type: &'a str,
+    amount: Decimal,
+    currency_code: &'a str,
+    from_account_id: Option<Uuid>,
+    to_account_id: Option<Uuid>,
+    merchant: Option<&'a str>,
+    note: Option<&'a str>,
+}
+
+#[derive(Debug, Default)]
+struct RuleOutcome {
+    rule_ids: Vec<Uuid>,
+    category_id: Option<Uuid>,
+    note: Option<String>,
+    added_tags: Vec<String>,
+}
+
+impl RuleOutcome {
+    fn is_empty(&self) -> bool {
+        self.category_id.is_none() && self.note.is_none() && self.added_tags.is_empty()
+    }
+}
+
+struct CompiledRule {
+    id: Uuid,
+    conditions: Vec<RuleCondition>,
+    actions: RuleActions,
+}
+
+pub(crate) struct RuleSet {
+    rules: Vec<CompiledRule>,
+    categories: CategoryTree,
+}
+
+fn is_blank(value: Option<&str>) -> bool {
+    value.is_none_or(|v| v.trim().is_empty())
+}
+
+fn text_matches(op: RuleOperator, actual: Option<&str>, expected: &str) -> bool {
+    let Some(actual) = actual else {
+        return false;
+    };
+    let actual = actual.to_lowercase();
+    let expected = expected.to_lowercase();
+
+    match op {
+        RuleOperator::Contains => actual.contains(&expected),
+        RuleOperator::Equals => actual.trim() == expected,
+        RuleOperator::StartsWith => actual.trim_start().starts_with(&expected),
+        _ => false,
+    }
+}
+
+fn condition_matches(condition: &RuleCondition, target: &RuleTarget) -> bool {
+    match condition.field {
+        RuleField::Merchant => text_matches(condition.op, target.merchant, &condition.value),
+        RuleField::Note => text_matches(condition.op, target.note, &condition.value),
+        RuleField::Amount => {
+            let Ok(expected) = Decimal::from_str(&condition.value) else {
+                return false;
+            };
+            match condition.op {
+                RuleOperator::Equals => target.amount == expected,
+                RuleOperator::Gt => target.amount > expected,
+                RuleOperator::Gte => target.amount >= expected,
+                RuleOperator::Lt => target.amount < expected,
+                RuleOperator::Lte => target.amount <= expected,
+                _ => false,
+            }
+        }
+        RuleField::AccountId => Uuid::parse_str(&condition.value)
+            .is_ok_and(|id| target.from_account_id == Some(id) || target.to_account_id == Some(id)),
+        RuleField::TxnType => target
+            .txn_type
+            .trim()
+            .eq_ignore_ascii_case(&condition.value),
+        RuleField::CurrencyCode => target
+            .currency_code
+            .trim()
+            .eq_ignore_ascii_case(&condition.value),
+    }
+}
+
+impl RuleSet {
+    pub(crate) async fn load<C: ConnectionTrait>(
+        db: &C,
+        user_id: Uuid,
+    ) -> Result<Self, ServiceError> {
+        let rules = TransactionRule::find()
+            .filter(transaction_rule::Column::UserId.eq(user_id))
+            .filter(transaction_rule::Column::Enabled.eq(true))
+            .order_by_asc(transaction_rule::Column::Priority)
+            .order_by_asc(transaction_rule::Column::CreatedAt)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|rule| {
+                Some(CompiledRule {
+                    id: rule.id,
+                    conditions: serde_json::from_value(rule.conditions).ok()?,
+                    actions: serde_json::from_value(rule.actions).ok()?,
+                })
+            })
+            .collect();
+
+        let categories = CategoryTree::load(db, user_id).await?;
+
+        Ok(Self { rules, categories })
+    }
+
+    fn only(mut self, rule_ids: &[Uuid]) -> Self {
+        self.rules.retain(|rule| rule_ids.contains(&rule.id));
+        self
+    }
+
+    pub(crate) fn category_path(&self, id: Uuid) -> Option<String> {
+        self.categories.path(id)
+    }
+
+    fn evaluate(
+        &self,
+        target: &RuleTarget,
+        has_category: bool,
+        has_note: bool,
+        tags: &[String],
+    ) -> RuleOutcome {
+        let kind = kind_for_txn_type(&target.txn_type.trim().to_lowercase());
+        let mut outcome = RuleOutcome::default();
+
+        for rule in &self.rules {
+            if !rule.conditions.iter().all(|c| condition_matches(c, target)) {
+                continue;
+            }
+
+            let mut changed = false;
+            if let Some(category_id) = rule.actions.category_id {
+                let usable = self
+                    .categories
+                    .get(category_id)
+                    .is_some_and(|c| c.kind == kind && !c.archived);
+                if usable && !has_category && outcome.category_id.is_none() {
+                    outcome.category_id = Some(category_id);
+                    changed = true;
+                }
+            }
+            if let Some(note) = &rule.actions.note {
+                if !has_note && outcome.note.is_none() {
+                    outcome.note = Some(note.clone());
+                    changed = true;
+                }
+            }
+            for name in &rule.actions.tags {
+                let key = name.to_lowercase();
+                let present = tags
+                    .iter()
+                    .chain(&outcome.added_tags)
+                    .any(|t| t.trim().trim_start_matches('#').to_lowercase() == key);
+                if !present {
+                    outcome.added_tags.push(name.clone());
+                    changed = true;
+                }
+            }
+
+            if changed {
+                outcome.rule_ids.push(rule.id);
+            }
+        }
+
+        outcome
+    }
+
+    /// Fills blank category, note and missing tags on a request from matching rules.
+    pub(crate) fn apply(&self, req: &mut CreateTransactionRequest) -> Vec<Uuid> {
+        if self.rules.is_empty() {
+            return Vec::new();
+        }
+
+        let target = RuleTarget {
+            txn_type: &req.txn_type,
+            amount: req.amount,
+            currency_code: &req.currency_code,
+            from_account_id: req.from_account_id,
+            to_account_id: req.to_account_id,
+            merchant: req.merchant.as_deref(),
+            note: req.note.as_deref(),
+        };
+        let has_category = req.category_id.is_some() || !is_blank(req.category.as_deref());
+        let has_note = !is_blank(req.note.as_deref());
+        let outcome = self.evaluate(
+            &target,
+            has_category,
+            has_note,
+            req.tags.as_deref().unwrap_or_default(),
+        );
+
+        if let Some(category_id) = outcome.category_id {
+            req.category = None;
+            req.category_id = Some(category_id);
+        }
+        if let Some(note) = outcome.note {
+            req.note = Some(note);
+        }
+        if !outcome.added_tags.is_empty() {
+            req.tags
+                .get_or_insert_with(Vec::new)
+                .extend(outcome.added_tags);
+        }
+
+        outcome.rule_ids
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, ServiceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ServiceError::Validation(
+            "Rule name cannot be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > 100 {
+        return Err(ServiceError::Validation(
+            "Rule name too long (max 100 chars)".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+async fn validate_conditions<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    conditions: Vec<RuleCondition>,
+) -> Result<Vec<RuleCondition>, ServiceError> {
+    if conditions.is_empty() {
+        return Err(ServiceError::Validation(
+            "Rule must have at least one condition".to_string(),
+        ));
+    }
+    if conditions.len() > MAX_CONDITIONS {
+        return Err(ServiceError::Validation(format!(
+            "Too many conditions (max {})",
+            MAX_CONDITIONS
+        )));
+    }
+
+    let mut validated = Vec::with_capacity(conditions.len());
+    for condition in conditions {
+        let value = condition.value.trim().to_string();
+        if value.is_empty() || value.chars().count() > 255 {
+            return Err(ServiceError::Validation(
+                "Condition value must be 1-255 chars".to_string(),
+            ));
+        }
+
+        let op_allowed = match condition.field {
+            RuleField::Merchant | RuleField::Note => matches!(
+                condition.op,
+                RuleOperator::Contains | RuleOperator::Equals | RuleOperator::StartsWith
+            ),
+            RuleField::Amount => !matches!(
+                condition.op,
+                RuleOperator::Contains | RuleOperator::StartsWith
+            ),
+            RuleField::AccountId | RuleField::TxnType | RuleField::CurrencyCode => {
+                condition.op == RuleOperator::Equals
+            }
+        };
+        if !op_allowed {
+            return Err(ServiceError::Validation(format!(
+                "Operator {:?} is not supported for field {:?}",
+                condition.op, condition.field
+            )));
+        }
+
+        let value = match condition.field {
+            RuleField::Amount => Decimal::from_str(&value)
+                .map_err(|_| ServiceError::Validation(format!("Invalid amount: {}", value)))?
+                .to_string(),
+            RuleField::AccountId => {
+                let account_id = Uuid::parse_str(&value).map_err(|_| {
+                    ServiceError::Validation(format!("Invalid account id: {}", value))
+                })?;
+                let account = Account::find_by_id(account_id).one(db).await?.ok_or(
+                    ServiceError::Validation(format!("Account {} not found", account_id)),
+                )?;
+                if account.user_id != user_id {
+                    return Err(ServiceError::Forbidden);
+                }
+                account_id.to_string()
+            }
+            RuleField::TxnType => value.to_lowercase(),
+            RuleField::CurrencyCode => value.to_uppercase(),
+            RuleField::Merchant | RuleField::Note => value,
+        };
+
+        validated.push(RuleCondition {
+            field: condition.field,
+            op: condition.op,
+            value,
+        });
+    }
+
+    Ok(validated)
+}
+
+async fn validate_actions<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    actions: RuleActions,
+) -> Result<RuleActions, ServiceError> {
+    if let Some(category_id) = actions.category_id {
+        let category =
+            Category::find_by_id(category_id)
+                .one(db)
+                .await?
+                .ok_or(ServiceError::Validation(format!(
+                    "Category {} not found",
+                    category_id
+                )))?;
+        if category.user_id != user_id {
+            return Err(ServiceError::Forbidden);
+        }
+    }
+
+    let tags = tag::normalize_tags(&actions.tags)?;
+    let note = actions
+        .note
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+
+    if actions.category_id.is_none() && tags.is_empty() && note.is_none() {
+        return Err(ServiceError::Validation(
+            "Rule must have at least one action".to_string(),
+        ));
+    }
+
+    Ok(RuleActions {
+        category_id: actions.category_id,
+        tags,
+        note,
+    })
+}
+
+fn to_json<T: Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).expect("rule definitions always serialize")
+}
+
+async fn load_owned_rule(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    rule_id: Uuid,
+) -> Result<transaction_rule::Model, ServiceError> {
+    let rule = TransactionRule::find_by_id(rule_id)
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound)?;
+
+    if rule.user_id != user_id {
+        return Err(ServiceError::Forbidden);
+    }
+
+    Ok(rule)
+}
+
+pub async fn create_rule(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: CreateRuleRequest,
+) -> Result<RuleResponse, ServiceError> {
+    let name = validate_name(&req.name)?;
+    let conditions = validate_conditions(db, user_id, req.conditions).await?;
+    let actions = validate_actions(db, user_id, req.actions).await?;
+
+    let now = Utc::now().into();
+    let rule = transaction_rule::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        name: Set(name),
+        priority: Set(req.priority.unwrap_or(100)),
+        enabled: Set(req.enabled.unwrap_or(true)),
+        conditions: Set(to_json(&conditions)),
+        actions: Set(to_json(&actions)),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    Ok(RuleResponse::from(rule.insert(db).await?))
+}
+
+pub async fn get_rule(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    rule_id: Uuid,
+) -> Result<RuleResponse, ServiceError> {
+    let rule = load_owned_rule(db, user_id, rule_id).await?;
+    Ok(RuleResponse::from(rule))
+}
+
+pub async fn list_rules(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<RuleResponse>, ServiceError> {
+    let rules = TransactionRule::find()
+        .filter(transaction_rule::Column::UserId.eq(user_id))
+        .order_by_asc(transaction_rule::Column::Priority)
+        .order_by_asc(transaction_rule::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    Ok(rules.into_iter().map(RuleResponse::from).collect())
+}
+
+pub async fn update_rule(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    rule_id: Uuid,
+    req: UpdateRuleRequest,
+) -> Result<RuleResponse, ServiceError> {
+    let existing = load_owned_rule(db, user_id, rule_id).await?;
+    let mut rule: transaction_rule::ActiveModel = existing.into();
+
+    if let Some(name) = req.name {
+        rule.name = Set(validate_name(&name)?);
+    }
+    if let Some(priority) = req.priority {
+        rule.priority = Set(priority);
+    }
+    if let Some(enabled) = req.enabled {
+        rule.enabled = Set(enabled);
+    }
+    if let Some(conditions) = req.conditions {
+        rule.conditions = Set(to_json(
+            &validate_conditions(db, user_id, conditions).await?,
+        ));
+    }
+    if let Some(actions) = req.actions {
+        rule.actions = Set(to_json(&validate_actions(db, user_id, actions).await?));
+    }
+    rule.updated_at = Set(Utc::now().into());
+
+    Ok(RuleResponse::from(rule.update(db).await?))
+}
+
+pub async fn delete_rule(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    rule_id: Uuid,
+) -> Result<(), ServiceError> {
+    let rule = load_owned_rule(db, user_id, rule_id).await?;
+    let active: transaction_rule::ActiveModel = rule.into();
+    active.delete(db).await?;
+    Ok(())
+}
+
+/// Re-runs rules over stored transactions, filling only blank fields.
+pub async fn apply_rules(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: ApplyRulesRequest,
+) -> Result<ApplyRulesResponse, ServiceError> {
+    let dry_run = req.dry_run.unwrap_or(false);
+    let tx = db.begin().await?;
+
+    let mut rules = RuleSet::load(&tx, user_id).await?;
+    if let Some(ids) = &req.rule_ids {
+        rules = rules.only(ids);
+    }
+
+    let mut condition = Condition::all().add(transaction::Column::UserId.eq(user_id));
+    if let Some(start) = req.start {
+        condition = condition.add(transaction::Column::OccurredAt.gte(start));
+    }
+    if let Some(end) = req.end {
+        condition = condition.add(transaction::Column::OccurredAt.lte(end));
+    }
+    if let Some(account_id) = req.account_id {
+        condition = condition.add(
+            Condition::any()
+                .add(transaction::Column::FromAccountId.eq(account_id))
+                .add(transaction::Column::ToAccountId.eq(account_id)),
+        );
+    }
+
+    let mut scanned = 0;
+    let mut changes = Vec::new();
+    let mut after: Option<Uuid> = None;
+    loop {
+        let mut query = Transaction::find().filter(condition.clone());
+        if let Some(last) = after {
+            query = query.filter(transaction::Column::Id.gt(last));
+        }
+        let page = query
+            .order_by_asc(transaction::Column::Id)
+            .limit(APPLY_PAGE_SIZE)
+            .all(&tx)
+            .await?;
+        let Some(last) = page.last().map(|t| t.id) else {
+            break;
+        };
+        after = Some(last);
+        scanned += page.len();
+
+        let mut tags = load_tags(&tx, page.iter().map(|t| t.id).collect()).await?;
+        for txn in page {
+            let existing_tags = tags.remove(&txn.id).unwrap_or_default();
+            let target = RuleTarget {
+                txn_type: &txn.txn_type,
+                amount: txn.amount,
+                currency_code: &txn.currency_code,
+                from_account_id: txn.from_account_id,
+                to_account_id: txn.to_account_id,
+                merchant: txn.merchant.as_deref(),
+                note: txn.note.as_deref(),
+            };
+            let outcome = rules.evaluate(
+                &target,
+                txn.category_id.is_some(),
+                !is_blank(txn.note.as_deref()),
+                &existing_tags,
+            );
+            if outcome.is_empty() {
+                continue;
+            }
+
+            if !dry_run {
+                if !outcome.added_tags.is_empty() {
+                    let mut all_tags = existing_tags;
+                    all_tags.extend(outcome.added_tags.iter().cloned());
+                    set_transaction_tags(&tx, user_id, txn.id, &all_tags).await?;
+                }
+                if outcome.category_id.is_some() || outcome.note.is_some() {
+                    let mut active: transaction::ActiveModel = txn.clone().into();
+                    if let Some(category_id) = outcome.category_id {
+                        active.category_id = Set(Some(category_id));
+                    }
+                    if let Some(note) = &outcome.note {
+                        active.note = Set(Some(note.clone()));
+                    }
+                    active.updated_at = Set(Utc::now().into());
+                    active.update(&tx).await?;
+                }
+            }
+
+            changes.push(RuleApplyChange {
+                transaction_id: txn.id,
+                rule_ids: outcome.rule_ids,
+                category_id: outcome.category_id,
+                note: outcome.note,
+                added_tags: outcome.added_tags,
+            });
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(ApplyRulesResponse {
+        scanned,
+        updated: changes.len(),
+        dry_run,
+        changes,
+    })
+}
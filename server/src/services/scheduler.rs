@@ -0,0 +1,37 @@
+use chrono::Utc;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+use crate::services::notify::Notifier;
+use crate::services::{budget, recurring, snapshot};
+
+/// Runs periodic background jobs until the process exits.
+pub async fn run(db: DatabaseConnection, notifier: Arc<dyn Notifier>, period: Duration) {
+    let mut ticker = tokio::time::interval(period);
+    loop {
+        ticker.tick().await;
+        let today = Utc::now().date_naive();
+
+        match recurring::materialize_due(&db, None, today).await {
+            Ok(result) if result.posted > 0 || result.failed > 0 => {
+                info!(
+                    posted = result.posted,
+                    failed = result.failed,
+                    "Materialized recurring transactions"
+                );
+            }
+            Ok(_) => {}
+            Err(e) => error!(error = %e, "Failed to materialize recurring transactions"),
+        }
+
+        if let Err(e) = budget::sweep_alerts(&db, notifier.as_ref(), today).await {
+            error!(error = %e, "Failed to check budget alerts");
+        }
+
+        if let Err(e) = snapshot::capture_snapshots(&db, today).await {
+            error!(error = %e, "Failed to capture balance snapshots");
+        }
+    }
+}
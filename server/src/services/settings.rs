@@ -0,0 +1,64 @@
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entities::{prelude::*, user};
+use crate::errors::ServiceError;
+use crate::services::currency::validate_currency_code;
+use crate::utils::nullable::double_option;
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSettingsRequest {
+    #[serde(default, deserialize_with = "double_option")]
+    pub base_currency: Option<Option<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SettingsResponse {
+    pub username: String,
+    pub base_currency: Option<String>,
+}
+
+impl From<user::Model> for SettingsResponse {
+    fn from(model: user::Model) -> Self {
+        Self {
+            username: model.username,
+            base_currency: model.base_currency,
+        }
+    }
+}
+
+async fn load_user(db: &DatabaseConnection, user_id: Uuid) -> Result<user::Model, ServiceError> {
+    User::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound)
+}
+
+pub async fn get_settings(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<SettingsResponse, ServiceError> {
+    let user = load_user(db, user_id).await?;
+    Ok(SettingsResponse::from(user))
+}
+
+pub async fn update_settings(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: UpdateSettingsRequest,
+) -> Result<SettingsResponse, ServiceError> {
+    let user = load_user(db, user_id).await?;
+    let mut active: user::ActiveModel = user.into();
+
+    if let Some(base_currency) = req.base_currency {
+        let base_currency = match base_currency {
+            Some(code) => Some(validate_currency_code(db, user_id, &code).await?),
+            None => None,
+        };
+        active.base_currency = Set(base_currency);
+    }
+
+    let updated = active.update(db).await?;
+    Ok(SettingsResponse::from(updated))
+}
@@ -0,0 +1,298 @@
+use chrono::{Days, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::entities::{account, balance_snapshot, holdings, prelude::*};
+use crate::errors::ServiceError;
+use crate::services::account::balance_history;
+use crate::services::holdings::current_value;
+
+const MAX_BACKFILL_DAYS: u64 = 3660;
+
+const INSERT_CHUNK_SIZE: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct BackfillSnapshotsRequest {
+    pub start: NaiveDate,
+    pub end: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackfillSnapshotsResponse {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub account_snapshots: usize,
+    pub holdings_snapshots: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SnapshotQuery {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub account_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SnapshotResponse {
+    pub snapshot_date: NaiveDate,
+    pub account_id: Uuid,
+    pub holdings_id: Option<Uuid>,
+    pub currency_code: String,
+    pub balance: Option<Decimal>,
+    pub quantity: Option<Decimal>,
+    pub market_value: Option<Decimal>,
+}
+
+impl From<balance_snapshot::Model> for SnapshotResponse {
+    fn from(model: balance_snapshot::Model) -> Self {
+        Self {
+            snapshot_date: model.snapshot_date,
+            account_id: model.account_id,
+            holdings_id: model.holdings_id,
+            currency_code: model.currency_code,
+            balance: model.balance,
+            quantity: model.quantity,
+            market_value: model.market_value,
+        }
+    }
+}
+
+/// Writes end-of-day snapshots for `dates`. Account balances are replayed
+/// from transactions and always overwritten. Holdings have no history, so
+/// they are recorded at their current value: replacing existing rows when
+/// `refresh_holdings` is set, otherwise only filling missing days.
+async fn write_snapshots<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    user_id: Uuid,
+    dates: &[NaiveDate],
+    refresh_holdings: bool,
+) -> Result<(usize, usize), ServiceError> {
+    let history = balance_history(db, user_id, dates).await?;
+    let holdings = Holdings::find()
+        .filter(holdings::Column::UserId.eq(user_id))
+        .all(db)
+        .await?;
+
+    let now = Utc::now().into();
+    let mut open: HashSet<(Uuid, NaiveDate)> = HashSet::new();
+    let mut account_rows = Vec::new();
+    for (account, balances) in &history {
+        for (date, balance) in dates.iter().zip(balances) {
+            let Some(balance) = balance else {
+                continue;
+            };
+            open.insert((account.id, *date));
+            account_rows.push(balance_snapshot::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                user_id: Set(user_id),
+                account_id: Set(account.id),
+                holdings_id: Set(None),
+                snapshot_date: Set(*date),
+                currency_code: Set(account.currency_code.clone()),
+                balance: Set(Some(*balance)),
+                quantity: Set(None),
+                market_value: Set(None),
+                created_at: Set(now),
+            });
+        }
+    }
+
+    let tx = db.begin().await?;
+    BalanceSnapshot::delete_many()
+        .filter(balance_snapshot::Column::UserId.eq(user_id))
+        .filter(balance_snapshot::Column::HoldingsId.is_null())
+        .filter(balance_snapshot::Column::SnapshotDate.is_in(dates.iter().copied()))
+        .exec(&tx)
+        .await?;
+
+    let existing: HashSet<(Option<Uuid>, NaiveDate)> = if refresh_holdings {
+        BalanceSnapshot::delete_many()
+            .filter(balance_snapshot::Column::UserId.eq(user_id))
+            .filter(balance_snapshot::Column::HoldingsId.is_not_null())
+            .filter(balance_snapshot::Column::SnapshotDate.is_in(dates.iter().copied()))
+            .exec(&tx)
+            .await?;
+        HashSet::new()
+    } else {
+        BalanceSnapshot::find()
+            .select_only()
+            .column(balance_snapshot::Column::HoldingsId)
+            .column(balance_snapshot::Column::SnapshotDate)
+            .filter(balance_snapshot::Column::UserId.eq(user_id))
+            .filter(balance_snapshot::Column::HoldingsId.is_not_null())
+            .filter(balance_snapshot::Column::SnapshotDate.is_in(dates.iter().copied()))
+            .into_tuple()
+            .all(&tx)
+            .await?
+            .into_iter()
+            .collect()
+    };
+
+    let mut holdings_rows = Vec::new();
+    for holding in &holdings {
+        let added = holding.created_at.with_timezone(&Utc).date_naive();
+        let value = current_value(holding);
+        for date in dates {
+            if *date < added
+                || !open.contains(&(holding.account_id, *date))
+                || existing.contains(&(Some(holding.id), *date))
+            {
+                continue;
+            }
+            holdings_rows.push(balance_snapshot::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                user_id: Set(user_id),
+                account_id: Set(holding.account_id),
+                holdings_id: Set(Some(holding.id)),
+                snapshot_date: Set(*date),
+                currency_code: Set(holding.currency_code.clone()),
+                balance: Set(None),
+                quantity: Set(Some(holding.quantity)),
+                market_value: Set(Some(value)),
+                created_at: Set(now),
+            });
+        }
+    }
+
+    let written = (account_rows.len(), holdings_rows.len());
+    for chunk in account_rows
+        .chunks(INSERT_CHUNK_SIZE)
+        .chain(holdings_rows.chunks(INSERT_CHUNK_SIZE))
+    {
+        BalanceSnapshot::insert_many(chunk.to_vec())
+            .exec(&tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(written)
+}
+
+async fn capture_user(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    today: NaiveDate,
+) -> Result<usize, ServiceError> {
+    let mut written = 0;
+    if let Some(yesterday) = today.pred_opt() {
+        let (accounts, holdings) = write_snapshots(db, user_id, &[yesterday], false).await?;
+        written += accounts + holdings;
+    }
+    let (accounts, holdings) = write_snapshots(db, user_id, &[today], true).await?;
+    Ok(written + accounts + holdings)
+}
+
+/// Snapshots every user's accounts and holdings for `today`, and settles
+/// yesterday's account balances now that the day is over.
+pub async fn capture_snapshots(
+    db: &DatabaseConnection,
+    today: NaiveDate,
+) -> Result<usize, ServiceError> {
+    let user_ids: Vec<Uuid> = Account::find()
+        .select_only()
+        .column(account::Column::UserId)
+        .distinct()
+        .filter(account::Column::DeletedAt.is_null())
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let mut written = 0;
+    for user_id in user_ids {
+        match capture_user(db, user_id, today).await {
+            Ok(count) => written += count,
+            Err(e) => warn!(user_id = %user_id, error = %e, "Failed to capture balance snapshots"),
+        }
+    }
+
+    Ok(written)
+}
+
+pub async fn backfill_snapshots(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: BackfillSnapshotsRequest,
+) -> Result<BackfillSnapshotsResponse, ServiceError> {
+    let today = Utc::now().date_naive();
+    let end = req.end.unwrap_or(today).min(today);
+    if req.start > end {
+        return Err(ServiceError::Validation(
+            "start must not be after end (or today)".to_string(),
+        ));
+    }
+
+    let mut dates = Vec::new();
+    let mut cursor = req.start;
+    while cursor <= end {
+        if dates.len() as u64 == MAX_BACKFILL_DAYS {
+            return Err(ServiceError::Validation(format!(
+                "Backfill range too large (max {} days)",
+                MAX_BACKFILL_DAYS
+            )));
+        }
+        dates.push(cursor);
+        cursor = match cursor.checked_add_days(Days::new(1)) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+
+    let (account_snapshots, holdings_snapshots) =
+        write_snapshots(db, user_id, &dates, false).await?;
+
+    Ok(BackfillSnapshotsResponse {
+        start: req.start,
+        end,
+        account_snapshots,
+        holdings_snapshots,
+    })
+}
+
+pub async fn list_snapshots(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    query: SnapshotQuery,
+) -> Result<Vec<SnapshotResponse>, ServiceError> {
+    let mut select = BalanceSnapshot::find()
+        .filter(balance_snapshot::Column::UserId.eq(user_id))
+        .filter(balance_snapshot::Column::SnapshotDate.gte(query.start))
+        .filter(balance_snapshot::Column::SnapshotDate.lte(query.end));
+    if let Some(account_id) = query.account_id {
+        select = select.filter(balance_snapshot::Column::AccountId.eq(account_id));
+    }
+
+    let snapshots = select
+        .order_by_asc(balance_snapshot::Column::SnapshotDate)
+        .order_by_asc(balance_snapshot::Column::AccountId)
+        .order_by_asc(balance_snapshot::Column::HoldingsId)
+        .all(db)
+        .await?;
+
+    Ok(snapshots.into_iter().map(SnapshotResponse::from).collect())
+}
+
+/// Snapshot rows for the given days, grouped by day.
+pub(crate) async fn snapshots_on<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    dates: &[NaiveDate],
+) -> Result<HashMap<NaiveDate, Vec<balance_snapshot::Model>>, ServiceError> {
+    let rows = BalanceSnapshot::find()
+        .filter(balance_snapshot::Column::UserId.eq(user_id))
+        .filter(balance_snapshot::Column::SnapshotDate.is_in(dates.iter().copied()))
+        .all(db)
+        .await?;
+
+    let mut by_date: HashMap<NaiveDate, Vec<balance_snapshot::Model>> = HashMap::new();
+    for row in rows {
+        by_date.entry(row.snapshot_date).or_default().push(row);
+    }
+    Ok(by_date)
+}
@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::{Expr, Func},
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, Set,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{prelude::*, tag, transaction_tags};
+use crate::errors::ServiceError;
+
+const MAX_TAGS_PER_TRANSACTION: usize = 20;
+
+#[derive(Debug, Serialize)]
+pub struct TagResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<tag::Model> for TagResponse {
+    fn from(model: tag::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            created_at: model.created_at.with_timezone(&Utc),
+        }
+    }
+}
+
+pub(crate) fn normalize_tags(tags: &[String]) -> Result<Vec<String>, ServiceError> {
+    let mut normalized: Vec<String> = Vec::new();
+    for raw in tags {
+        let name = raw.trim().trim_start_matches('#').trim();
+        if name.is_empty() {
+            return Err(ServiceError::Validation("Tag cannot be empty".to_string()));
+        }
+        if name.chars().count() > 64 {
+            return Err(ServiceError::Validation(
+                "Tag too long (max 64 chars)".to_string(),
+            ));
+        }
+        if name.contains(',') {
+            return Err(ServiceError::Validation("Tag cannot contain ','".to_string()));
+        }
+        let key = name.to_lowercase();
+        if !normalized.iter().any(|t| t.to_lowercase() == key) {
+            normalized.push(name.to_string());
+        }
+    }
+
+    if normalized.len() > MAX_TAGS_PER_TRANSACTION {
+        return Err(ServiceError::Validation(format!(
+            "Too many tags (max {})",
+            MAX_TAGS_PER_TRANSACTION
+        )));
+    }
+
+    Ok(normalized)
+}
+
+pub(crate) async fn find_tag_ids<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    names: &[String],
+) -> Result<Vec<Uuid>, ServiceError> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let lowered: Vec<String> = names.iter().map(|n| n.trim().to_lowercase()).collect();
+    let ids = Tag::find()
+        .filter(tag::Column::UserId.eq(user_id))
+        .filter(Expr::expr(Func::lower(Expr::col(tag::Column::Name))).is_in(lowered))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+
+    Ok(ids)
+}
+
+async fn resolve_tag<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    name: &str,
+) -> Result<Uuid, ServiceError> {
+    let existing = Tag::find()
+        .filter(tag::Column::UserId.eq(user_id))
+        .filter(Expr::expr(Func::lower(Expr::col(tag::Column::Name))).eq(name.to_lowercase()))
+        .one(db)
+        .await?;
+
+    if let Some(existing) = existing {
+        return Ok(existing.id);
+    }
+
+    let tag = tag::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        name: Set(name.to_string()),
+        created_at: Set(Utc::now().into()),
+    };
+
+    Ok(tag.insert(db).await?.id)
+}
+
+/// Replaces the tags on a transaction, creating tags that don't exist yet.
+pub(crate) async fn set_transaction_tags<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    transaction_id: Uuid,
+    tags: &[String],
+) -> Result<Vec<String>, ServiceError> {
+    let names = normalize_tags(tags)?;
+
+    TransactionTags::delete_many()
+        .filter(transaction_tags::Column::TransactionId.eq(transaction_id))
+        .exec(db)
+        .await?;
+
+    for name in &names {
+        let tag_id = resolve_tag(db, user_id, name).await?;
+        transaction_tags::ActiveModel {
+            transaction_id: Set(transaction_id),
+            tag_id: Set(tag_id),
+        }
+        .insert(db)
+        .await?;
+    }
+
+    let mut stored = load_tags(db, vec![transaction_id]).await?;
+    Ok(stored.remove(&transaction_id).unwrap_or_default())
+}
+
+pub(crate) async fn load_tags<C: ConnectionTrait>(
+    db: &C,
+    txn_ids: Vec<Uuid>,
+) -> Result<HashMap<Uuid, Vec<String>>, ServiceError> {
+    let mut grouped: HashMap<Uuid, Vec<String>> = HashMap::new();
+    if txn_ids.is_empty() {
+        return Ok(grouped);
+    }
+
+    let rows = TransactionTags::find()
+        .filter(transaction_tags::Column::TransactionId.is_in(txn_ids))
+        .find_also_related(Tag)
+        .order_by_asc(tag::Column::Name)
+        .all(db)
+        .await?;
+
+    for (link, tag) in rows {
+        if let Some(tag) = tag {
+            grouped.entry(link.transaction_id).or_default().push(tag.name);
+        }
+    }
+
+    Ok(grouped)
+}
+
+pub async fn list_tags(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<TagResponse>, ServiceError> {
+    let tags = Tag::find()
+        .filter(tag::Column::UserId.eq(user_id))
+        .order_by_asc(tag::Column::Name)
+        .all(db)
+        .await?;
+
+    Ok(tags.into_iter().map(TagResponse::from).collect())
+}
@@ -1,16 +1,25 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use rust_decimal::Decimal;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait,
-    Order, PaginatorTrait, QueryFilter, QueryOrder, Set,
+    sea_query::Query, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait,
+    DatabaseConnection, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
-use crate::entities::{prelude::*, transaction};
-use crate::errors::ServiceError;
+use crate::entities::{account, prelude::*, transaction, transaction_split, transaction_tags};
+use crate::errors::{BatchItemError, ServiceError};
+use crate::services::account::adjust_balance;
+use crate::services::category::{self, kind_for_txn_type, CategoryTree};
+use crate::services::currency::Currencies;
+use crate::services::fx::{self, FxConverter, FxCost};
+use crate::services::rule::RuleSet;
+use crate::services::tag::{self, load_tags, set_transaction_tags};
+use crate::utils::nullable::double_option;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct CreateTransactionRequest {
     pub from_account_id: Option<Uuid>,
     pub to_account_id: Option<Uuid>,
@@ -19,19 +28,73 @@ pub struct CreateTransactionRequest {
     pub currency_code: String,
     pub to_amount: Option<Decimal>,
     pub to_currency_code: Option<String>,
+    pub settled_amount: Option<Decimal>,
     pub category: Option<String>,
+    pub category_id: Option<Uuid>,
     pub note: Option<String>,
     pub occurred_at: DateTime<Utc>,
     pub ref_transaction_id: Option<Uuid>,
     pub merchant: Option<String>,
+    pub external_id: Option<String>,
+    pub splits: Option<Vec<TransactionSplitRequest>>,
+    pub tags: Option<Vec<String>>,
+    pub allow_duplicate: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransactionSplitRequest {
+    pub category: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub amount: Decimal,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
 pub struct UpdateTransactionRequest {
+    #[serde(default, deserialize_with = "double_option")]
+    pub from_account_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub to_account_id: Option<Option<Uuid>>,
+    pub txn_type: Option<String>,
+    pub amount: Option<Decimal>,
+    pub currency_code: Option<String>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub to_amount: Option<Option<Decimal>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub to_currency_code: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub settled_amount: Option<Option<Decimal>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub ref_transaction_id: Option<Option<Uuid>>,
     pub category: Option<String>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub category_id: Option<Option<Uuid>>,
     pub note: Option<String>,
     pub occurred_at: Option<DateTime<Utc>>,
     pub merchant: Option<String>,
+    pub splits: Option<Vec<TransactionSplitRequest>>,
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransactionSplitResponse {
+    pub id: Uuid,
+    pub category_id: Uuid,
+    pub category: String,
+    pub amount: Decimal,
+    pub note: Option<String>,
+}
+
+impl TransactionSplitResponse {
+    pub(crate) fn new(model: transaction_split::Model, categories: &CategoryTree) -> Self {
+        Self {
+            id: model.id,
+            category: categories.path(model.category_id).unwrap_or_default(),
+            category_id: model.category_id,
+            amount: model.amount,
+            note: model.note,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -45,17 +108,29 @@ pub struct TransactionResponse {
     pub currency_code: String,
     pub to_amount: Option<Decimal>,
     pub to_currency_code: Option<String>,
+    pub implied_rate: Option<Decimal>,
+    pub fx_cost: Option<FxCost>,
+    pub original_amount: Option<Decimal>,
+    pub original_currency_code: Option<String>,
+    pub category_id: Option<Uuid>,
     pub category: Option<String>,
     pub note: Option<String>,
     pub occurred_at: DateTime<Utc>,
     pub ref_transaction_id: Option<Uuid>,
     pub merchant: Option<String>,
+    pub external_id: Option<String>,
+    pub splits: Vec<TransactionSplitResponse>,
+    pub tags: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-impl From<transaction::Model> for TransactionResponse {
-    fn from(model: transaction::Model) -> Self {
+impl TransactionResponse {
+    pub(crate) fn new(
+        model: transaction::Model,
+        splits: Vec<transaction_split::Model>,
+        categories: &CategoryTree,
+    ) -> Self {
         Self {
             id: model.id,
             user_id: model.user_id,
@@ -66,18 +141,29 @@ impl From<transaction::Model> for TransactionResponse {
             currency_code: model.currency_code,
             to_amount: model.to_amount,
             to_currency_code: model.to_currency_code,
-            category: model.category,
+            implied_rate: model.implied_rate,
+            fx_cost: None,
+            original_amount: model.original_amount,
+            original_currency_code: model.original_currency_code,
+            category_id: model.category_id,
+            category: model.category_id.and_then(|id| categories.path(id)),
             note: model.note,
             occurred_at: model.occurred_at.with_timezone(&Utc),
             ref_transaction_id: model.ref_transaction_id,
             merchant: model.merchant,
+            external_id: model.external_id,
+            splits: splits
+                .into_iter()
+                .map(|line| TransactionSplitResponse::new(line, categories))
+                .collect(),
+            tags: Vec::new(),
             created_at: model.created_at.with_timezone(&Utc),
             updated_at: model.updated_at.with_timezone(&Utc),
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct TransactionQuery {
     pub start: Option<DateTime<Utc>>,
     pub end: Option<DateTime<Utc>>,
@@ -87,10 +173,16 @@ pub struct TransactionQuery {
     pub max_amount: Option<Decimal>,
     pub keyword: Option<String>,
     pub txn_type: Option<String>,
+    pub tags: Option<String>,
+    pub tag_mode: Option<String>,
     pub limit: Option<u64>,
     pub offset: Option<u64>,
 }
 
+const MAX_BATCH_SIZE: usize = 500;
+
+const DUPLICATE_WINDOW_DAYS: i64 = 3;
+
 const VALID_TXN_TYPES: &[&str] = &["expense", "income", "transfer", "refund", "adjustment"];
 
 fn validate_txn_type(t: &str) -> Result<(), ServiceError> {
@@ -100,17 +192,314 @@ fn validate_txn_type(t: &str) -> Result<(), ServiceError> {
     Ok(())
 }
 
-fn validate_currency_code(code: &str) -> Result<(), ServiceError> {
-    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+fn normalize_merchant(merchant: Option<&str>) -> String {
+    merchant
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .take(64)
+        .collect()
+}
+
+fn fingerprint(txn: &transaction::ActiveModel) -> Option<String> {
+    let (side, account_id) = match (txn.from_account_id.as_ref(), txn.to_account_id.as_ref()) {
+        (Some(id), _) => ("from", id),
+        (None, Some(id)) => ("to", id),
+        (None, None) => return None,
+    };
+
+    Some(format!(
+        "{}|{}|{}|{}|{}|{}",
+        txn.txn_type.as_ref(),
+        side,
+        account_id,
+        txn.amount.as_ref().normalize(),
+        txn.currency_code.as_ref(),
+        normalize_merchant(txn.merchant.as_ref().as_deref()),
+    ))
+}
+
+async fn find_duplicate<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    txn: &transaction::ActiveModel,
+    ignore: &[Uuid],
+) -> Result<Option<transaction::Model>, ServiceError> {
+    let Some(fingerprint) = txn.fingerprint.as_ref().clone() else {
+        return Ok(None);
+    };
+    let account_id = txn.from_account_id.as_ref().or(*txn.to_account_id.as_ref());
+    let external_id = txn.external_id.as_ref().clone();
+
+    let occurred_at = *txn.occurred_at.as_ref();
+    let window = Duration::days(DUPLICATE_WINDOW_DAYS);
+
+    let mut same_fingerprint = Condition::all()
+        .add(transaction::Column::Fingerprint.eq(fingerprint))
+        .add(transaction::Column::OccurredAt.between(occurred_at - window, occurred_at + window));
+    if let Some(ref id) = external_id {
+        same_fingerprint = same_fingerprint.add(
+            Condition::any()
+                .add(transaction::Column::ExternalId.is_null())
+                .add(transaction::Column::ExternalId.eq(id.clone())),
+        );
+    }
+
+    let mut condition = Condition::any().add(same_fingerprint);
+    if let (Some(id), Some(account_id)) = (external_id, account_id) {
+        condition = condition.add(
+            Condition::all()
+                .add(transaction::Column::ExternalId.eq(id))
+                .add(
+                    Condition::any()
+                        .add(transaction::Column::FromAccountId.eq(account_id))
+                        .add(transaction::Column::ToAccountId.eq(account_id)),
+                ),
+        );
+    }
+
+    let mut select = Transaction::find()
+        .filter(transaction::Column::UserId.eq(user_id))
+        .filter(transaction::Column::Id.ne(*txn.id.as_ref()))
+        .filter(condition);
+    if let Some(ref_id) = *txn.ref_transaction_id.as_ref() {
+        select = select.filter(transaction::Column::Id.ne(ref_id));
+    }
+    if !ignore.is_empty() {
+        select = select.filter(transaction::Column::Id.is_not_in(ignore.iter().copied()));
+    }
+
+    let duplicate = select
+        .order_by_asc(transaction::Column::OccurredAt)
+        .one(db)
+        .await?;
+
+    Ok(duplicate)
+}
+
+/// Checks `req` for a duplicate without creating it, ignoring matches
+/// against the transactions in `ignore`.
+pub(crate) async fn find_duplicate_for<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    req: CreateTransactionRequest,
+    ignore: &[Uuid],
+) -> Result<Option<Uuid>, ServiceError> {
+    let req = CreateTransactionRequest {
+        category: None,
+        category_id: None,
+        ..req
+    };
+    let txn = build_transaction(db, user_id, req, None).await?;
+    Ok(find_duplicate(db, user_id, &txn, ignore).await?.map(|dup| dup.id))
+}
+
+async fn resolve_category<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    kind: &str,
+    path: Option<&str>,
+    category_id: Option<Uuid>,
+    current: Option<Uuid>,
+) -> Result<Option<Uuid>, ServiceError> {
+    let path = path.map(str::trim).filter(|p| !p.is_empty());
+
+    match (path, category_id) {
+        (Some(_), Some(_)) => Err(ServiceError::Validation(
+            "Provide either category or category_id, not both".to_string(),
+        )),
+        (Some(path), None) => Ok(Some(category::resolve_path(db, user_id, kind, path).await?)),
+        (None, Some(id)) => {
+            category::verify_assignable(db, user_id, kind, id, current).await?;
+            Ok(Some(id))
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+fn validate_splits(
+    txn: &transaction::Model,
+    splits: &[TransactionSplitRequest],
+) -> Result<(), ServiceError> {
+    if splits.is_empty() {
+        return Ok(());
+    }
+
+    if txn.txn_type == "transfer" {
         return Err(ServiceError::Validation(
-            "Currency code must be 3 letters".to_string(),
+            "Transfer cannot have split lines".to_string(),
         ));
     }
+
+    let mut total = Decimal::ZERO;
+    for split in splits {
+        if split.amount <= Decimal::ZERO {
+            return Err(ServiceError::Validation(
+                "Split amount must be positive".to_string(),
+            ));
+        }
+        total += split.amount;
+    }
+
+    if total != txn.amount {
+        return Err(ServiceError::Validation(format!(
+            "Split amounts must sum to transaction amount ({} != {})",
+            total, txn.amount
+        )));
+    }
+
     Ok(())
 }
 
-async fn load_owned_transaction(
-    db: &DatabaseConnection,
+pub(crate) async fn load_splits<C: ConnectionTrait>(
+    db: &C,
+    txn_ids: Vec<Uuid>,
+) -> Result<HashMap<Uuid, Vec<transaction_split::Model>>, ServiceError> {
+    let mut grouped: HashMap<Uuid, Vec<transaction_split::Model>> = HashMap::new();
+    if txn_ids.is_empty() {
+        return Ok(grouped);
+    }
+
+    let splits = TransactionSplit::find()
+        .filter(transaction_split::Column::TransactionId.is_in(txn_ids))
+        .order_by_asc(transaction_split::Column::Position)
+        .all(db)
+        .await?;
+
+    for split in splits {
+        grouped.entry(split.transaction_id).or_default().push(split);
+    }
+
+    Ok(grouped)
+}
+
+async fn resolve_split_categories<C: ConnectionTrait>(
+    db: &C,
+    txn: &transaction::Model,
+    splits: &[TransactionSplitRequest],
+    current: &[Uuid],
+) -> Result<Vec<Uuid>, ServiceError> {
+    let kind = kind_for_txn_type(&txn.txn_type);
+
+    let mut ids = Vec::with_capacity(splits.len());
+    for split in splits {
+        let existing = split.category_id.filter(|id| current.contains(id));
+        let id = resolve_category(
+            db,
+            txn.user_id,
+            kind,
+            split.category.as_deref(),
+            split.category_id,
+            existing,
+        )
+        .await?
+        .ok_or(ServiceError::Validation(
+            "Split category cannot be empty".to_string(),
+        ))?;
+        ids.push(id);
+    }
+
+    Ok(ids)
+}
+
+async fn replace_splits<C: ConnectionTrait>(
+    db: &C,
+    txn: &transaction::Model,
+    splits: &[TransactionSplitRequest],
+    current: &[Uuid],
+) -> Result<Vec<transaction_split::Model>, ServiceError> {
+    validate_splits(txn, splits)?;
+    let category_ids = resolve_split_categories(db, txn, splits, current).await?;
+
+    TransactionSplit::delete_many()
+        .filter(transaction_split::Column::TransactionId.eq(txn.id))
+        .exec(db)
+        .await?;
+
+    let now = Utc::now().into();
+    let mut inserted = Vec::with_capacity(splits.len());
+    for (i, (split, category_id)) in splits.iter().zip(category_ids).enumerate() {
+        let line = transaction_split::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            transaction_id: Set(txn.id),
+            category_id: Set(category_id),
+            amount: Set(split.amount),
+            note: Set(split.note.clone()),
+            position: Set(i as i32),
+            created_at: Set(now),
+        };
+        inserted.push(line.insert(db).await?);
+    }
+
+    Ok(inserted)
+}
+
+async fn to_responses<C: ConnectionTrait>(
+    db: &C,
+    models: Vec<transaction::Model>,
+) -> Result<Vec<TransactionResponse>, ServiceError> {
+    let Some(user_id) = models.first().map(|m| m.user_id) else {
+        return Ok(Vec::new());
+    };
+    let ids: Vec<Uuid> = models.iter().map(|m| m.id).collect();
+    let mut splits = load_splits(db, ids.clone()).await?;
+    let mut tags = load_tags(db, ids).await?;
+    let categories = CategoryTree::load(db, user_id).await?;
+
+    let mut responses: Vec<TransactionResponse> = models
+        .into_iter()
+        .map(|model| {
+            let lines = splits.remove(&model.id).unwrap_or_default();
+            let model_tags = tags.remove(&model.id).unwrap_or_default();
+            let mut response = TransactionResponse::new(model, lines, &categories);
+            response.tags = model_tags;
+            response
+        })
+        .collect();
+    attach_fx_costs(db, &mut responses).await?;
+    Ok(responses)
+}
+
+/// Fills `fx_cost` on cross-currency transfers from the reference rate on
+/// the day they happened.
+async fn attach_fx_costs<C: ConnectionTrait>(
+    db: &C,
+    responses: &mut [TransactionResponse],
+) -> Result<(), ServiceError> {
+    let Some(user_id) = responses
+        .iter()
+        .find(|r| r.implied_rate.is_some())
+        .map(|r| r.user_id)
+    else {
+        return Ok(());
+    };
+    let mut converter = FxConverter::new(Currencies::load(db, user_id).await?);
+    for response in responses {
+        let (Some(to_amount), Some(to_currency)) =
+            (response.to_amount, response.to_currency_code.as_deref())
+        else {
+            continue;
+        };
+        if response.implied_rate.is_none() {
+            continue;
+        }
+        response.fx_cost = converter
+            .cost(
+                db,
+                response.amount,
+                &response.currency_code,
+                to_amount,
+                to_currency,
+                response.occurred_at.date_naive(),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+async fn load_owned_transaction<C: ConnectionTrait>(
+    db: &C,
     user_id: Uuid,
     txn_id: Uuid,
 ) -> Result<transaction::Model, ServiceError> {
@@ -126,11 +515,11 @@ async fn load_owned_transaction(
     Ok(txn)
 }
 
-async fn verify_account_ownership(
-    db: &DatabaseConnection,
+async fn verify_account_ownership<C: ConnectionTrait>(
+    db: &C,
     user_id: Uuid,
     account_id: Uuid,
-) -> Result<(), ServiceError> {
+) -> Result<account::Model, ServiceError> {
     let account = Account::find_by_id(account_id)
         .one(db)
         .await?
@@ -143,14 +532,217 @@ async fn verify_account_ownership(
         return Err(ServiceError::Forbidden);
     }
 
+    Ok(account)
+}
+
+fn check_account_currency(account: &account::Model, currency: &str) -> Result<(), ServiceError> {
+    if account.currency_code != currency {
+        return Err(ServiceError::Validation(format!(
+            "Currency {} does not match account {} currency {}",
+            currency, account.id, account.currency_code
+        )));
+    }
     Ok(())
 }
 
-pub async fn create_transaction(
-    db: &DatabaseConnection,
+/// The amount a non-transfer posts to its account, in the account's
+/// currency. Spending in another currency needs `settled_amount`; the
+/// amount as charged is kept as the original.
+struct Settlement {
+    amount: Decimal,
+    currency_code: String,
+    original_amount: Option<Decimal>,
+    original_currency_code: Option<String>,
+}
+
+fn settle(
+    account: &account::Model,
+    amount: Decimal,
+    currency: String,
+    settled_amount: Option<Decimal>,
+) -> Result<Settlement, ServiceError> {
+    match settled_amount {
+        None => {
+            if currency != account.currency_code {
+                return Err(ServiceError::Validation(format!(
+                    "Currency {} does not match account currency {}; pass settled_amount to record a foreign-currency transaction",
+                    currency, account.currency_code
+                )));
+            }
+            Ok(Settlement {
+                amount,
+                currency_code: currency,
+                original_amount: None,
+                original_currency_code: None,
+            })
+        }
+        Some(settled) => {
+            if currency == account.currency_code {
+                return Err(ServiceError::Validation(
+                    "settled_amount is only allowed when the currency differs from the account currency".to_string(),
+                ));
+            }
+            if settled <= Decimal::ZERO {
+                return Err(ServiceError::Validation(
+                    "Settled amount must be positive".to_string(),
+                ));
+            }
+            Ok(Settlement {
+                amount: settled,
+                currency_code: account.currency_code.clone(),
+                original_amount: Some(amount),
+                original_currency_code: Some(currency),
+            })
+        }
+    }
+}
+
+async fn apply_balance_effect<C: ConnectionTrait>(
+    db: &C,
+    txn: &transaction::Model,
+    reverse: bool,
+) -> Result<(), ServiceError> {
+    let sign = if reverse { Decimal::NEGATIVE_ONE } else { Decimal::ONE };
+
+    if let Some(from_id) = txn.from_account_id {
+        adjust_balance(db, from_id, -txn.amount * sign).await?;
+    }
+    if let Some(to_id) = txn.to_account_id {
+        let credited = txn.to_amount.unwrap_or(txn.amount);
+        adjust_balance(db, to_id, credited * sign).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn create_transaction<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
     user_id: Uuid,
     req: CreateTransactionRequest,
 ) -> Result<TransactionResponse, ServiceError> {
+    let tx = db.begin().await?;
+    let rules = RuleSet::load(&tx, user_id).await?;
+    let response = insert_transaction(&tx, user_id, req, &rules).await?;
+    tx.commit().await?;
+
+    Ok(response)
+}
+
+pub async fn create_transactions_batch(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    reqs: Vec<CreateTransactionRequest>,
+) -> Result<Vec<TransactionResponse>, ServiceError> {
+    if reqs.len() > MAX_BATCH_SIZE {
+        return Err(ServiceError::Validation(format!(
+            "Batch too large (max {} items)",
+            MAX_BATCH_SIZE
+        )));
+    }
+
+    let tx = db.begin().await?;
+    let responses = insert_transactions(&tx, user_id, reqs).await?;
+    tx.commit().await?;
+
+    Ok(responses)
+}
+
+pub(crate) async fn insert_transactions<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    user_id: Uuid,
+    reqs: Vec<CreateTransactionRequest>,
+) -> Result<Vec<TransactionResponse>, ServiceError> {
+    if reqs.is_empty() {
+        return Err(ServiceError::Validation("Batch cannot be empty".to_string()));
+    }
+
+    let rules = RuleSet::load(db, user_id).await?;
+    let mut responses = Vec::with_capacity(reqs.len());
+    let mut errors = Vec::new();
+
+    for (index, req) in reqs.into_iter().enumerate() {
+        let savepoint = db.begin().await?;
+        match insert_transaction(&savepoint, user_id, req, &rules).await {
+            Ok(response) => {
+                savepoint.commit().await?;
+                responses.push(response);
+            }
+            Err(e) => {
+                savepoint.rollback().await?;
+                // Only client errors are reported per item; anything else fails
+                // the whole request with its real cause.
+                match e {
+                    ServiceError::Validation(_)
+                    | ServiceError::Conflict(_)
+                    | ServiceError::NotFound
+                    | ServiceError::Forbidden => errors.push(BatchItemError {
+                        index,
+                        error: e.status_and_message().1,
+                    }),
+                    _ => return Err(e),
+                }
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(ServiceError::Batch(errors));
+    }
+
+    Ok(responses)
+}
+
+async fn insert_transaction<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    mut req: CreateTransactionRequest,
+    rules: &RuleSet,
+) -> Result<TransactionResponse, ServiceError> {
+    rules.apply(&mut req);
+    let splits = req.splits.take().unwrap_or_default();
+    let tags = req.tags.take().unwrap_or_default();
+    let allow_duplicate = req.allow_duplicate.unwrap_or(false);
+
+    let txn = build_transaction(db, user_id, req, None).await?;
+
+    if !allow_duplicate {
+        if let Some(duplicate) = find_duplicate(db, user_id, &txn, &[]).await? {
+            return Err(ServiceError::Conflict(format!(
+                "Possible duplicate of transaction {}",
+                duplicate.id
+            )));
+        }
+    }
+
+    let model = txn.insert(db).await?;
+    let lines = replace_splits(db, &model, &splits, &[]).await?;
+    let tags = set_transaction_tags(db, user_id, model.id, &tags).await?;
+    apply_balance_effect(db, &model, false).await?;
+
+    let categories = CategoryTree::load(db, user_id).await?;
+    let mut response = TransactionResponse::new(model, lines, &categories);
+    response.tags = tags;
+    attach_fx_costs(db, std::slice::from_mut(&mut response)).await?;
+    Ok(response)
+}
+
+pub(crate) async fn validate_transaction_request<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    user_id: Uuid,
+    req: CreateTransactionRequest,
+) -> Result<(), ServiceError> {
+    let scratch = db.begin().await?;
+    let result = build_transaction(&scratch, user_id, req, None).await;
+    scratch.rollback().await?;
+    result.map(|_| ())
+}
+
+async fn build_transaction<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    req: CreateTransactionRequest,
+    current_category: Option<Uuid>,
+) -> Result<transaction::ActiveModel, ServiceError> {
     if req.amount <= Decimal::ZERO {
         return Err(ServiceError::Validation("Amount must be positive".to_string()));
     }
@@ -158,10 +750,26 @@ pub async fn create_transaction(
     let txn_type = req.txn_type.trim().to_lowercase();
     validate_txn_type(&txn_type)?;
 
-    let currency = req.currency_code.trim().to_uppercase();
-    validate_currency_code(&currency)?;
+    if let Some(tags) = &req.tags {
+        tag::normalize_tags(tags)?;
+    }
+
+    let currencies = Currencies::load(db, user_id).await?;
+    let currency = currencies.normalize(&req.currency_code)?;
 
-    match txn_type.as_str() {
+    let external_id = req
+        .external_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string);
+    if external_id.as_ref().is_some_and(|id| id.len() > 255) {
+        return Err(ServiceError::Validation(
+            "External id too long (max 255 chars)".to_string(),
+        ));
+    }
+
+    let mut txn = match txn_type.as_str() {
         "transfer" => {
             let from = req.from_account_id.ok_or(ServiceError::Validation(
                 "Transfer must have from_account_id".to_string(),
@@ -176,14 +784,21 @@ pub async fn create_transaction(
                 ));
             }
 
-            verify_account_ownership(db, user_id, from).await?;
-            verify_account_ownership(db, user_id, to).await?;
-
-            let to_currency = req.to_currency_code.as_ref().map(|c| c.trim().to_uppercase());
-            if let Some(ref tc) = to_currency {
-                validate_currency_code(tc)?;
+            if req.settled_amount.is_some() {
+                return Err(ServiceError::Validation(
+                    "Transfers record the received amount in to_amount, not settled_amount".to_string(),
+                ));
             }
 
+            let from_account = verify_account_ownership(db, user_id, from).await?;
+            let to_account = verify_account_ownership(db, user_id, to).await?;
+
+            let to_currency = req
+                .to_currency_code
+                .as_deref()
+                .map(|c| currencies.normalize(c))
+                .transpose()?;
+
             if (req.to_amount.is_some() && to_currency.is_none())
                 || (req.to_amount.is_none() && to_currency.is_some())
             {
@@ -200,8 +815,18 @@ pub async fn create_transaction(
                 }
             }
 
+            check_account_currency(&from_account, &currency)?;
+            check_account_currency(&to_account, to_currency.as_deref().unwrap_or(&currency))?;
+
+            let implied_rate = match (req.to_amount, &to_currency) {
+                (Some(to_amt), Some(tc)) if *tc != currency => {
+                    Some((to_amt / req.amount).round_dp(fx::RATE_SCALE))
+                }
+                _ => None,
+            };
+
             let now = Utc::now().into();
-            let txn = transaction::ActiveModel {
+            transaction::ActiveModel {
                 id: Set(Uuid::new_v4()),
                 user_id: Set(user_id),
                 from_account_id: Set(Some(from)),
@@ -211,17 +836,19 @@ pub async fn create_transaction(
                 currency_code: Set(currency),
                 to_amount: Set(req.to_amount),
                 to_currency_code: Set(to_currency),
-                category: Set(req.category),
+                implied_rate: Set(implied_rate),
+                original_amount: Set(None),
+                original_currency_code: Set(None),
+                category_id: Set(None),
                 note: Set(req.note),
                 occurred_at: Set(req.occurred_at.into()),
                 ref_transaction_id: Set(None),
                 merchant: Set(req.merchant),
+                external_id: Set(external_id),
+                fingerprint: Set(None),
                 created_at: Set(now),
                 updated_at: Set(now),
-            };
-
-            let model = txn.insert(db).await?;
-            return Ok(TransactionResponse::from(model));
+            }
         }
         "refund" | "adjustment" => {
             if req.to_amount.is_some() || req.to_currency_code.is_some() {
@@ -236,53 +863,52 @@ pub async fn create_transaction(
 
             let ref_txn = load_owned_transaction(db, user_id, ref_txn_id).await?;
 
-            if ref_txn.currency_code != currency {
-                return Err(ServiceError::Validation(
-                    "Refund/adjustment currency must match original transaction".to_string(),
-                ));
-            }
-
-            if req.from_account_id.is_some() && req.to_account_id.is_some() {
-                return Err(ServiceError::Validation(
-                    "Refund/adjustment must have only one of from_account_id or to_account_id".to_string(),
-                ));
-            }
+            let account_id = match (req.from_account_id, req.to_account_id) {
+                (Some(_), Some(_)) => {
+                    return Err(ServiceError::Validation(
+                        "Refund/adjustment must have only one of from_account_id or to_account_id".to_string(),
+                    ))
+                }
+                (None, None) => {
+                    return Err(ServiceError::Validation(
+                        "Refund/adjustment must have from_account_id or to_account_id".to_string(),
+                    ))
+                }
+                (Some(id), None) | (None, Some(id)) => id,
+            };
+            let account = verify_account_ownership(db, user_id, account_id).await?;
+            let settlement = settle(&account, req.amount, currency, req.settled_amount)?;
 
-            if req.from_account_id.is_none() && req.to_account_id.is_none() {
+            if ref_txn.currency_code != settlement.currency_code {
                 return Err(ServiceError::Validation(
-                    "Refund/adjustment must have from_account_id or to_account_id".to_string(),
+                    "Refund/adjustment currency must match original transaction".to_string(),
                 ));
             }
 
-            if let Some(from_id) = req.from_account_id {
-                verify_account_ownership(db, user_id, from_id).await?;
-            }
-            if let Some(to_id) = req.to_account_id {
-                verify_account_ownership(db, user_id, to_id).await?;
-            }
-
             let now = Utc::now().into();
-            let txn = transaction::ActiveModel {
+            transaction::ActiveModel {
                 id: Set(Uuid::new_v4()),
                 user_id: Set(user_id),
                 from_account_id: Set(req.from_account_id),
                 to_account_id: Set(req.to_account_id),
                 txn_type: Set(txn_type),
-                amount: Set(req.amount),
-                currency_code: Set(currency),
+                amount: Set(settlement.amount),
+                currency_code: Set(settlement.currency_code),
                 to_amount: Set(None),
                 to_currency_code: Set(None),
-                category: Set(req.category),
+                implied_rate: Set(None),
+                original_amount: Set(settlement.original_amount),
+                original_currency_code: Set(settlement.original_currency_code),
+                category_id: Set(None),
                 note: Set(req.note),
                 occurred_at: Set(req.occurred_at.into()),
                 ref_transaction_id: Set(Some(ref_txn_id)),
                 merchant: Set(req.merchant),
+                external_id: Set(external_id),
+                fingerprint: Set(None),
                 created_at: Set(now),
                 updated_at: Set(now),
-            };
-
-            let model = txn.insert(db).await?;
-            return Ok(TransactionResponse::from(model));
+            }
         }
         _ => {
             if req.to_amount.is_some() || req.to_currency_code.is_some() {
@@ -297,49 +923,60 @@ pub async fn create_transaction(
                 ));
             }
 
-            if req.from_account_id.is_some() && req.to_account_id.is_some() {
-                return Err(ServiceError::Validation(
-                    "Non-transfer transaction cannot have both from and to accounts".to_string(),
-                ));
-            }
-
-            if req.from_account_id.is_none() && req.to_account_id.is_none() {
-                return Err(ServiceError::Validation(
-                    "Transaction must have from_account_id or to_account_id".to_string(),
-                ));
-            }
-
-            if let Some(from_id) = req.from_account_id {
-                verify_account_ownership(db, user_id, from_id).await?;
-            }
-            if let Some(to_id) = req.to_account_id {
-                verify_account_ownership(db, user_id, to_id).await?;
-            }
+            let account_id = match (req.from_account_id, req.to_account_id) {
+                (Some(_), Some(_)) => {
+                    return Err(ServiceError::Validation(
+                        "Non-transfer transaction cannot have both from and to accounts".to_string(),
+                    ))
+                }
+                (None, None) => {
+                    return Err(ServiceError::Validation(
+                        "Transaction must have from_account_id or to_account_id".to_string(),
+                    ))
+                }
+                (Some(id), None) | (None, Some(id)) => id,
+            };
+            let account = verify_account_ownership(db, user_id, account_id).await?;
+            let settlement = settle(&account, req.amount, currency, req.settled_amount)?;
 
             let now = Utc::now().into();
-            let txn = transaction::ActiveModel {
+            transaction::ActiveModel {
                 id: Set(Uuid::new_v4()),
                 user_id: Set(user_id),
                 from_account_id: Set(req.from_account_id),
                 to_account_id: Set(req.to_account_id),
                 txn_type: Set(txn_type),
-                amount: Set(req.amount),
-                currency_code: Set(currency),
+                amount: Set(settlement.amount),
+                currency_code: Set(settlement.currency_code),
                 to_amount: Set(None),
                 to_currency_code: Set(None),
-                category: Set(req.category),
+                implied_rate: Set(None),
+                original_amount: Set(settlement.original_amount),
+                original_currency_code: Set(settlement.original_currency_code),
+                category_id: Set(None),
                 note: Set(req.note),
                 occurred_at: Set(req.occurred_at.into()),
                 ref_transaction_id: Set(None),
                 merchant: Set(req.merchant),
+                external_id: Set(external_id),
+                fingerprint: Set(None),
                 created_at: Set(now),
                 updated_at: Set(now),
-            };
-
-            let model = txn.insert(db).await?;
-            return Ok(TransactionResponse::from(model));
+            }
         }
-    }
+    };
+    txn.category_id = Set(resolve_category(
+        db,
+        user_id,
+        kind_for_txn_type(txn.txn_type.as_ref()),
+        req.category.as_deref(),
+        req.category_id,
+        current_category,
+    )
+    .await?);
+    txn.fingerprint = Set(fingerprint(&txn));
+
+    Ok(txn)
 }
 
 pub async fn get_transaction(
@@ -348,54 +985,127 @@ pub async fn get_transaction(
     txn_id: Uuid,
 ) -> Result<TransactionResponse, ServiceError> {
     let txn = load_owned_transaction(db, user_id, txn_id).await?;
-    Ok(TransactionResponse::from(txn))
+    let mut responses = to_responses(db, vec![txn]).await?;
+    Ok(responses.remove(0))
 }
 
-pub async fn list_transactions(
-    db: &DatabaseConnection,
+/// Builds the row filter for a `TransactionQuery`, ignoring its pagination.
+pub(crate) async fn filter_condition<C: ConnectionTrait>(
+    db: &C,
     user_id: Uuid,
-    filter: TransactionQuery,
-) -> Result<Vec<TransactionResponse>, ServiceError> {
-    let mut query = Transaction::find().filter(transaction::Column::UserId.eq(user_id));
+    filter: &TransactionQuery,
+) -> Result<Condition, ServiceError> {
+    let mut condition = Condition::all().add(transaction::Column::UserId.eq(user_id));
 
     if let Some(start) = filter.start {
-        query = query.filter(transaction::Column::OccurredAt.gte(start));
+        condition = condition.add(transaction::Column::OccurredAt.gte(start));
     }
     if let Some(end) = filter.end {
-        query = query.filter(transaction::Column::OccurredAt.lte(end));
+        condition = condition.add(transaction::Column::OccurredAt.lte(end));
     }
-    if let Some(category) = filter.category {
-        query = query.filter(transaction::Column::Category.eq(category));
+    if let Some(category) = &filter.category {
+        let categories = CategoryTree::load(db, user_id).await?;
+        let roots: Vec<Uuid> = match Uuid::parse_str(category.trim()) {
+            Ok(id) => categories.get(id).map(|c| c.id).into_iter().collect(),
+            Err(_) => categories.find_path(category).iter().map(|c| c.id).collect(),
+        };
+        let ids: Vec<Uuid> = roots
+            .into_iter()
+            .flat_map(|id| categories.descendants(id))
+            .collect();
+
+        condition = condition.add(
+            Condition::any()
+                .add(transaction::Column::CategoryId.is_in(ids.clone()))
+                .add(
+                    transaction::Column::Id.in_subquery(
+                        Query::select()
+                            .column(transaction_split::Column::TransactionId)
+                            .from(TransactionSplit)
+                            .and_where(transaction_split::Column::CategoryId.is_in(ids))
+                            .to_owned(),
+                    ),
+                ),
+        );
     }
     if let Some(account_id) = filter.account_id {
-        query = query.filter(
+        condition = condition.add(
             Condition::any()
                 .add(transaction::Column::FromAccountId.eq(account_id))
                 .add(transaction::Column::ToAccountId.eq(account_id)),
         );
     }
     if let Some(min) = filter.min_amount {
-        query = query.filter(transaction::Column::Amount.gte(min));
+        condition = condition.add(transaction::Column::Amount.gte(min));
     }
     if let Some(max) = filter.max_amount {
-        query = query.filter(transaction::Column::Amount.lte(max));
+        condition = condition.add(transaction::Column::Amount.lte(max));
     }
-    if let Some(keyword) = filter.keyword {
+    if let Some(keyword) = &filter.keyword {
         if keyword.len() > 100 {
             return Err(ServiceError::Validation("Keyword too long".to_string()));
         }
         let pattern = format!("%{}%", keyword.replace('%', "\\%").replace('_', "\\_"));
-        query = query.filter(
+        condition = condition.add(
             Condition::any()
                 .add(transaction::Column::Note.like(&pattern))
                 .add(transaction::Column::Merchant.like(&pattern)),
         );
     }
-    if let Some(txn_type) = filter.txn_type {
-        query = query.filter(transaction::Column::TxnType.eq(txn_type.to_lowercase()));
+    if let Some(txn_type) = &filter.txn_type {
+        condition = condition.add(transaction::Column::TxnType.eq(txn_type.to_lowercase()));
     }
+    if let Some(tags) = &filter.tags {
+        let mut names: Vec<String> = tags
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        names.sort();
+        names.dedup();
+        let tag_ids = tag::find_tag_ids(db, user_id, &names).await?;
+        let tagged_with = |ids: Vec<Uuid>| {
+            transaction::Column::Id.in_subquery(
+                Query::select()
+                    .column(transaction_tags::Column::TransactionId)
+                    .from(TransactionTags)
+                    .and_where(transaction_tags::Column::TagId.is_in(ids))
+                    .to_owned(),
+            )
+        };
 
-    query = query.order_by(transaction::Column::OccurredAt, Order::Desc);
+        match filter.tag_mode.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("any") => condition = condition.add(tagged_with(tag_ids)),
+            Some("all") => {
+                if tag_ids.len() < names.len() {
+                    return Ok(condition.add(transaction::Column::Id.is_in(Vec::<Uuid>::new())));
+                }
+                for id in tag_ids {
+                    condition = condition.add(tagged_with(vec![id]));
+                }
+            }
+            Some(other) => {
+                return Err(ServiceError::Validation(format!(
+                    "Invalid tag_mode: {} (expected any or all)",
+                    other
+                )))
+            }
+        }
+    }
+
+    Ok(condition)
+}
+
+pub async fn list_transactions(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    filter: TransactionQuery,
+) -> Result<Vec<TransactionResponse>, ServiceError> {
+    let condition = filter_condition(db, user_id, &filter).await?;
+    let query = Transaction::find()
+        .filter(condition)
+        .order_by(transaction::Column::OccurredAt, Order::Desc);
 
     let page = filter.offset.unwrap_or(0) / filter.limit.unwrap_or(100);
     let limit = filter.limit.unwrap_or(100).min(500);
@@ -403,7 +1113,7 @@ pub async fn list_transactions(
     let paginator = query.paginate(db, limit);
     let transactions = paginator.fetch_page(page).await?;
 
-    Ok(transactions.into_iter().map(TransactionResponse::from).collect())
+    to_responses(db, transactions).await
 }
 
 pub async fn update_transaction(
@@ -412,26 +1122,117 @@ pub async fn update_transaction(
     txn_id: Uuid,
     req: UpdateTransactionRequest,
 ) -> Result<TransactionResponse, ServiceError> {
-    let txn = load_owned_transaction(db, user_id, txn_id).await?;
+    let tx = db.begin().await?;
+    let existing = load_owned_transaction(&tx, user_id, txn_id).await?;
 
-    let mut active: transaction::ActiveModel = txn.into();
+    // A foreign-currency transaction is edited in its original amount, with
+    // the stored amount as what settled.
+    let (amount, currency_code, settled_amount) =
+        match (existing.original_amount, &existing.original_currency_code) {
+            (Some(original), Some(code)) => (original, code.clone(), Some(existing.amount)),
+            _ => (existing.amount, existing.currency_code.clone(), None),
+        };
+    let merged = CreateTransactionRequest {
+        from_account_id: req.from_account_id.unwrap_or(existing.from_account_id),
+        to_account_id: req.to_account_id.unwrap_or(existing.to_account_id),
+        txn_type: req.txn_type.unwrap_or_else(|| existing.txn_type.clone()),
+        amount: req.amount.unwrap_or(amount),
+        currency_code: req.currency_code.unwrap_or(currency_code),
+        to_amount: req.to_amount.unwrap_or(existing.to_amount),
+        to_currency_code: req
+            .to_currency_code
+            .unwrap_or_else(|| existing.to_currency_code.clone()),
+        settled_amount: req.settled_amount.unwrap_or(settled_amount),
+        category_id: match req.category {
+            Some(_) => None,
+            None => req.category_id.unwrap_or(existing.category_id),
+        },
+        category: req.category,
+        note: req.note.or_else(|| existing.note.clone()),
+        occurred_at: req
+            .occurred_at
+            .unwrap_or_else(|| existing.occurred_at.with_timezone(&Utc)),
+        ref_transaction_id: req.ref_transaction_id.unwrap_or(existing.ref_transaction_id),
+        merchant: req.merchant.or_else(|| existing.merchant.clone()),
+        external_id: existing.external_id.clone(),
+        splits: None,
+        tags: None,
+        allow_duplicate: None,
+    };
 
-    if let Some(category) = req.category {
-        active.category = Set(Some(category));
-    }
-    if let Some(note) = req.note {
-        active.note = Set(Some(note));
-    }
-    if let Some(occurred_at) = req.occurred_at {
-        active.occurred_at = Set(occurred_at.into());
+    if merged.ref_transaction_id == Some(txn_id) {
+        return Err(ServiceError::Validation(
+            "Transaction cannot reference itself".to_string(),
+        ));
     }
-    if let Some(merchant) = req.merchant {
-        active.merchant = Set(Some(merchant));
+
+    let mut txn = build_transaction(&tx, user_id, merged, existing.category_id).await?;
+
+    // Refunds are reported against the original's type, account and currency.
+    if txn.currency_code.as_ref() != &existing.currency_code
+        || txn.txn_type.as_ref() != &existing.txn_type
+        || txn.from_account_id.as_ref() != &existing.from_account_id
+        || txn.to_account_id.as_ref() != &existing.to_account_id
+    {
+        let refund_count = Transaction::find()
+            .filter(transaction::Column::RefTransactionId.eq(txn_id))
+            .count(&tx)
+            .await?;
+
+        if refund_count > 0 {
+            return Err(ServiceError::Conflict(
+                "Cannot change type, accounts or currency of transaction with existing refunds"
+                    .to_string(),
+            ));
+        }
     }
-    active.updated_at = Set(Utc::now().into());
 
-    let model = active.update(db).await?;
-    Ok(TransactionResponse::from(model))
+    apply_balance_effect(&tx, &existing, true).await?;
+
+    txn.id = Set(existing.id);
+    txn.created_at = Set(existing.created_at);
+    let model = txn.update(&tx).await?;
+
+    let existing_lines = load_splits(&tx, vec![model.id])
+        .await?
+        .remove(&model.id)
+        .unwrap_or_default();
+    let current: Vec<Uuid> = existing_lines.iter().map(|line| line.category_id).collect();
+
+    let lines = match req.splits {
+        Some(splits) => replace_splits(&tx, &model, &splits, &current).await?,
+        None => {
+            let as_requests: Vec<TransactionSplitRequest> = existing_lines
+                .iter()
+                .map(|line| TransactionSplitRequest {
+                    category: None,
+                    category_id: Some(line.category_id),
+                    amount: line.amount,
+                    note: line.note.clone(),
+                })
+                .collect();
+            validate_splits(&model, &as_requests)?;
+            resolve_split_categories(&tx, &model, &as_requests, &current).await?;
+            existing_lines
+        }
+    };
+
+    let tags = match req.tags {
+        Some(tags) => set_transaction_tags(&tx, user_id, model.id, &tags).await?,
+        None => load_tags(&tx, vec![model.id])
+            .await?
+            .remove(&model.id)
+            .unwrap_or_default(),
+    };
+
+    apply_balance_effect(&tx, &model, false).await?;
+    let categories = CategoryTree::load(&tx, user_id).await?;
+    tx.commit().await?;
+
+    let mut response = TransactionResponse::new(model, lines, &categories);
+    response.tags = tags;
+    attach_fx_costs(db, std::slice::from_mut(&mut response)).await?;
+    Ok(response)
 }
 
 pub async fn delete_transaction(
@@ -439,11 +1240,12 @@ pub async fn delete_transaction(
     user_id: Uuid,
     txn_id: Uuid,
 ) -> Result<(), ServiceError> {
-    let txn = load_owned_transaction(db, user_id, txn_id).await?;
+    let tx = db.begin().await?;
+    let txn = load_owned_transaction(&tx, user_id, txn_id).await?;
 
     let refund_count = Transaction::find()
         .filter(transaction::Column::RefTransactionId.eq(txn_id))
-        .count(db)
+        .count(&tx)
         .await?;
 
     if refund_count > 0 {
@@ -452,8 +1254,11 @@ pub async fn delete_transaction(
         ));
     }
 
+    apply_balance_effect(&tx, &txn, true).await?;
+
     let active: transaction::ActiveModel = txn.into();
-    active.delete(db).await?;
+    active.delete(&tx).await?;
+    tx.commit().await?;
 
     Ok(())
 }
@@ -11,6 +11,7 @@ async fn test_account_crud() {
         name: "Test Account".to_string(),
         r#type: "bank_card".to_string(),
         currency_code: "USD".to_string(),
+        initial_balance: None,
     };
 
     let created = account::create_account(&db, user_id, req)
@@ -62,6 +63,7 @@ async fn test_account_user_isolation() {
         name: "User A Account".to_string(),
         r#type: "cash".to_string(),
         currency_code: "USD".to_string(),
+        initial_balance: None,
     };
 
     let account_a = account::create_account(&db, user_a, req)
@@ -117,6 +119,7 @@ async fn test_account_currency_validation() {
         name: "Invalid Currency".to_string(),
         r#type: "bank_card".to_string(),
         currency_code: "INVALID".to_string(),
+        initial_balance: None,
     };
 
     let result = account::create_account(&db, user_id, req).await;
@@ -129,6 +132,7 @@ async fn test_account_currency_validation() {
         name: "Short Currency".to_string(),
         r#type: "bank_card".to_string(),
         currency_code: "US".to_string(),
+        initial_balance: None,
     };
 
     let result = account::create_account(&db, user_id, req).await;
@@ -141,6 +145,7 @@ async fn test_account_currency_validation() {
         name: "Valid Currency".to_string(),
         r#type: "bank_card".to_string(),
         currency_code: "CNY".to_string(),
+        initial_balance: None,
     };
 
     let account = account::create_account(&db, user_id, req)
@@ -160,6 +165,7 @@ async fn test_account_type_normalization() {
         name: "Mixed Case Type".to_string(),
         r#type: "  BaNk_CaRd  ".to_string(),
         currency_code: "usd".to_string(),
+        initial_balance: None,
     };
 
     let account = account::create_account(&db, user_id, req)
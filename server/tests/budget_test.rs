@@ -0,0 +1,391 @@
+mod common;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use server::errors::ServiceError;
+use server::services::budget::{self, CreateBudgetRequest};
+use server::services::notify::Notifier;
+use server::services::transaction::{self, CreateTransactionRequest, TransactionSplitRequest};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct RecordingNotifier {
+    messages: Mutex<Vec<String>>,
+}
+
+#[async_trait::async_trait]
+impl Notifier for RecordingNotifier {
+    async fn send(&self, message: &str) -> anyhow::Result<()> {
+        self.messages.lock().unwrap().push(message.to_string());
+        Ok(())
+    }
+}
+
+struct FailingNotifier;
+
+#[async_trait::async_trait]
+impl Notifier for FailingNotifier {
+    async fn send(&self, _message: &str) -> anyhow::Result<()> {
+        anyhow::bail!("channel unavailable")
+    }
+}
+
+fn new_budget(category_id: Option<Uuid>, amount: i64) -> CreateBudgetRequest {
+    CreateBudgetRequest {
+        name: "Food".to_string(),
+        category_id,
+        amount: Decimal::new(amount, 0),
+        currency_code: "USD".to_string(),
+        period: None,
+        rollover: None,
+        start_date: None,
+    }
+}
+
+fn expense(
+    account_id: Uuid,
+    amount: i64,
+    category_id: Uuid,
+    occurred_at: DateTime<Utc>,
+) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        category_id: Some(category_id),
+        occurred_at,
+        allow_duplicate: Some(true),
+        ..common::expense(account_id, amount)
+    }
+}
+
+#[tokio::test]
+async fn test_budget_status_and_alerts() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 10000).await;
+    let food = common::create_category(&db, user_id, "Food", None).await;
+    let groceries = common::create_category(&db, user_id, "Groceries", Some(food)).await;
+    let travel = common::create_category(&db, user_id, "Travel", None).await;
+    let notifier = RecordingNotifier::default();
+    let now = Utc::now();
+
+    let food_budget = budget::create_budget(&db, user_id, new_budget(Some(food), 100))
+        .await
+        .expect("Failed to create budget");
+    assert_eq!(food_budget.category.as_deref(), Some("Food"));
+
+    let result = budget::create_budget(&db, user_id, new_budget(Some(food), 50)).await;
+    assert!(matches!(result, Err(ServiceError::Conflict(_))));
+
+    let result = budget::create_budget(&db, user_id, new_budget(None, 0)).await;
+    assert!(result.is_err(), "Amount must be positive");
+
+    let groceries_txn =
+        transaction::create_transaction(&db, user_id, expense(account_id, 50, groceries, now))
+            .await
+            .expect("Failed to create expense");
+
+    let mut split = expense(account_id, 40, travel, now);
+    split.splits = Some(vec![
+        TransactionSplitRequest {
+            category: None,
+            category_id: Some(food),
+            amount: Decimal::new(20, 0),
+            note: None,
+        },
+        TransactionSplitRequest {
+            category: None,
+            category_id: Some(travel),
+            amount: Decimal::new(20, 0),
+            note: None,
+        },
+    ]);
+    transaction::create_transaction(&db, user_id, split)
+        .await
+        .expect("Failed to create split expense");
+
+    let refund = CreateTransactionRequest {
+        from_account_id: None,
+        to_account_id: Some(account_id),
+        txn_type: "refund".to_string(),
+        category_id: None,
+        ref_transaction_id: Some(groceries_txn.id),
+        ..expense(account_id, 10, food, now)
+    };
+    transaction::create_transaction(&db, user_id, refund)
+        .await
+        .expect("Failed to create refund");
+
+    let status = budget::get_budget_status(&db, user_id, food_budget.id, None)
+        .await
+        .expect("Failed to get status");
+    assert_eq!(status.spent, Decimal::new(60, 0));
+    assert_eq!(status.remaining, Decimal::new(40, 0));
+    assert!(!status.over_budget);
+
+    let sent = budget::check_alerts(&db, &notifier, user_id, &[groceries_txn.id])
+        .await
+        .expect("Failed to check alerts");
+    assert_eq!(sent, 0, "60% is below every threshold");
+
+    let txn = transaction::create_transaction(&db, user_id, expense(account_id, 25, food, now))
+        .await
+        .expect("Failed to create expense");
+    let sent = budget::check_alerts(&db, &notifier, user_id, &[txn.id])
+        .await
+        .expect("Failed to check alerts");
+    assert_eq!(sent, 1);
+    assert!(notifier.messages.lock().unwrap()[0].contains("80%"));
+
+    let sent = budget::check_alerts(&db, &notifier, user_id, &[txn.id])
+        .await
+        .expect("Failed to check alerts");
+    assert_eq!(sent, 0, "Each threshold alerts once per period");
+
+    let txn =
+        transaction::create_transaction(&db, user_id, expense(account_id, 30, groceries, now))
+            .await
+            .expect("Failed to create expense");
+    let sent = budget::check_alerts(&db, &notifier, user_id, &[txn.id])
+        .await
+        .expect("Failed to check alerts");
+    assert_eq!(sent, 1);
+    assert!(notifier.messages.lock().unwrap()[1].contains("100%"));
+
+    let status = budget::get_budget_status(&db, user_id, food_budget.id, None)
+        .await
+        .expect("Failed to get status");
+    assert_eq!(status.spent, Decimal::new(115, 0));
+    assert!(status.over_budget);
+
+    let other_user = common::create_test_user(&db).await;
+    let result = budget::get_budget_status(&db, other_user, food_budget.id, None).await;
+    assert!(matches!(result, Err(ServiceError::Forbidden)));
+
+    common::cleanup_test_user(&db, user_id).await;
+    common::cleanup_test_user(&db, other_user).await;
+}
+
+#[tokio::test]
+async fn test_refund_of_split_expense() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 10000).await;
+    let food = common::create_category(&db, user_id, "Food", None).await;
+    let travel = common::create_category(&db, user_id, "Travel", None).await;
+    let now = Utc::now();
+
+    let food_budget = budget::create_budget(&db, user_id, new_budget(Some(food), 100))
+        .await
+        .expect("Failed to create budget");
+    let travel_budget = budget::create_budget(
+        &db,
+        user_id,
+        CreateBudgetRequest {
+            name: "Travel".to_string(),
+            ..new_budget(Some(travel), 100)
+        },
+    )
+    .await
+    .expect("Failed to create budget");
+
+    let split = CreateTransactionRequest {
+        category_id: None,
+        splits: Some(vec![
+            TransactionSplitRequest {
+                category: None,
+                category_id: Some(food),
+                amount: Decimal::new(30, 0),
+                note: None,
+            },
+            TransactionSplitRequest {
+                category: None,
+                category_id: Some(travel),
+                amount: Decimal::new(10, 0),
+                note: None,
+            },
+        ]),
+        ..expense(account_id, 40, food, now)
+    };
+    let split = transaction::create_transaction(&db, user_id, split)
+        .await
+        .expect("Failed to create split expense");
+
+    let refund = CreateTransactionRequest {
+        from_account_id: None,
+        to_account_id: Some(account_id),
+        txn_type: "refund".to_string(),
+        category_id: None,
+        ref_transaction_id: Some(split.id),
+        ..expense(account_id, 20, food, now)
+    };
+    transaction::create_transaction(&db, user_id, refund)
+        .await
+        .expect("Failed to create refund");
+
+    let status = budget::get_budget_status(&db, user_id, food_budget.id, None)
+        .await
+        .expect("Failed to get status");
+    assert_eq!(
+        status.spent,
+        Decimal::new(15, 0),
+        "Refund is shared 3:1 by the splits"
+    );
+    let status = budget::get_budget_status(&db, user_id, travel_budget.id, None)
+        .await
+        .expect("Failed to get status");
+    assert_eq!(status.spent, Decimal::new(5, 0));
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_budget_rollover() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 10000).await;
+    let food = common::create_category(&db, user_id, "Food", None).await;
+
+    let rollover = budget::create_budget(
+        &db,
+        user_id,
+        CreateBudgetRequest {
+            rollover: Some(true),
+            start_date: Some(common::date(2026, 1, 15)),
+            ..new_budget(Some(food), 100)
+        },
+    )
+    .await
+    .expect("Failed to create budget");
+    assert_eq!(rollover.start_date, common::date(2026, 1, 1));
+
+    let yearly = budget::create_budget(
+        &db,
+        user_id,
+        CreateBudgetRequest {
+            period: Some("yearly".to_string()),
+            start_date: Some(common::date(2026, 1, 1)),
+            ..new_budget(None, 1000)
+        },
+    )
+    .await
+    .expect("Failed to create yearly budget");
+
+    for (amount, day) in [
+        (70, common::at(2026, 1, 10)),
+        (130, common::at(2026, 2, 3)),
+        (20, common::at(2026, 3, 1)),
+    ] {
+        transaction::create_transaction(&db, user_id, expense(account_id, amount, food, day))
+            .await
+            .expect("Failed to create expense");
+    }
+
+    let february =
+        budget::get_budget_status(&db, user_id, rollover.id, Some(common::date(2026, 2, 20)))
+            .await
+            .expect("Failed to get status");
+    assert_eq!(february.rollover, Decimal::new(30, 0));
+    assert_eq!(february.available, Decimal::new(130, 0));
+    assert_eq!(february.spent, Decimal::new(130, 0));
+    assert_eq!(february.percent_used, Some(Decimal::new(100, 0)));
+    assert!(!february.over_budget);
+
+    let march =
+        budget::get_budget_status(&db, user_id, rollover.id, Some(common::date(2026, 3, 5)))
+            .await
+            .expect("Failed to get status");
+    assert_eq!(
+        march.rollover,
+        Decimal::ZERO,
+        "February overspent its carry"
+    );
+    assert_eq!(march.spent, Decimal::new(20, 0));
+    assert_eq!(march.period_end, common::date(2026, 3, 31));
+
+    let year = budget::get_budget_status(&db, user_id, yearly.id, Some(common::date(2026, 6, 1)))
+        .await
+        .expect("Failed to get status");
+    assert_eq!(year.spent, Decimal::new(220, 0));
+    assert_eq!(year.rollover, Decimal::ZERO);
+    assert_eq!(year.period_end, common::date(2026, 12, 31));
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_exhausted_budget_alerts_on_real_spend() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 10000).await;
+    let food = common::create_category(&db, user_id, "Food", None).await;
+    let name = format!("Exhausted {}", Uuid::new_v4());
+    let notifier = RecordingNotifier::default();
+    let alerts_for = |notifier: &RecordingNotifier| {
+        notifier
+            .messages
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.contains(&name))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    let exhausted = budget::create_budget(
+        &db,
+        user_id,
+        CreateBudgetRequest {
+            name: name.clone(),
+            rollover: Some(true),
+            start_date: Some(common::date(2026, 1, 1)),
+            ..new_budget(Some(food), 100)
+        },
+    )
+    .await
+    .expect("Failed to create budget");
+    transaction::create_transaction(
+        &db,
+        user_id,
+        expense(account_id, 250, food, common::at(2026, 1, 10)),
+    )
+    .await
+    .expect("Failed to create expense");
+
+    let february = common::date(2026, 2, 10);
+    let status = budget::get_budget_status(&db, user_id, exhausted.id, Some(february))
+        .await
+        .expect("Failed to get status");
+    assert_eq!(status.available, Decimal::new(-50, 0));
+    assert_eq!(status.spent, Decimal::ZERO);
+    assert_eq!(status.percent_used, None);
+
+    budget::sweep_alerts(&db, &notifier, february)
+        .await
+        .expect("Failed to sweep alerts");
+    assert!(alerts_for(&notifier).is_empty(), "Nothing spent yet");
+
+    transaction::create_transaction(
+        &db,
+        user_id,
+        expense(account_id, 10, food, common::at(2026, 2, 9)),
+    )
+    .await
+    .expect("Failed to create expense");
+
+    budget::sweep_alerts(&db, &FailingNotifier, february)
+        .await
+        .expect("Failed to sweep alerts");
+    budget::sweep_alerts(&db, &notifier, february)
+        .await
+        .expect("Failed to sweep alerts");
+    let sent = alerts_for(&notifier);
+    assert_eq!(sent.len(), 1, "A failed send is retried");
+    assert!(sent[0].contains("100%"));
+
+    budget::sweep_alerts(&db, &notifier, february)
+        .await
+        .expect("Failed to sweep alerts");
+    assert_eq!(alerts_for(&notifier).len(), 1);
+
+    common::cleanup_test_user(&db, user_id).await;
+}
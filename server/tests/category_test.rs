@@ -0,0 +1,263 @@
+mod common;
+
+use rust_decimal::Decimal;
+use server::errors::ServiceError;
+use server::services::budget::{self, CreateBudgetRequest};
+use server::services::category::{self, CreateCategoryRequest, UpdateCategoryRequest};
+use server::services::rule::{
+    self, CreateRuleRequest, RuleActions, RuleCondition, RuleField, RuleOperator,
+};
+use server::services::transaction::{self, TransactionSplitRequest};
+use uuid::Uuid;
+
+fn new_category(name: &str, parent_id: Option<Uuid>) -> CreateCategoryRequest {
+    CreateCategoryRequest {
+        name: name.to_string(),
+        parent_id,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_category_crud_and_validation() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+
+    let food = category::create_category(
+        &db,
+        user_id,
+        CreateCategoryRequest {
+            color: Some("#FFAA00".to_string()),
+            icon: Some("utensils".to_string()),
+            ..new_category(" Food ", None)
+        },
+    )
+    .await
+    .expect("Failed to create category");
+    assert_eq!(food.name, "Food");
+    assert_eq!(food.kind, "expense");
+    assert_eq!(food.color.as_deref(), Some("#ffaa00"));
+
+    let groceries =
+        category::create_category(&db, user_id, new_category("Groceries", Some(food.id)))
+            .await
+            .expect("Failed to create subcategory");
+    assert_eq!(groceries.path, "Food:Groceries");
+
+    let result = category::create_category(&db, user_id, new_category("food", None)).await;
+    assert!(
+        matches!(result, Err(ServiceError::Conflict(_))),
+        "Names are unique per parent"
+    );
+
+    let result = category::create_category(
+        &db,
+        user_id,
+        CreateCategoryRequest {
+            kind: Some("income".to_string()),
+            ..new_category("Bonus", Some(food.id))
+        },
+    )
+    .await;
+    assert!(result.is_err(), "Child kind must match parent");
+
+    let result = category::create_category(&db, user_id, new_category("A:B", None)).await;
+    assert!(result.is_err(), "Names cannot contain the path separator");
+
+    let update = UpdateCategoryRequest {
+        parent_id: Some(Some(groceries.id)),
+        ..Default::default()
+    };
+    let result = category::update_category(&db, user_id, food.id, update).await;
+    assert!(result.is_err(), "Category cannot become its own descendant");
+
+    let other_user = common::create_test_user(&db).await;
+    let result = category::get_category(&db, other_user, food.id).await;
+    assert!(matches!(result, Err(ServiceError::Forbidden)));
+
+    let result = category::delete_category(&db, user_id, food.id).await;
+    assert!(
+        matches!(result, Err(ServiceError::Conflict(_))),
+        "Parent with children"
+    );
+
+    category::delete_category(&db, user_id, groceries.id)
+        .await
+        .expect("Unused leaf category can be deleted");
+
+    common::cleanup_test_user(&db, user_id).await;
+    common::cleanup_test_user(&db, other_user).await;
+}
+
+#[tokio::test]
+async fn test_category_filter_includes_subcategories() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 1000).await;
+
+    let food = category::create_category(&db, user_id, new_category("Food", None))
+        .await
+        .expect("Failed to create category");
+    let groceries =
+        category::create_category(&db, user_id, new_category("Groceries", Some(food.id)))
+            .await
+            .expect("Failed to create subcategory");
+
+    let mut by_id = common::expense(account_id, 10);
+    by_id.category_id = Some(groceries.id);
+    let by_id = transaction::create_transaction(&db, user_id, by_id)
+        .await
+        .expect("Failed to create transaction");
+    assert_eq!(by_id.category.as_deref(), Some("Food:Groceries"));
+
+    let mut by_path = common::expense(account_id, 20);
+    by_path.category = Some("food : Dining".to_string());
+    let by_path = transaction::create_transaction(&db, user_id, by_path)
+        .await
+        .expect("Failed to create transaction");
+    assert_eq!(by_path.category.as_deref(), Some("Food:Dining"));
+
+    let mut split_txn = common::expense(account_id, 30);
+    split_txn.category = Some("Shopping".to_string());
+    split_txn.splits = Some(vec![
+        TransactionSplitRequest {
+            category: None,
+            category_id: Some(groceries.id),
+            amount: Decimal::new(25, 0),
+            note: None,
+        },
+        TransactionSplitRequest {
+            category: Some("Household".to_string()),
+            category_id: None,
+            amount: Decimal::new(5, 0),
+            note: None,
+        },
+    ]);
+    let split_txn = transaction::create_transaction(&db, user_id, split_txn)
+        .await
+        .expect("Failed to create split transaction");
+    assert_eq!(split_txn.splits[0].category, "Food:Groceries");
+
+    let found = transaction::list_transactions(&db, user_id, common::category_query("Food"))
+        .await
+        .expect("Failed to list transactions");
+    assert_eq!(found.len(), 3);
+
+    let found = transaction::list_transactions(
+        &db,
+        user_id,
+        common::category_query(&groceries.id.to_string()),
+    )
+    .await
+    .expect("Failed to list transactions");
+    let mut ids: Vec<Uuid> = found.iter().map(|t| t.id).collect();
+    ids.sort();
+    let mut expected = vec![by_id.id, split_txn.id];
+    expected.sort();
+    assert_eq!(ids, expected);
+
+    let found = transaction::list_transactions(&db, user_id, common::category_query("Food:Dining"))
+        .await
+        .expect("Failed to list transactions");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, by_path.id);
+
+    let mut income = common::expense(account_id, 5);
+    income.txn_type = "income".to_string();
+    income.to_account_id = income.from_account_id.take();
+    income.category_id = Some(food.id);
+    let result = transaction::create_transaction(&db, user_id, income).await;
+    assert!(result.is_err(), "Income cannot use an expense category");
+
+    let archive = UpdateCategoryRequest {
+        archived: Some(true),
+        ..Default::default()
+    };
+    category::update_category(&db, user_id, groceries.id, archive)
+        .await
+        .expect("Failed to archive category");
+
+    let mut archived = common::expense(account_id, 7);
+    archived.category_id = Some(groceries.id);
+    let result = transaction::create_transaction(&db, user_id, archived).await;
+    assert!(result.is_err(), "Archived categories cannot be assigned");
+
+    let listed = category::list_categories(&db, user_id, false)
+        .await
+        .expect("Failed to list categories");
+    assert!(listed.iter().all(|c| c.id != groceries.id));
+
+    let result = category::delete_category(&db, user_id, groceries.id).await;
+    assert!(
+        matches!(result, Err(ServiceError::Conflict(_))),
+        "Category in use"
+    );
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_category_referenced_by_budget_or_rule_cannot_be_deleted() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let food = common::create_category(&db, user_id, "Food", None).await;
+
+    let food_budget = budget::create_budget(
+        &db,
+        user_id,
+        CreateBudgetRequest {
+            name: "Food".to_string(),
+            category_id: Some(food),
+            amount: Decimal::new(100, 0),
+            currency_code: "USD".to_string(),
+            period: None,
+            rollover: None,
+            start_date: None,
+        },
+    )
+    .await
+    .expect("Failed to create budget");
+    let result = category::delete_category(&db, user_id, food).await;
+    assert!(
+        matches!(result, Err(ServiceError::Conflict(_))),
+        "Budget would be deleted with the category"
+    );
+    budget::delete_budget(&db, user_id, food_budget.id)
+        .await
+        .expect("Failed to delete budget");
+
+    let grocer = rule::create_rule(
+        &db,
+        user_id,
+        CreateRuleRequest {
+            name: "Grocer".to_string(),
+            priority: None,
+            enabled: None,
+            conditions: vec![RuleCondition {
+                field: RuleField::Merchant,
+                op: RuleOperator::Contains,
+                value: "grocer".to_string(),
+            }],
+            actions: RuleActions {
+                category_id: Some(food),
+                ..Default::default()
+            },
+        },
+    )
+    .await
+    .expect("Failed to create rule");
+    let result = category::delete_category(&db, user_id, food).await;
+    assert!(
+        matches!(result, Err(ServiceError::Conflict(_))),
+        "Rule would lose its action"
+    );
+    rule::delete_rule(&db, user_id, grocer.id)
+        .await
+        .expect("Failed to delete rule");
+
+    category::delete_category(&db, user_id, food)
+        .await
+        .expect("Unreferenced category can be deleted");
+
+    common::cleanup_test_user(&db, user_id).await;
+}
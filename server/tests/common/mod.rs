@@ -1,6 +1,10 @@
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
 use sea_orm::{ActiveModelTrait, Database, DatabaseConnection, EntityTrait, Set};
 use server::entities::{prelude::*, user};
+use server::services::account::{self, CreateAccountRequest};
+use server::services::category::{self, CreateCategoryRequest};
+use server::services::transaction::{CreateTransactionRequest, TransactionQuery};
 use uuid::Uuid;
 
 pub async fn setup_test_db() -> DatabaseConnection {
@@ -20,6 +24,7 @@ pub async fn create_test_user(db: &DatabaseConnection) -> Uuid {
         id: Set(user_id),
         username: Set(format!("test_user_{}", user_id)),
         password_hash: Set("dummy_hash".to_string()),
+        base_currency: Set(None),
         created_at: Set(now),
     };
 
@@ -38,3 +43,123 @@ pub async fn cleanup_test_user(db: &DatabaseConnection, user_id: Uuid) {
         }
     }
 }
+
+// Shared fixtures. Not every test binary uses every helper.
+
+#[allow(dead_code)]
+pub fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+/// Noon UTC on the given day.
+#[allow(dead_code)]
+pub fn at(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap()
+}
+
+#[allow(dead_code)]
+pub fn account_request(currency_code: &str, balance: i64) -> CreateAccountRequest {
+    CreateAccountRequest {
+        name: format!("{} account", currency_code),
+        r#type: "bank_card".to_string(),
+        currency_code: currency_code.to_string(),
+        initial_balance: Some(Decimal::new(balance, 0)),
+    }
+}
+
+#[allow(dead_code)]
+pub async fn insert_account(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    req: CreateAccountRequest,
+) -> Uuid {
+    account::create_account(db, user_id, req)
+        .await
+        .expect("Failed to create account")
+        .id
+}
+
+#[allow(dead_code)]
+pub async fn create_account(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    currency_code: &str,
+    balance: i64,
+) -> Uuid {
+    insert_account(db, user_id, account_request(currency_code, balance)).await
+}
+
+#[allow(dead_code)]
+pub async fn create_named_account(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    name: &str,
+    r#type: &str,
+    currency_code: &str,
+    balance: i64,
+) -> Uuid {
+    let req = CreateAccountRequest {
+        name: name.to_string(),
+        r#type: r#type.to_string(),
+        ..account_request(currency_code, balance)
+    };
+    insert_account(db, user_id, req).await
+}
+
+#[allow(dead_code)]
+pub async fn balance_of(db: &DatabaseConnection, user_id: Uuid, account_id: Uuid) -> Decimal {
+    account::get_account(db, user_id, account_id)
+        .await
+        .expect("Failed to get account")
+        .balance
+}
+
+#[allow(dead_code)]
+pub async fn create_category(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    name: &str,
+    parent_id: Option<Uuid>,
+) -> Uuid {
+    category::create_category(
+        db,
+        user_id,
+        CreateCategoryRequest {
+            name: name.to_string(),
+            parent_id,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to create category")
+    .id
+}
+
+/// A transaction occurring now, with no accounts set.
+#[allow(dead_code)]
+pub fn txn_request(txn_type: &str, amount: Decimal, currency_code: &str) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        txn_type: txn_type.to_string(),
+        amount,
+        currency_code: currency_code.to_string(),
+        occurred_at: Utc::now(),
+        ..Default::default()
+    }
+}
+
+/// A USD expense from `account_id`, occurring now.
+#[allow(dead_code)]
+pub fn expense(account_id: Uuid, amount: i64) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        from_account_id: Some(account_id),
+        ..txn_request("expense", Decimal::new(amount, 0), "USD")
+    }
+}
+
+#[allow(dead_code)]
+pub fn category_query(category: &str) -> TransactionQuery {
+    TransactionQuery {
+        category: Some(category.to_string()),
+        ..Default::default()
+    }
+}
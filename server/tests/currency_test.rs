@@ -0,0 +1,181 @@
+mod common;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter};
+use server::entities::{fx_rate, prelude::FxRate};
+use server::errors::ServiceError;
+use server::services::account::{self, AccountResponse};
+use server::services::currency::{self, CreateCurrencyRequest};
+use server::services::fx::{self, ConvertQuery, UpsertRateRequest};
+use server::services::transaction::{self, CreateTransactionRequest};
+use uuid::Uuid;
+
+async fn create_account(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    currency_code: &str,
+) -> Result<AccountResponse, ServiceError> {
+    account::create_account(db, user_id, common::account_request(currency_code, 100)).await
+}
+
+fn custom(code: &str, minor_units: Option<u32>) -> CreateCurrencyRequest {
+    CreateCurrencyRequest {
+        code: code.to_string(),
+        name: format!("{} token", code),
+        minor_units,
+    }
+}
+
+async fn clear_rates(db: &DatabaseConnection, currencies: &[&str]) {
+    FxRate::delete_many()
+        .filter(
+            Condition::any()
+                .add(fx_rate::Column::BaseCurrency.is_in(currencies.iter().copied()))
+                .add(fx_rate::Column::QuoteCurrency.is_in(currencies.iter().copied())),
+        )
+        .exec(db)
+        .await
+        .expect("Failed to clear rates");
+}
+
+#[tokio::test]
+async fn test_custom_currency_codes() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let other_user = common::create_test_user(&db).await;
+
+    let result = create_account(&db, user_id, "USDT").await;
+    assert!(
+        matches!(result, Err(ServiceError::Validation(_))),
+        "Unregistered codes are rejected"
+    );
+
+    let created = currency::create_currency(&db, user_id, custom("usdt", Some(4)))
+        .await
+        .expect("Failed to create currency");
+    assert_eq!(created.code, "USDT");
+    assert_eq!(created.minor_units, 4);
+    assert!(created.custom);
+
+    let duplicate = currency::create_currency(&db, user_id, custom("USDT", None)).await;
+    assert!(matches!(duplicate, Err(ServiceError::Conflict(_))));
+    let iso = currency::create_currency(&db, user_id, custom("USD", None)).await;
+    assert!(matches!(iso, Err(ServiceError::Validation(_))));
+    let too_precise = currency::create_currency(&db, user_id, custom("SATS", Some(8))).await;
+    assert!(matches!(too_precise, Err(ServiceError::Validation(_))));
+
+    let wallet = create_account(&db, user_id, "usdt")
+        .await
+        .expect("Registered code should be accepted");
+    assert_eq!(wallet.currency_code, "USDT");
+    transaction::create_transaction(
+        &db,
+        user_id,
+        CreateTransactionRequest {
+            from_account_id: Some(wallet.id),
+            occurred_at: common::at(2026, 4, 1),
+            ..common::txn_request("expense", Decimal::new(25, 0), "USDT")
+        },
+    )
+    .await
+    .expect("Custom currency account should accept transactions");
+
+    let result = create_account(&db, other_user, "USDT").await;
+    assert!(
+        matches!(result, Err(ServiceError::Validation(_))),
+        "Custom codes are per user"
+    );
+
+    let listed = currency::list_currencies(&db, user_id).await.unwrap();
+    assert!(listed
+        .iter()
+        .any(|c| c.code == "USD" && !c.custom && c.minor_units == 2));
+    assert!(listed.iter().any(|c| c.code == "JPY" && c.minor_units == 0));
+    assert!(listed.iter().any(|c| c.code == "USDT" && c.custom));
+
+    let in_use = currency::delete_currency(&db, user_id, "USDT").await;
+    assert!(matches!(in_use, Err(ServiceError::Conflict(_))));
+
+    currency::create_currency(&db, user_id, custom("PTS", Some(0)))
+        .await
+        .expect("Failed to create currency");
+    currency::delete_currency(&db, user_id, "pts")
+        .await
+        .expect("Unused currency should be deleted");
+    let listed = currency::list_currencies(&db, user_id).await.unwrap();
+    assert!(!listed.iter().any(|c| c.code == "PTS"));
+
+    common::cleanup_test_user(&db, user_id).await;
+    common::cleanup_test_user(&db, other_user).await;
+}
+
+#[tokio::test]
+async fn test_historic_codes_and_minor_units() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    clear_rates(&db, &["DEM", "ISK"]).await;
+
+    let result = create_account(&db, user_id, "DEM").await;
+    assert!(
+        matches!(result, Err(ServiceError::Validation(_))),
+        "Withdrawn codes cannot hold new money"
+    );
+
+    let rate = |date: NaiveDate, quote: &str, rate: Decimal| UpsertRateRequest {
+        rate_date: date,
+        base_currency: "EUR".to_string(),
+        quote_currency: quote.to_string(),
+        rate,
+        source: None,
+    };
+    let written = fx::upsert_rates(
+        &db,
+        vec![
+            rate(
+                NaiveDate::from_ymd_opt(2001, 12, 31).unwrap(),
+                "DEM",
+                Decimal::new(195583, 5),
+            ),
+            rate(
+                NaiveDate::from_ymd_opt(2026, 4, 1).unwrap(),
+                "ISK",
+                Decimal::new(150123, 3),
+            ),
+        ],
+    )
+    .await
+    .expect("Historic codes are valid in rate data");
+    assert_eq!(written, 2);
+
+    let unknown = fx::upsert_rates(
+        &db,
+        vec![rate(
+            NaiveDate::from_ymd_opt(2026, 4, 1).unwrap(),
+            "XYZ",
+            Decimal::ONE,
+        )],
+    )
+    .await;
+    assert!(matches!(unknown, Err(ServiceError::Validation(_))));
+
+    let converted = fx::convert_amount(
+        &db,
+        ConvertQuery {
+            amount: Decimal::new(10, 0),
+            from: "EUR".to_string(),
+            to: "ISK".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 4, 1),
+        },
+    )
+    .await
+    .expect("Failed to convert");
+    assert_eq!(
+        converted.converted,
+        Decimal::new(1501, 0),
+        "ISK has no minor units"
+    );
+
+    clear_rates(&db, &["DEM", "ISK"]).await;
+    common::cleanup_test_user(&db, user_id).await;
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use rust_decimal::Decimal;
+use sea_orm::DatabaseConnection;
+use server::services::export::{self, ExportFormat};
+use server::services::holdings::{self, CreateHoldingsRequest};
+use server::services::transaction::{self, CreateTransactionRequest, TransactionSplitRequest};
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+fn txn(txn_type: &str, amount: Decimal, day: u32) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        occurred_at: common::at(2026, 3, day),
+        ..common::txn_request(txn_type, amount, "USD")
+    }
+}
+
+async fn export_to_string(db: &DatabaseConnection, user_id: Uuid, format: ExportFormat) -> String {
+    let mut stream = export::export_stream(db.clone(), user_id, format);
+    let mut out = String::new();
+    while let Some(chunk) = stream.next().await {
+        out.push_str(&chunk.expect("Export stream failed"));
+    }
+    out
+}
+
+async fn seed_ledger(db: &DatabaseConnection, user_id: Uuid) {
+    let checking =
+        common::create_named_account(db, user_id, "Main Checking", "bank_card", "USD", 1000).await;
+    let savings =
+        common::create_named_account(db, user_id, "Euro Savings", "bank_card", "EUR", 1000).await;
+
+    let mut groceries = txn("expense", Decimal::new(100, 0), 1);
+    groceries.from_account_id = Some(checking);
+    groceries.category = Some("Shopping".to_string());
+    groceries.merchant = Some("Costco \"Wholesale\"".to_string());
+    groceries.splits = Some(vec![
+        TransactionSplitRequest {
+            category: Some("Food:Groceries".to_string()),
+            category_id: None,
+            amount: Decimal::new(70, 0),
+            note: None,
+        },
+        TransactionSplitRequest {
+            category: Some("Household".to_string()),
+            category_id: None,
+            amount: Decimal::new(30, 0),
+            note: None,
+        },
+    ]);
+    let groceries = transaction::create_transaction(db, user_id, groceries)
+        .await
+        .expect("Failed to create expense");
+
+    let mut refund = txn("refund", Decimal::new(30, 0), 3);
+    refund.to_account_id = Some(checking);
+    refund.ref_transaction_id = Some(groceries.id);
+    transaction::create_transaction(db, user_id, refund)
+        .await
+        .expect("Failed to create refund");
+
+    let mut transfer = txn("transfer", Decimal::new(110, 0), 5);
+    transfer.from_account_id = Some(checking);
+    transfer.to_account_id = Some(savings);
+    transfer.to_amount = Some(Decimal::new(100, 0));
+    transfer.to_currency_code = Some("EUR".to_string());
+    transaction::create_transaction(db, user_id, transfer)
+        .await
+        .expect("Failed to create transfer");
+
+    holdings::create_holdings(
+        db,
+        user_id,
+        CreateHoldingsRequest {
+            account_id: savings,
+            asset_type: "stock".to_string(),
+            symbol: "AAPL".to_string(),
+            name: Some("Apple".to_string()),
+            quantity: Decimal::new(10, 0),
+            cost_basis_total: Decimal::new(1500, 0),
+            currency_code: "USD".to_string(),
+            last_price: Some(Decimal::new(15025, 2)),
+            last_price_at: None,
+            market_value: Some(Decimal::new(150250, 2)),
+        },
+    )
+    .await
+    .expect("Failed to create holding");
+}
+
+#[tokio::test]
+async fn test_export_json_and_csv() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    seed_ledger(&db, user_id).await;
+
+    let json = export_to_string(&db, user_id, ExportFormat::Json).await;
+    let parsed: serde_json::Value = serde_json::from_str(&json).expect("Export is valid JSON");
+    assert_eq!(parsed["accounts"].as_array().unwrap().len(), 2);
+    assert_eq!(parsed["transactions"].as_array().unwrap().len(), 3);
+    assert_eq!(parsed["holdings"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        parsed["transactions"][0]["splits"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(parsed["transactions"][2]["to_currency_code"], "EUR");
+
+    let csv = export_to_string(&db, user_id, ExportFormat::Csv).await;
+    assert!(csv.starts_with("# accounts\nid,name,type"));
+    assert!(csv.contains("\n# transactions\n"));
+    assert!(csv.contains("\n# holdings\n"));
+    assert!(csv.contains("\"Costco \"\"Wholesale\"\"\""));
+    assert!(csv.contains("Food:Groceries=70|Household=30"));
+
+    let other_user = common::create_test_user(&db).await;
+    let empty = export_to_string(&db, other_user, ExportFormat::Json).await;
+    let parsed: serde_json::Value = serde_json::from_str(&empty).expect("Export is valid JSON");
+    assert!(parsed["transactions"].as_array().unwrap().is_empty());
+
+    common::cleanup_test_user(&db, user_id).await;
+    common::cleanup_test_user(&db, other_user).await;
+}
+
+#[tokio::test]
+async fn test_export_beancount_and_ledger() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    seed_ledger(&db, user_id).await;
+
+    let beancount = export_to_string(&db, user_id, ExportFormat::Beancount).await;
+    assert!(beancount.contains("open Assets:MainChecking\n"));
+    assert!(beancount.contains("2026-03-01 * \"Costco \\\"Wholesale\\\"\" \"Shopping\"\n"));
+    assert!(beancount.contains("  Assets:MainChecking  -100 USD\n"));
+    assert!(beancount.contains("  Expenses:Food:Groceries  70 USD\n"));
+    assert!(beancount.contains("  Expenses:Household  30 USD\n"));
+    assert!(beancount.contains("  Expenses:Shopping  -30 USD\n"));
+    assert!(beancount.contains("  Assets:EuroSavings  100 EUR @@ 110 USD\n"));
+    assert!(beancount.contains("price AAPL 150.25 USD\n"));
+    assert!(
+        beancount.contains("custom \"holding\" Assets:EuroSavings \"AAPL\" \"10\" 1502.5 USD\n")
+    );
+    assert!(beancount.contains("open Expenses:Food:Groceries\n"));
+    assert!(beancount.contains("open Equity:Opening-Balances\n"));
+
+    let ledger = export_to_string(&db, user_id, ExportFormat::Ledger).await;
+    assert!(ledger.contains("account Assets:Main Checking\n"));
+    assert!(ledger.contains("2026/03/05 * transfer\n"));
+    assert!(ledger.contains("  Assets:Euro Savings  100 EUR @@ 110 USD\n"));
+    assert!(ledger.contains("P 2026/"));
+
+    common::cleanup_test_user(&db, user_id).await;
+}
@@ -0,0 +1,228 @@
+mod common;
+
+use chrono::{Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sea_orm::DatabaseConnection;
+use server::services::forecast::{self, ForecastQuery};
+use server::services::notify::Notifier;
+use server::services::recurring::{self, CreateRecurringRequest, PostponeOccurrenceRequest};
+use server::services::transaction::{self, CreateTransactionRequest};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct RecordingNotifier {
+    messages: Mutex<Vec<String>>,
+}
+
+#[async_trait::async_trait]
+impl Notifier for RecordingNotifier {
+    async fn send(&self, message: &str) -> anyhow::Result<()> {
+        self.messages.lock().unwrap().push(message.to_string());
+        Ok(())
+    }
+}
+
+async fn spend(db: &DatabaseConnection, user_id: Uuid, account_id: Uuid, amount: i64) {
+    transaction::create_transaction(
+        db,
+        user_id,
+        CreateTransactionRequest {
+            category: Some("Food".to_string()),
+            occurred_at: Utc::now() - Duration::days(10),
+            allow_duplicate: Some(true),
+            ..common::expense(account_id, amount)
+        },
+    )
+    .await
+    .expect("Failed to create transaction");
+}
+
+fn template(
+    name: &str,
+    txn_type: &str,
+    from: Option<Uuid>,
+    to: Option<Uuid>,
+    amount: i64,
+    start_date: NaiveDate,
+) -> CreateRecurringRequest {
+    CreateRecurringRequest {
+        name: name.to_string(),
+        from_account_id: from,
+        to_account_id: to,
+        txn_type: txn_type.to_string(),
+        amount: Decimal::new(amount, 0),
+        currency_code: "USD".to_string(),
+        frequency: "monthly".to_string(),
+        start_date,
+        ..Default::default()
+    }
+}
+
+fn query(notify: bool) -> ForecastQuery {
+    ForecastQuery {
+        days: Some(20),
+        history_days: Some(90),
+        notify: Some(notify),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_forecast_projects_recurring_and_spending() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let notifier = RecordingNotifier::default();
+    let today = Utc::now().date_naive();
+
+    let checking =
+        common::create_named_account(&db, user_id, "Checking", "bank_card", "USD", 1000).await;
+    let card = common::create_named_account(&db, user_id, "Card", "credit_card", "USD", 0).await;
+    spend(&db, user_id, checking, 180).await;
+    spend(&db, user_id, card, 90).await;
+
+    // Already posted by the scheduler, so it must not count as habitual spending.
+    recurring::create_recurring(
+        &db,
+        user_id,
+        template(
+            "Gym",
+            "expense",
+            Some(checking),
+            None,
+            30,
+            today - Duration::days(3),
+        ),
+    )
+    .await
+    .expect("Failed to create recurring");
+    recurring::materialize_due(&db, Some(user_id), today)
+        .await
+        .expect("Failed to materialize");
+
+    let rent = recurring::create_recurring(
+        &db,
+        user_id,
+        template(
+            "Rent",
+            "expense",
+            Some(checking),
+            None,
+            900,
+            today + Duration::days(5),
+        ),
+    )
+    .await
+    .expect("Failed to create recurring");
+    recurring::create_recurring(
+        &db,
+        user_id,
+        template(
+            "Salary",
+            "income",
+            None,
+            Some(checking),
+            1000,
+            today + Duration::days(15),
+        ),
+    )
+    .await
+    .expect("Failed to create recurring");
+
+    let result = forecast::get_forecast(&db, &notifier, user_id, query(true))
+        .await
+        .expect("Failed to forecast");
+    assert_eq!(result.start, today);
+    assert_eq!(result.end, today + Duration::days(20));
+
+    let checking_forecast = result
+        .accounts
+        .iter()
+        .find(|a| a.account_id == checking)
+        .unwrap();
+    assert_eq!(checking_forecast.current_balance, Decimal::new(790, 0));
+    assert_eq!(checking_forecast.points.len(), 21);
+    assert_eq!(checking_forecast.spending.len(), 1);
+    assert_eq!(
+        checking_forecast.spending[0].category.as_deref(),
+        Some("Food")
+    );
+    assert_eq!(
+        checking_forecast.spending[0].daily_average,
+        Decimal::new(2, 0)
+    );
+    let names: Vec<&str> = checking_forecast
+        .scheduled
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Rent", "Salary"]);
+    assert_eq!(checking_forecast.scheduled[0].amount, Decimal::new(-900, 0));
+
+    assert_eq!(checking_forecast.points[4].balance, Decimal::new(782, 0));
+    assert_eq!(checking_forecast.points[5].balance, Decimal::new(-120, 0));
+    assert_eq!(
+        checking_forecast.negative_on,
+        Some(today + Duration::days(5))
+    );
+    assert_eq!(checking_forecast.lowest_balance, Decimal::new(-138, 0));
+    assert_eq!(checking_forecast.lowest_date, today + Duration::days(14));
+    assert_eq!(checking_forecast.projected_balance, Decimal::new(850, 0));
+
+    let card_forecast = result
+        .accounts
+        .iter()
+        .find(|a| a.account_id == card)
+        .unwrap();
+    assert_eq!(card_forecast.projected_balance, Decimal::new(-110, 0));
+    assert_eq!(
+        card_forecast.negative_on, None,
+        "Liabilities are not warned on"
+    );
+
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.warnings[0].account_id, checking);
+    {
+        let messages = notifier.messages.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("Checking"));
+    }
+
+    // Moving rent past payday clears the warning.
+    recurring::postpone_occurrence(
+        &db,
+        user_id,
+        rent.id,
+        PostponeOccurrenceRequest {
+            date: today + Duration::days(5),
+            to: today + Duration::days(16),
+        },
+    )
+    .await
+    .expect("Failed to postpone");
+    let result = forecast::get_forecast(&db, &notifier, user_id, query(true))
+        .await
+        .expect("Failed to forecast");
+    assert!(result.warnings.is_empty());
+    let checking_forecast = result
+        .accounts
+        .iter()
+        .find(|a| a.account_id == checking)
+        .unwrap();
+    assert_eq!(checking_forecast.points[16].balance, Decimal::new(858, 0));
+    assert_eq!(notifier.messages.lock().unwrap().len(), 1);
+
+    let result = forecast::get_forecast(
+        &db,
+        &notifier,
+        user_id,
+        ForecastQuery {
+            days: Some(0),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(result.is_err(), "days must be positive");
+
+    common::cleanup_test_user(&db, user_id).await;
+}
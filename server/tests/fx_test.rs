@@ -0,0 +1,432 @@
+mod common;
+
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter};
+use server::entities::{fx_rate, prelude::FxRate};
+use server::errors::ServiceError;
+use server::services::account;
+use server::services::fx::ingest::{self, IngestRatesRequest};
+use server::services::fx::{self, ConvertParams, ConvertQuery, FxRateQuery, UpsertRateRequest};
+use server::services::report::{self, FxCostQuery, NetWorthQuery, SummaryQuery};
+use server::services::settings::{self, UpdateSettingsRequest};
+use server::services::transaction::{self, CreateTransactionRequest};
+use uuid::Uuid;
+
+// Currencies no other test quotes, so nearest-date lookups stay predictable.
+const TEST_CURRENCIES: [&str; 2] = ["KZT", "MNT"];
+
+fn rate(day: u32, quote: &str, rate: i64) -> UpsertRateRequest {
+    UpsertRateRequest {
+        rate_date: common::date(2026, 1, day),
+        base_currency: "eur".to_string(),
+        quote_currency: quote.to_string(),
+        rate: Decimal::new(rate, 0),
+        source: None,
+    }
+}
+
+async fn clear_rates(db: &DatabaseConnection, currencies: &[&str]) {
+    FxRate::delete_many()
+        .filter(
+            Condition::any()
+                .add(fx_rate::Column::BaseCurrency.is_in(currencies.iter().copied()))
+                .add(fx_rate::Column::QuoteCurrency.is_in(currencies.iter().copied())),
+        )
+        .exec(db)
+        .await
+        .expect("Failed to clear rates");
+}
+
+fn convert(amount: i64, from: &str, to: &str, day: u32) -> ConvertQuery {
+    ConvertQuery {
+        amount: Decimal::new(amount, 0),
+        from: from.to_string(),
+        to: to.to_string(),
+        date: Some(common::date(2026, 1, day)),
+    }
+}
+
+async fn spend(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    account_id: Uuid,
+    currency: &str,
+    amount: i64,
+) {
+    transaction::create_transaction(
+        db,
+        user_id,
+        CreateTransactionRequest {
+            from_account_id: Some(account_id),
+            occurred_at: common::at(2026, 1, 15),
+            allow_duplicate: Some(true),
+            ..common::txn_request("expense", Decimal::new(amount, 0), currency)
+        },
+    )
+    .await
+    .expect("Failed to create transaction");
+}
+
+#[tokio::test]
+async fn test_fx_rates_and_conversion() {
+    let db = common::setup_test_db().await;
+    clear_rates(&db, &TEST_CURRENCIES).await;
+
+    fx::upsert_rates(
+        &db,
+        vec![
+            rate(10, "KZT", 500),
+            rate(20, "KZT", 520),
+            rate(12, "MNT", 3900),
+        ],
+    )
+    .await
+    .expect("Failed to upsert rates");
+    let replaced = fx::upsert_rate(&db, rate(20, "kzt", 510))
+        .await
+        .expect("Failed to upsert rate");
+    assert_eq!(replaced.base_currency, "EUR");
+    assert_eq!(replaced.source, "manual");
+
+    let rates = fx::list_rates(
+        &db,
+        FxRateQuery {
+            base_currency: Some("EUR".to_string()),
+            quote_currency: Some("KZT".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to list rates");
+    let listed: Vec<(NaiveDate, Decimal)> = rates.iter().map(|r| (r.rate_date, r.rate)).collect();
+    assert_eq!(
+        listed,
+        vec![
+            (common::date(2026, 1, 20), Decimal::new(510, 0)),
+            (common::date(2026, 1, 10), Decimal::new(500, 0))
+        ],
+        "Re-upserting a date replaces its rate"
+    );
+
+    let result = fx::upsert_rate(&db, rate(20, "EUR", 1)).await;
+    assert!(matches!(result, Err(ServiceError::Validation(_))));
+
+    // Inverse of the nearest quote: Jan 10 is closer to Jan 14 than Jan 20.
+    let direct = fx::convert_amount(&db, convert(100, "KZT", "EUR", 14))
+        .await
+        .expect("Failed to convert");
+    assert_eq!(direct.rate, Decimal::new(2, 3));
+    assert_eq!(direct.rate_date, common::date(2026, 1, 10));
+    assert_eq!(direct.converted, Decimal::new(2, 1));
+    assert_eq!(direct.via, None);
+
+    let crossed = fx::convert_amount(&db, convert(1000, "KZT", "MNT", 19))
+        .await
+        .expect("Failed to convert");
+    assert_eq!(crossed.via.as_deref(), Some("EUR"));
+    assert_eq!(crossed.rate, Decimal::new(764705877, 8));
+    assert_eq!(crossed.rate_date, common::date(2026, 1, 12));
+    assert_eq!(crossed.converted, Decimal::new(764706, 2));
+
+    let missing = fx::convert_amount(&db, convert(1, "KZT", "BTN", 19)).await;
+    assert!(matches!(missing, Err(ServiceError::Validation(_))));
+
+    clear_rates(&db, &TEST_CURRENCIES).await;
+}
+
+#[tokio::test]
+async fn test_base_currency_conversion() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    fx::upsert_rates(&db, vec![rate(20, "LAK", 4000)])
+        .await
+        .expect("Failed to upsert rates");
+
+    let lak = common::create_account(&db, user_id, "LAK", 8000).await;
+    let eur = common::create_account(&db, user_id, "EUR", 50).await;
+    spend(&db, user_id, lak, "LAK", 400).await;
+    spend(&db, user_id, eur, "EUR", 10).await;
+
+    let convert = ConvertParams {
+        convert: Some(true),
+    };
+    let accounts = account::list_accounts(&db, user_id).await.unwrap();
+    let result = fx::with_conversion(&db, user_id, accounts, &convert).await;
+    assert!(
+        matches!(result, Err(ServiceError::Validation(_))),
+        "Conversion needs a base currency"
+    );
+
+    let updated = settings::update_settings(
+        &db,
+        user_id,
+        UpdateSettingsRequest {
+            base_currency: Some(Some("eur".to_string())),
+        },
+    )
+    .await
+    .expect("Failed to update settings");
+    assert_eq!(updated.base_currency.as_deref(), Some("EUR"));
+
+    let accounts = account::list_accounts(&db, user_id).await.unwrap();
+    let accounts = fx::with_conversion(&db, user_id, accounts, &convert)
+        .await
+        .expect("Failed to convert accounts");
+    let lak_account = accounts.iter().find(|a| a.item.id == lak).unwrap();
+    let converted = lak_account.converted.as_ref().expect("Missing conversion");
+    assert_eq!(converted.currency_code, "EUR");
+    assert_eq!(converted.amount, Decimal::new(19, 1));
+
+    let summary = report::get_summary(
+        &db,
+        user_id,
+        SummaryQuery {
+            start: common::date(2026, 1, 1),
+            end: common::date(2026, 1, 31),
+            group_by: Some("month".to_string()),
+            convert: Some(true),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to get summary");
+    assert_eq!(summary.totals.len(), 2);
+    let converted = summary.converted.expect("Missing converted summary");
+    assert_eq!(converted.buckets.len(), 1);
+    assert_eq!(converted.total.currency_code, "EUR");
+    assert_eq!(converted.total.expense, Decimal::new(101, 1));
+
+    let today = Utc::now().date_naive();
+    let net_worth = report::get_net_worth(
+        &db,
+        user_id,
+        NetWorthQuery {
+            start: today,
+            end: today,
+            interval: Some("day".to_string()),
+            convert: Some(true),
+        },
+    )
+    .await
+    .expect("Failed to get net worth");
+    assert_eq!(net_worth.points.len(), 2);
+    let converted = net_worth.converted.expect("Missing converted net worth");
+    assert_eq!(converted.len(), 1);
+    assert_eq!(converted[0].net_worth, Decimal::new(419, 1));
+
+    clear_rates(&db, &["LAK"]).await;
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+async fn transfer(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    from: Uuid,
+    to: Uuid,
+    amount: i64,
+    to_amount: Option<(i64, &str)>,
+) -> transaction::TransactionResponse {
+    transaction::create_transaction(
+        db,
+        user_id,
+        CreateTransactionRequest {
+            from_account_id: Some(from),
+            to_account_id: Some(to),
+            to_amount: to_amount.map(|(a, _)| Decimal::new(a, 0)),
+            to_currency_code: to_amount.map(|(_, c)| c.to_string()),
+            occurred_at: common::at(2026, 3, 10),
+            allow_duplicate: Some(true),
+            ..common::txn_request("transfer", Decimal::new(amount, 0), "EUR")
+        },
+    )
+    .await
+    .expect("Failed to create transfer")
+}
+
+#[tokio::test]
+async fn test_transfer_fx_cost() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    clear_rates(&db, &["MUR", "SCR"]).await;
+    fx::upsert_rates(
+        &db,
+        vec![UpsertRateRequest {
+            rate_date: common::date(2026, 3, 10),
+            base_currency: "EUR".to_string(),
+            quote_currency: "MUR".to_string(),
+            rate: Decimal::new(50, 0),
+            source: None,
+        }],
+    )
+    .await
+    .expect("Failed to upsert rates");
+
+    let eur = common::create_account(&db, user_id, "EUR", 1000).await;
+    let savings = common::create_account(&db, user_id, "EUR", 0).await;
+    let mur = common::create_account(&db, user_id, "MUR", 0).await;
+    let scr = common::create_account(&db, user_id, "SCR", 0).await;
+
+    let same = transfer(&db, user_id, eur, savings, 10, None).await;
+    assert!(same.implied_rate.is_none());
+    assert!(same.fx_cost.is_none());
+
+    let created = transfer(&db, user_id, eur, mur, 100, Some((4900, "MUR"))).await;
+    assert_eq!(created.implied_rate, Some(Decimal::new(49, 0)));
+    let cost = created.fx_cost.expect("Missing fx cost");
+    assert_eq!(cost.reference_rate, Decimal::new(50, 0));
+    assert_eq!(cost.reference_amount, Decimal::new(5000, 0));
+    assert_eq!(cost.cost, Decimal::new(100, 0));
+    assert_eq!(cost.cost_percent, Decimal::new(2, 0));
+
+    let unpriced = transfer(&db, user_id, eur, scr, 20, Some((300, "SCR"))).await;
+    assert_eq!(unpriced.implied_rate, Some(Decimal::new(15, 0)));
+    let fetched = transaction::get_transaction(&db, user_id, unpriced.id)
+        .await
+        .expect("Failed to get transaction");
+    assert!(fetched.fx_cost.is_none(), "No rate for EUR/SCR");
+
+    settings::update_settings(
+        &db,
+        user_id,
+        UpdateSettingsRequest {
+            base_currency: Some(Some("EUR".to_string())),
+        },
+    )
+    .await
+    .expect("Failed to update settings");
+
+    let report = report::get_fx_cost(
+        &db,
+        user_id,
+        FxCostQuery {
+            start: common::date(2026, 3, 1),
+            end: common::date(2026, 3, 31),
+            convert: Some(true),
+        },
+    )
+    .await
+    .expect("Failed to get fx cost report");
+    assert_eq!(report.transactions.len(), 2);
+    assert_eq!(report.pairs.len(), 2);
+    let mur_pair = report
+        .pairs
+        .iter()
+        .find(|p| p.to_currency_code == "MUR")
+        .unwrap();
+    assert_eq!(mur_pair.count, 1);
+    assert_eq!(mur_pair.unpriced, 0);
+    assert_eq!(mur_pair.cost, Decimal::new(100, 0));
+    assert_eq!(mur_pair.cost_percent, Decimal::new(2, 0));
+    let scr_pair = report
+        .pairs
+        .iter()
+        .find(|p| p.to_currency_code == "SCR")
+        .unwrap();
+    assert_eq!(scr_pair.unpriced, 1);
+    assert_eq!(scr_pair.cost, Decimal::ZERO);
+    let converted = report.converted.expect("Missing converted cost");
+    assert_eq!(converted.currency_code, "EUR");
+    assert_eq!(converted.cost, Decimal::new(2, 0));
+
+    clear_rates(&db, &["MUR", "SCR"]).await;
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+const ECB_SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+	<gesmes:subject>Reference rates</gesmes:subject>
+	<Cube>
+		<Cube time="2026-02-03">
+			<Cube currency="TTD" rate="7.3"/>
+			<Cube currency="BBD" rate="2.16"/>
+		</Cube>
+		<Cube time="2026-02-02">
+			<Cube currency="TTD" rate="7.2"/>
+			<Cube currency="BBD" rate="n/a"/>
+		</Cube>
+	</Cube>
+</gesmes:Envelope>
+"#;
+
+const CSV_SAMPLE: &str = "date,base,quote,rate
+2026-02-03,EUR,TTD,7.35
+2026-02-04,eur,bbd,2.17
+2026-02-05,EUR,EUR,1
+";
+
+const INGEST_SOURCE: &str = "ecb-test";
+
+async fn clear_source(db: &DatabaseConnection) {
+    FxRate::delete_many()
+        .filter(fx_rate::Column::Source.eq(INGEST_SOURCE))
+        .exec(db)
+        .await
+        .expect("Failed to clear rates");
+}
+
+fn ingest_request(format: &str, content: &str) -> IngestRatesRequest {
+    IngestRatesRequest {
+        format: format.to_string(),
+        content: content.to_string(),
+        source: Some(INGEST_SOURCE.to_string()),
+    }
+}
+
+#[tokio::test]
+async fn test_fx_rate_ingestion() {
+    let db = common::setup_test_db().await;
+    clear_source(&db).await;
+
+    let result = ingest::ingest_rates(&db, ingest_request("ecb", ECB_SAMPLE))
+        .await
+        .expect("Failed to ingest ECB rates");
+    assert_eq!(result.parsed, 3);
+    assert_eq!(result.written, 3);
+    assert_eq!(result.start, Some(common::date(2026, 2, 2)));
+    assert_eq!(result.end, Some(common::date(2026, 2, 3)));
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].line, 11);
+
+    let again = ingest::ingest_rates(&db, ingest_request("ecb", ECB_SAMPLE))
+        .await
+        .expect("Failed to ingest ECB rates");
+    assert_eq!(again.parsed, 3);
+    assert_eq!(again.written, 0, "Reloading the same file changes nothing");
+
+    let result = ingest::ingest_rates(&db, ingest_request("csv", CSV_SAMPLE))
+        .await
+        .expect("Failed to ingest CSV rates");
+    assert_eq!(result.parsed, 2);
+    assert_eq!(result.written, 2);
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].line, 4);
+
+    let rates = fx::list_rates(
+        &db,
+        FxRateQuery {
+            quote_currency: Some("TTD".to_string()),
+            source: Some(INGEST_SOURCE.to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to list rates");
+    let listed: Vec<(NaiveDate, Decimal)> = rates.iter().map(|r| (r.rate_date, r.rate)).collect();
+    assert_eq!(
+        listed,
+        vec![
+            (common::date(2026, 2, 3), Decimal::new(735, 2)),
+            (common::date(2026, 2, 2), Decimal::new(72, 1))
+        ]
+    );
+
+    let result = ingest::ingest_rates(&db, ingest_request("ofx", CSV_SAMPLE)).await;
+    assert!(matches!(result, Err(ServiceError::Validation(_))));
+    let result = ingest::ingest_rates(&db, ingest_request("ecb", CSV_SAMPLE)).await;
+    assert!(matches!(result, Err(ServiceError::Validation(_))));
+    let result = ingest::ingest_rates(&db, ingest_request("csv", "day,rate\n")).await;
+    assert!(matches!(result, Err(ServiceError::Validation(_))));
+
+    clear_source(&db).await;
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use rust_decimal::Decimal;
+use server::errors::ServiceError;
+use server::services::goal::{self, CreateGoalRequest, UpdateGoalRequest};
+use server::services::transaction::{self, CreateTransactionRequest};
+use uuid::Uuid;
+
+fn transfer(from: Uuid, to: Uuid, amount: i64, y: i32, m: u32, d: u32) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        from_account_id: Some(from),
+        to_account_id: Some(to),
+        occurred_at: common::at(y, m, d),
+        allow_duplicate: Some(true),
+        ..common::txn_request("transfer", Decimal::new(amount, 0), "USD")
+    }
+}
+
+fn new_goal(account_ids: Vec<Uuid>) -> CreateGoalRequest {
+    CreateGoalRequest {
+        name: "Emergency fund".to_string(),
+        target_amount: Decimal::new(6000, 0),
+        currency_code: "usd".to_string(),
+        start_date: Some(common::date(2026, 1, 1)),
+        deadline: Some(common::date(2026, 12, 31)),
+        account_ids,
+    }
+}
+
+#[tokio::test]
+async fn test_goal_validation_and_links() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let savings =
+        common::create_named_account(&db, user_id, "Savings", "bank_card", "USD", 0).await;
+    let euro = common::create_named_account(&db, user_id, "Euro", "bank_card", "EUR", 0).await;
+
+    let result = goal::create_goal(&db, user_id, new_goal(vec![])).await;
+    assert!(result.is_err(), "At least one account is required");
+
+    let result = goal::create_goal(&db, user_id, new_goal(vec![savings, euro])).await;
+    assert!(
+        result.is_err(),
+        "Linked accounts must match the goal currency"
+    );
+
+    let mut invalid = new_goal(vec![savings]);
+    invalid.deadline = Some(common::date(2025, 12, 1));
+    let result = goal::create_goal(&db, user_id, invalid).await;
+    assert!(result.is_err(), "Deadline must follow the start date");
+
+    let created = goal::create_goal(&db, user_id, new_goal(vec![savings, savings]))
+        .await
+        .expect("Failed to create goal");
+    assert_eq!(created.currency_code, "USD");
+    assert_eq!(created.account_ids, vec![savings]);
+
+    let other_user = common::create_test_user(&db).await;
+    let foreign =
+        common::create_named_account(&db, other_user, "Theirs", "bank_card", "USD", 0).await;
+    let result = goal::update_goal(
+        &db,
+        user_id,
+        created.id,
+        UpdateGoalRequest {
+            account_ids: Some(vec![savings, foreign]),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(ServiceError::Forbidden)));
+
+    let result = goal::get_goal(&db, other_user, created.id).await;
+    assert!(matches!(result, Err(ServiceError::Forbidden)));
+
+    let updated = goal::update_goal(
+        &db,
+        user_id,
+        created.id,
+        UpdateGoalRequest {
+            name: Some("Rainy day".to_string()),
+            deadline: Some(None),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to update goal");
+    assert_eq!(updated.name, "Rainy day");
+    assert_eq!(updated.deadline, None);
+    assert_eq!(updated.account_ids, vec![savings]);
+
+    common::cleanup_test_user(&db, user_id).await;
+    common::cleanup_test_user(&db, other_user).await;
+}
+
+#[tokio::test]
+async fn test_goal_progress() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let checking =
+        common::create_named_account(&db, user_id, "Checking", "bank_card", "USD", 10000).await;
+    let savings =
+        common::create_named_account(&db, user_id, "Savings", "bank_card", "USD", 1000).await;
+    let brokerage =
+        common::create_named_account(&db, user_id, "Brokerage", "bank_card", "USD", 0).await;
+
+    let created = goal::create_goal(&db, user_id, new_goal(vec![savings, brokerage]))
+        .await
+        .expect("Failed to create goal");
+
+    for req in [
+        transfer(checking, savings, 500, 2026, 2, 1),
+        transfer(checking, savings, 700, 2026, 3, 1),
+        transfer(savings, brokerage, 300, 2026, 3, 15),
+        transfer(savings, checking, 200, 2026, 3, 20),
+        transfer(checking, savings, 400, 2025, 12, 1),
+    ] {
+        transaction::create_transaction(&db, user_id, req)
+            .await
+            .expect("Failed to create transfer");
+    }
+
+    let progress =
+        goal::get_goal_progress(&db, user_id, created.id, Some(common::date(2026, 4, 1)))
+            .await
+            .expect("Failed to get progress");
+    assert_eq!(progress.current_amount, Decimal::new(2400, 0));
+    assert_eq!(progress.remaining, Decimal::new(3600, 0));
+    assert_eq!(progress.percent_complete, Decimal::new(40, 0));
+    assert_eq!(
+        progress.transferred_in,
+        Decimal::new(1200, 0),
+        "Only since start"
+    );
+    assert_eq!(
+        progress.transferred_out,
+        Decimal::new(200, 0),
+        "Internal moves ignored"
+    );
+    assert_eq!(progress.net_contributions, Decimal::new(1000, 0));
+    assert_eq!(
+        progress.average_monthly_contribution,
+        Decimal::new(33333, 2)
+    );
+    assert_eq!(progress.months_remaining, Some(8));
+    assert_eq!(
+        progress.required_monthly_contribution,
+        Some(Decimal::new(450, 0))
+    );
+    assert_eq!(
+        progress.projected_completion,
+        Some(common::date(2027, 3, 1))
+    );
+    assert_eq!(progress.on_track, Some(false));
+
+    transaction::create_transaction(
+        &db,
+        user_id,
+        transfer(checking, brokerage, 3600, 2026, 4, 1),
+    )
+    .await
+    .expect("Failed to create transfer");
+    let progress =
+        goal::get_goal_progress(&db, user_id, created.id, Some(common::date(2026, 4, 1)))
+            .await
+            .expect("Failed to get progress");
+    assert!(progress.completed);
+    assert_eq!(progress.remaining, Decimal::ZERO);
+    assert_eq!(progress.required_monthly_contribution, Some(Decimal::ZERO));
+    assert_eq!(progress.on_track, Some(true));
+
+    common::cleanup_test_user(&db, user_id).await;
+}
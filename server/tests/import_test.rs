@@ -0,0 +1,418 @@
+mod common;
+
+use rust_decimal::Decimal;
+use server::errors::ServiceError;
+use server::services::account::{self, CreateAccountRequest};
+use server::services::import::{
+    self,
+    profile::{self, CreateImportProfileRequest},
+    ImportCsvRequest, ImportStatementRequest,
+};
+
+#[tokio::test]
+async fn test_csv_import_with_debit_credit_profile() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+
+    let account = account::create_account(
+        &db,
+        user_id,
+        CreateAccountRequest {
+            name: "Checking".to_string(),
+            r#type: "bank_card".to_string(),
+            ..common::account_request("EUR", 1000)
+        },
+    )
+    .await
+    .expect("Failed to create account");
+
+    let result = profile::create_profile(
+        &db,
+        user_id,
+        CreateImportProfileRequest {
+            name: "Broken".to_string(),
+            account_id: account.id,
+            delimiter: None,
+            has_header: None,
+            date_column: "Date".to_string(),
+            date_format: "%d.%m.%Y".to_string(),
+            amount_column: Some("Amount".to_string()),
+            debit_column: Some("Debit".to_string()),
+            credit_column: None,
+            merchant_column: None,
+            note_column: None,
+            decimal_separator: None,
+        },
+    )
+    .await;
+    assert!(
+        result.is_err(),
+        "Amount and debit columns are mutually exclusive"
+    );
+
+    let bank = profile::create_profile(
+        &db,
+        user_id,
+        CreateImportProfileRequest {
+            name: "My Bank".to_string(),
+            account_id: account.id,
+            delimiter: Some(";".to_string()),
+            has_header: Some(true),
+            date_column: "Datum".to_string(),
+            date_format: "%d.%m.%Y".to_string(),
+            amount_column: None,
+            debit_column: Some("Soll".to_string()),
+            credit_column: Some("Haben".to_string()),
+            merchant_column: Some("Empfänger".to_string()),
+            note_column: Some("Verwendungszweck".to_string()),
+            decimal_separator: Some(",".to_string()),
+        },
+    )
+    .await
+    .expect("Failed to create profile");
+
+    let duplicate = profile::create_profile(
+        &db,
+        user_id,
+        CreateImportProfileRequest {
+            name: "My Bank".to_string(),
+            account_id: account.id,
+            delimiter: None,
+            has_header: None,
+            date_column: "Date".to_string(),
+            date_format: "%Y-%m-%d".to_string(),
+            amount_column: Some("Amount".to_string()),
+            debit_column: None,
+            credit_column: None,
+            merchant_column: None,
+            note_column: None,
+            decimal_separator: None,
+        },
+    )
+    .await;
+    assert!(matches!(duplicate, Err(ServiceError::Conflict(_))));
+
+    let content = "Datum;Empfänger;Verwendungszweck;Soll;Haben\n\
+                   01.03.2026;REWE;Einkauf;1.234,50;\n\
+                   02.03.2026;ACME GmbH;Gehalt;;2.000,00\n\
+                   31.02.2026;Bad Row;;10,00;\n";
+
+    let preview = import::preview_csv(
+        &db,
+        user_id,
+        ImportCsvRequest {
+            profile_id: bank.id,
+            content: content.to_string(),
+        },
+    )
+    .await
+    .expect("Failed to preview import");
+    assert_eq!(preview.rows.len(), 3);
+    assert_eq!(preview.valid_count, 2);
+    assert_eq!(preview.error_count, 1);
+    assert_eq!(preview.rows[0].txn_type.as_deref(), Some("expense"));
+    assert_eq!(preview.rows[0].amount, Some(Decimal::new(123450, 2)));
+    assert_eq!(preview.rows[0].merchant.as_deref(), Some("REWE"));
+    assert_eq!(preview.rows[1].txn_type.as_deref(), Some("income"));
+    assert_eq!(preview.rows[2].line, 4);
+
+    match import::import_csv(
+        &db,
+        user_id,
+        ImportCsvRequest {
+            profile_id: bank.id,
+            content: content.to_string(),
+        },
+    )
+    .await
+    {
+        Err(ServiceError::Batch(items)) => assert_eq!(items[0].index, 2),
+        other => panic!("Expected batch error, got {:?}", other),
+    }
+
+    let valid = content.lines().take(3).collect::<Vec<_>>().join("\n");
+    let created = import::import_csv(
+        &db,
+        user_id,
+        ImportCsvRequest {
+            profile_id: bank.id,
+            content: valid.clone(),
+        },
+    )
+    .await
+    .expect("Failed to import CSV");
+    assert_eq!(created.transactions.len(), 2);
+
+    let reimported = import::import_csv(
+        &db,
+        user_id,
+        ImportCsvRequest {
+            profile_id: bank.id,
+            content: valid,
+        },
+    )
+    .await
+    .expect("Failed to re-import CSV");
+    assert_eq!(reimported.transactions.len(), 0);
+    assert_eq!(reimported.skipped_duplicates, 2);
+
+    let refreshed = account::get_account(&db, user_id, account.id)
+        .await
+        .expect("Failed to get account");
+    assert_eq!(refreshed.balance, Decimal::new(176550, 2));
+
+    let other_user = common::create_test_user(&db).await;
+    let result = import::preview_csv(
+        &db,
+        other_user,
+        ImportCsvRequest {
+            profile_id: bank.id,
+            content: content.to_string(),
+        },
+    )
+    .await;
+    assert!(result.is_err(), "Profiles are private to their owner");
+
+    common::cleanup_test_user(&db, user_id).await;
+    common::cleanup_test_user(&db, other_user).await;
+}
+
+#[tokio::test]
+async fn test_ofx_import_dedups_by_fitid_and_updates_positions() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+
+    let account = account::create_account(
+        &db,
+        user_id,
+        CreateAccountRequest {
+            name: "Brokerage".to_string(),
+            r#type: "investment".to_string(),
+            ..common::account_request("USD", 500)
+        },
+    )
+    .await
+    .expect("Failed to create account");
+
+    let content = "OFXHEADER:100\nDATA:OFXSGML\n\n<OFX>\n<BANKMSGSRSV1><STMTTRNRS><STMTRS>\n\
+                   <CURDEF>USD\n<BANKTRANLIST>\n\
+                   <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20260301120000[-5:EST]<TRNAMT>-42.50\
+                   <FITID>A-1<NAME>Coffee &amp; Co<MEMO>Latte</STMTTRN>\n\
+                   <STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20260302<TRNAMT>100.00\
+                   <FITID>A-2<NAME>Dividend</STMTTRN>\n\
+                   </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1>\n\
+                   <INVSTMTMSGSRSV1><INVSTMTTRNRS><INVSTMTRS><INVPOSLIST>\n\
+                   <POSSTOCK><INVPOS><SECID><UNIQUEID>037833100<UNIQUEIDTYPE>CUSIP</SECID>\
+                   <HELDINACCT>CASH<POSTYPE>LONG<UNITS>10<UNITPRICE>150.25<MKTVAL>1502.50\
+                   <DTPRICEASOF>20260302</INVPOS></POSSTOCK>\n\
+                   </INVPOSLIST></INVSTMTRS></INVSTMTTRNRS></INVSTMTMSGSRSV1>\n\
+                   <SECLISTMSGSRSV1><SECLIST><STOCKINFO><SECINFO><SECID><UNIQUEID>037833100\
+                   <UNIQUEIDTYPE>CUSIP</SECID><SECNAME>Apple Inc<TICKER>AAPL</SECINFO></STOCKINFO>\
+                   </SECLIST></SECLISTMSGSRSV1>\n</OFX>\n";
+
+    let request = || ImportStatementRequest {
+        account_id: account.id,
+        content: content.to_string(),
+        date_format: None,
+    };
+
+    let preview = import::preview_ofx(&db, user_id, request())
+        .await
+        .expect("Failed to preview OFX");
+    assert_eq!(preview.valid_count, 2);
+    assert_eq!(preview.rows[0].merchant.as_deref(), Some("Coffee & Co"));
+    assert_eq!(preview.rows[0].external_id.as_deref(), Some("A-1"));
+    assert_eq!(preview.positions.len(), 1);
+    assert_eq!(preview.positions[0].symbol, "AAPL");
+
+    let first = import::import_ofx(&db, user_id, request())
+        .await
+        .expect("Failed to import OFX");
+    assert_eq!(first.transactions.len(), 2);
+    assert_eq!(first.skipped_duplicates, 0);
+    assert_eq!(first.holdings.len(), 1);
+    assert_eq!(first.holdings[0].asset_type, "stock");
+    assert_eq!(first.holdings[0].quantity, Decimal::new(10, 0));
+
+    let second = import::import_ofx(&db, user_id, request())
+        .await
+        .expect("Failed to re-import OFX");
+    assert_eq!(second.transactions.len(), 0);
+    assert_eq!(second.skipped_duplicates, 2);
+    assert_eq!(second.holdings.len(), 1);
+    assert_eq!(second.holdings[0].id, first.holdings[0].id);
+
+    let refreshed = account::get_account(&db, user_id, account.id)
+        .await
+        .expect("Failed to get account");
+    assert_eq!(refreshed.balance, Decimal::new(55750, 2));
+
+    let result = import::preview_ofx(
+        &db,
+        user_id,
+        ImportStatementRequest {
+            account_id: account.id,
+            content: content.replace("<CURDEF>USD", "<CURDEF>EUR"),
+            date_format: None,
+        },
+    )
+    .await;
+    assert!(result.is_err(), "Statement currency must match the account");
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_qif_import() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+
+    let account = account::create_account(
+        &db,
+        user_id,
+        CreateAccountRequest {
+            name: "Wallet".to_string(),
+            r#type: "cash".to_string(),
+            ..common::account_request("USD", 100)
+        },
+    )
+    .await
+    .expect("Failed to create account");
+
+    let content = "!Type:Bank\n\
+                   D03/01'26\nT-1,020.00\nPLandlord\nMMarch rent\nLHousing\n^\n\
+                   D03/05/2026\nT25.00\nPRefund\nL[Savings]\n^\n\
+                   D13/45/2026\nT1.00\n^\n";
+
+    let preview = import::preview_qif(
+        &db,
+        user_id,
+        ImportStatementRequest {
+            account_id: account.id,
+            content: content.to_string(),
+            date_format: None,
+        },
+    )
+    .await
+    .expect("Failed to preview QIF");
+    assert_eq!(preview.rows.len(), 3);
+    assert_eq!(preview.error_count, 1);
+    assert_eq!(preview.rows[0].amount, Some(Decimal::new(1020, 0)));
+    assert_eq!(
+        preview.rows[0].occurred_at.map(|at| at.date_naive()),
+        Some(common::date(2026, 3, 1))
+    );
+    assert_eq!(preview.rows[0].category.as_deref(), Some("Housing"));
+    assert_eq!(preview.rows[1].category, None);
+    assert_eq!(
+        preview.rows[1].occurred_at.map(|at| at.date_naive()),
+        Some(common::date(2026, 3, 5))
+    );
+    assert_eq!(preview.rows[2].line, 13);
+
+    let valid = content.lines().take(12).collect::<Vec<_>>().join("\n");
+    let result = import::import_qif(
+        &db,
+        user_id,
+        ImportStatementRequest {
+            account_id: account.id,
+            content: valid,
+            date_format: None,
+        },
+    )
+    .await
+    .expect("Failed to import QIF");
+    assert_eq!(result.transactions.len(), 2);
+    assert_eq!(result.transactions[0].merchant.as_deref(), Some("Landlord"));
+
+    let refreshed = account::get_account(&db, user_id, account.id)
+        .await
+        .expect("Failed to get account");
+    assert_eq!(refreshed.balance, Decimal::new(-895, 0));
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_import_larger_than_api_batch() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+
+    let account = account::create_account(
+        &db,
+        user_id,
+        CreateAccountRequest {
+            name: "Wallet".to_string(),
+            r#type: "cash".to_string(),
+            ..common::account_request("USD", 0)
+        },
+    )
+    .await
+    .expect("Failed to create account");
+
+    let content: String = std::iter::once("!Type:Bank\n".to_string())
+        .chain((1..=600).map(|i| format!("D03/01/2026\nT{}.00\nPShop {}\n^\n", i, i)))
+        .collect();
+
+    let result = import::import_qif(
+        &db,
+        user_id,
+        ImportStatementRequest {
+            account_id: account.id,
+            content,
+            date_format: None,
+        },
+    )
+    .await
+    .expect("Statements are not limited to the API batch size");
+    assert_eq!(result.transactions.len(), 600);
+
+    let refreshed = account::get_account(&db, user_id, account.id)
+        .await
+        .expect("Failed to get account");
+    assert_eq!(refreshed.balance, Decimal::new(180300, 0));
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_import_into_deleted_account_is_rejected() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+
+    let account = account::create_account(&db, user_id, common::account_request("USD", 0))
+        .await
+        .expect("Failed to create account");
+    account::delete_account(&db, user_id, account.id)
+        .await
+        .expect("Failed to delete account");
+
+    let profile = profile::create_profile(
+        &db,
+        user_id,
+        CreateImportProfileRequest {
+            name: "Closed".to_string(),
+            account_id: account.id,
+            date_column: "Date".to_string(),
+            date_format: "%Y-%m-%d".to_string(),
+            amount_column: Some("Amount".to_string()),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(matches!(profile, Err(ServiceError::NotFound)));
+
+    let result = import::import_qif(
+        &db,
+        user_id,
+        ImportStatementRequest {
+            account_id: account.id,
+            content: "!Type:Bank\nD03/01/2026\nT-5.00\n^\n".to_string(),
+            date_format: None,
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(ServiceError::NotFound)));
+
+    common::cleanup_test_user(&db, user_id).await;
+}
@@ -0,0 +1,382 @@
+mod common;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use server::errors::ServiceError;
+use server::services::recurring::{
+    self, CreateRecurringRequest, PostponeOccurrenceRequest, SkipOccurrenceRequest,
+    UpdateRecurringRequest,
+};
+use server::services::transaction::{self, CreateTransactionRequest, TransactionQuery};
+use uuid::Uuid;
+
+fn rent(account_id: Uuid, start_date: NaiveDate) -> CreateRecurringRequest {
+    CreateRecurringRequest {
+        name: "Rent".to_string(),
+        from_account_id: Some(account_id),
+        txn_type: "expense".to_string(),
+        amount: Decimal::new(1000, 0),
+        currency_code: "USD".to_string(),
+        merchant: Some("Landlord".to_string()),
+        tags: Some(vec!["housing".to_string()]),
+        frequency: "monthly".to_string(),
+        start_date,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_recurring_schedule_validation_and_preview() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 10000).await;
+
+    let mut invalid = rent(account_id, common::date(2026, 1, 1));
+    invalid.weekday = Some(2);
+    let result = recurring::create_recurring(&db, user_id, invalid).await;
+    assert!(result.is_err(), "weekday only applies to weekly templates");
+
+    let mut invalid = rent(account_id, common::date(2026, 1, 1));
+    invalid.amount = Decimal::ZERO;
+    let result = recurring::create_recurring(&db, user_id, invalid).await;
+    assert!(result.is_err(), "Template must be a valid transaction");
+
+    let mut weekly = rent(account_id, common::date(2026, 10, 18));
+    weekly.frequency = "weekly".to_string();
+    weekly.weekday = Some(4);
+    weekly.interval = Some(2);
+    weekly.end_date = Some(common::date(2026, 11, 30));
+    let weekly = recurring::create_recurring(&db, user_id, weekly)
+        .await
+        .expect("Failed to create weekly template");
+    assert_eq!(weekly.next_occurrence, Some(common::date(2026, 10, 23)));
+    let preview = recurring::preview_occurrences(&db, user_id, weekly.id, Some(10))
+        .await
+        .expect("Failed to preview");
+    let dates: Vec<NaiveDate> = preview.iter().map(|o| o.date).collect();
+    assert_eq!(
+        dates,
+        vec![
+            common::date(2026, 10, 23),
+            common::date(2026, 11, 6),
+            common::date(2026, 11, 20)
+        ]
+    );
+
+    let mut yearly = rent(account_id, common::date(2024, 2, 29));
+    yearly.frequency = "yearly".to_string();
+    yearly.max_occurrences = Some(3);
+    let yearly = recurring::create_recurring(&db, user_id, yearly)
+        .await
+        .expect("Failed to create yearly template");
+    let preview = recurring::preview_occurrences(&db, user_id, yearly.id, None)
+        .await
+        .expect("Failed to preview");
+    let dates: Vec<NaiveDate> = preview.iter().map(|o| o.date).collect();
+    assert_eq!(
+        dates,
+        vec![
+            common::date(2024, 2, 29),
+            common::date(2025, 2, 28),
+            common::date(2026, 2, 28)
+        ]
+    );
+
+    let mut monthly = rent(account_id, common::date(2026, 1, 15));
+    monthly.day_of_month = Some(31);
+    let monthly = recurring::create_recurring(&db, user_id, monthly)
+        .await
+        .expect("Failed to create monthly template");
+    let preview = recurring::preview_occurrences(&db, user_id, monthly.id, Some(3))
+        .await
+        .expect("Failed to preview");
+    let dates: Vec<NaiveDate> = preview.iter().map(|o| o.date).collect();
+    assert_eq!(
+        dates,
+        vec![
+            common::date(2026, 1, 31),
+            common::date(2026, 2, 28),
+            common::date(2026, 3, 31)
+        ]
+    );
+
+    let result = recurring::skip_occurrence(
+        &db,
+        user_id,
+        monthly.id,
+        SkipOccurrenceRequest {
+            date: common::date(2026, 2, 27),
+        },
+    )
+    .await;
+    assert!(result.is_err(), "Only scheduled dates can be skipped");
+
+    let other_user = common::create_test_user(&db).await;
+    let result = recurring::get_recurring(&db, other_user, monthly.id).await;
+    assert!(matches!(result, Err(ServiceError::Forbidden)));
+
+    common::cleanup_test_user(&db, user_id).await;
+    common::cleanup_test_user(&db, other_user).await;
+}
+
+#[tokio::test]
+async fn test_recurring_materialize_with_skip_and_postpone() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 10000).await;
+
+    let mut req = rent(account_id, common::date(2026, 1, 15));
+    req.day_of_month = Some(31);
+    req.max_occurrences = Some(4);
+    let template = recurring::create_recurring(&db, user_id, req)
+        .await
+        .expect("Failed to create template");
+
+    recurring::skip_occurrence(
+        &db,
+        user_id,
+        template.id,
+        SkipOccurrenceRequest {
+            date: common::date(2026, 2, 28),
+        },
+    )
+    .await
+    .expect("Failed to skip occurrence");
+    recurring::postpone_occurrence(
+        &db,
+        user_id,
+        template.id,
+        PostponeOccurrenceRequest {
+            date: common::date(2026, 3, 31),
+            to: common::date(2026, 4, 5),
+        },
+    )
+    .await
+    .expect("Failed to postpone occurrence");
+
+    let preview = recurring::preview_occurrences(&db, user_id, template.id, None)
+        .await
+        .expect("Failed to preview");
+    let statuses: Vec<&str> = preview.iter().map(|o| o.status.as_str()).collect();
+    assert_eq!(
+        statuses,
+        vec!["scheduled", "skipped", "postponed", "scheduled"]
+    );
+
+    let run = recurring::materialize_due(&db, Some(user_id), common::date(2026, 4, 2))
+        .await
+        .expect("Failed to materialize");
+    assert_eq!(run.posted, 1);
+
+    let template_now = recurring::get_recurring(&db, user_id, template.id)
+        .await
+        .expect("Failed to get template");
+    assert_eq!(template_now.occurrence_count, 3);
+    assert_eq!(
+        template_now.next_occurrence,
+        Some(common::date(2026, 4, 30))
+    );
+
+    let run = recurring::materialize_due(&db, Some(user_id), common::date(2026, 4, 30))
+        .await
+        .expect("Failed to materialize");
+    assert_eq!(run.posted, 2);
+
+    let run = recurring::materialize_due(&db, Some(user_id), common::date(2026, 6, 30))
+        .await
+        .expect("Failed to materialize");
+    assert_eq!(run.posted, 0, "Materializing is idempotent");
+
+    let template_now = recurring::get_recurring(&db, user_id, template.id)
+        .await
+        .expect("Failed to get template");
+    assert_eq!(template_now.next_occurrence, None, "Count limit reached");
+
+    let txns = transaction::list_transactions(
+        &db,
+        user_id,
+        TransactionQuery {
+            account_id: Some(account_id),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to list transactions");
+    let mut dates: Vec<NaiveDate> = txns.iter().map(|t| t.occurred_at.date_naive()).collect();
+    dates.sort();
+    assert_eq!(
+        dates,
+        vec![
+            common::date(2026, 1, 31),
+            common::date(2026, 4, 5),
+            common::date(2026, 4, 30)
+        ]
+    );
+    assert!(txns.iter().all(|t| t.tags == vec!["housing".to_string()]));
+
+    let balance = common::balance_of(&db, user_id, account_id).await;
+    assert_eq!(balance, Decimal::new(7000, 0));
+
+    let occurrences = recurring::list_occurrences(&db, user_id, template.id)
+        .await
+        .expect("Failed to list occurrences");
+    let statuses: Vec<&str> = occurrences.iter().map(|o| o.status.as_str()).collect();
+    assert_eq!(statuses, vec!["posted", "skipped", "posted", "posted"]);
+
+    let result = recurring::skip_occurrence(
+        &db,
+        user_id,
+        template.id,
+        SkipOccurrenceRequest {
+            date: common::date(2026, 1, 31),
+        },
+    )
+    .await;
+    assert!(
+        matches!(result, Err(ServiceError::Conflict(_))),
+        "Posted occurrences cannot be skipped"
+    );
+
+    let pause = UpdateRecurringRequest {
+        active: Some(false),
+        ..Default::default()
+    };
+    let paused = recurring::update_recurring(&db, user_id, template.id, pause)
+        .await
+        .expect("Failed to pause template");
+    assert!(!paused.active);
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_recurring_retries_failed_occurrences() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 10000).await;
+
+    let mut req = rent(account_id, common::date(2026, 1, 15));
+    req.max_occurrences = Some(3);
+    let template = recurring::create_recurring(&db, user_id, req)
+        .await
+        .expect("Failed to create template");
+
+    let manual = |occurred_at: DateTime<Utc>| CreateTransactionRequest {
+        merchant: Some("Landlord".to_string()),
+        occurred_at,
+        ..common::expense(account_id, 1000)
+    };
+    let paid = transaction::create_transaction(&db, user_id, manual(common::at(2026, 1, 14)))
+        .await
+        .expect("Failed to create expense");
+
+    let run = recurring::materialize_due(&db, Some(user_id), common::date(2026, 1, 15))
+        .await
+        .expect("Failed to materialize");
+    assert_eq!(
+        (run.posted, run.failed),
+        (0, 1),
+        "Manual payment is a duplicate"
+    );
+    let occurrences = recurring::list_occurrences(&db, user_id, template.id)
+        .await
+        .expect("Failed to list occurrences");
+    assert_eq!(occurrences[0].status, "failed");
+    assert!(occurrences[0]
+        .error
+        .as_deref()
+        .unwrap_or_default()
+        .contains("duplicate"));
+
+    transaction::delete_transaction(&db, user_id, paid.id)
+        .await
+        .expect("Failed to delete expense");
+    let run = recurring::materialize_due(&db, Some(user_id), common::date(2026, 1, 16))
+        .await
+        .expect("Failed to materialize");
+    assert_eq!(
+        (run.posted, run.failed),
+        (1, 0),
+        "Failed occurrences are retried"
+    );
+
+    recurring::postpone_occurrence(
+        &db,
+        user_id,
+        template.id,
+        PostponeOccurrenceRequest {
+            date: common::date(2026, 2, 15),
+            to: common::date(2026, 3, 14),
+        },
+    )
+    .await
+    .expect("Failed to postpone occurrence");
+    let run = recurring::materialize_due(&db, Some(user_id), common::date(2026, 3, 15))
+        .await
+        .expect("Failed to materialize");
+    assert_eq!(
+        (run.posted, run.failed),
+        (2, 0),
+        "The template's own postings are not duplicates"
+    );
+
+    let update = UpdateRecurringRequest {
+        max_occurrences: Some(Some(4)),
+        ..Default::default()
+    };
+    recurring::update_recurring(&db, user_id, template.id, update)
+        .await
+        .expect("Failed to extend template");
+    transaction::create_transaction(&db, user_id, manual(common::at(2026, 4, 15)))
+        .await
+        .expect("Failed to create expense");
+    let mut failures = 0;
+    for _ in 0..6 {
+        let run = recurring::materialize_due(&db, Some(user_id), common::date(2026, 4, 15))
+            .await
+            .expect("Failed to materialize");
+        failures += run.failed;
+    }
+    assert_eq!(failures, 5, "Retries stop after the attempt limit");
+
+    let moved = recurring::postpone_occurrence(
+        &db,
+        user_id,
+        template.id,
+        PostponeOccurrenceRequest {
+            date: common::date(2026, 4, 15),
+            to: common::date(2026, 4, 17),
+        },
+    )
+    .await
+    .expect("Failed occurrences can be postponed");
+    assert_eq!(moved.status, "postponed");
+    assert_eq!(moved.error, None);
+    let run = recurring::materialize_due(&db, Some(user_id), common::date(2026, 4, 17))
+        .await
+        .expect("Failed to materialize");
+    assert_eq!(
+        (run.posted, run.failed),
+        (0, 1),
+        "Postponing starts the retries over"
+    );
+
+    let skipped = recurring::skip_occurrence(
+        &db,
+        user_id,
+        template.id,
+        SkipOccurrenceRequest {
+            date: common::date(2026, 4, 15),
+        },
+    )
+    .await
+    .expect("Failed occurrences can be skipped");
+    assert_eq!(skipped.status, "skipped");
+    assert_eq!(skipped.error, None);
+    let run = recurring::materialize_due(&db, Some(user_id), common::date(2026, 4, 18))
+        .await
+        .expect("Failed to materialize");
+    assert_eq!((run.posted, run.failed), (0, 0));
+
+    common::cleanup_test_user(&db, user_id).await;
+}
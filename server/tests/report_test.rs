@@ -0,0 +1,427 @@
+mod common;
+
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use server::services::account::{self, CreateAccountRequest};
+use server::services::holdings::{self, CreateHoldingsRequest};
+use server::services::report::{self, BreakdownQuery, NetWorthQuery, SummaryQuery};
+use server::services::transaction::{self, CreateTransactionRequest, TransactionSplitRequest};
+use uuid::Uuid;
+
+fn txn(
+    txn_type: &str,
+    from: Option<Uuid>,
+    to: Option<Uuid>,
+    amount: i64,
+    currency_code: &str,
+    (y, m, d): (i32, u32, u32),
+) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        from_account_id: from,
+        to_account_id: to,
+        occurred_at: common::at(y, m, d),
+        allow_duplicate: Some(true),
+        ..common::txn_request(txn_type, Decimal::new(amount, 0), currency_code)
+    }
+}
+
+fn query(start: NaiveDate, end: NaiveDate, group_by: &str) -> SummaryQuery {
+    SummaryQuery {
+        start,
+        end,
+        group_by: Some(group_by.to_string()),
+        ..Default::default()
+    }
+}
+
+fn breakdown(start: NaiveDate, end: NaiveDate) -> BreakdownQuery {
+    BreakdownQuery {
+        start,
+        end,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_summary_report() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let checking = common::create_account(&db, user_id, "USD", 10000).await;
+    let savings = common::create_account(&db, user_id, "USD", 10000).await;
+    let euro = common::create_account(&db, user_id, "EUR", 10000).await;
+
+    let salary = txn("income", None, Some(checking), 3000, "USD", (2026, 1, 5));
+    let groceries = txn("expense", Some(checking), None, 400, "USD", (2026, 1, 10));
+    for req in [
+        salary,
+        txn("income", None, Some(checking), 3000, "USD", (2026, 2, 5)),
+        txn("expense", Some(checking), None, 1000, "USD", (2026, 2, 1)),
+        txn(
+            "transfer",
+            Some(checking),
+            Some(savings),
+            500,
+            "USD",
+            (2026, 2, 10),
+        ),
+        txn("expense", Some(euro), None, 50, "EUR", (2026, 2, 12)),
+        txn("expense", Some(checking), None, 99, "USD", (2026, 4, 1)),
+    ] {
+        transaction::create_transaction(&db, user_id, req)
+            .await
+            .expect("Failed to create transaction");
+    }
+    let groceries = transaction::create_transaction(&db, user_id, groceries)
+        .await
+        .expect("Failed to create expense");
+
+    let mut refund = txn("refund", None, Some(checking), 100, "USD", (2026, 2, 20));
+    refund.ref_transaction_id = Some(groceries.id);
+    transaction::create_transaction(&db, user_id, refund)
+        .await
+        .expect("Failed to create refund");
+
+    let summary = report::get_summary(
+        &db,
+        user_id,
+        query(common::date(2026, 1, 1), common::date(2026, 3, 31), "month"),
+    )
+    .await
+    .expect("Failed to get summary");
+    let usd: Vec<_> = summary
+        .buckets
+        .iter()
+        .filter(|b| b.currency_code == "USD")
+        .collect();
+    assert_eq!(usd.len(), 3, "Empty months are included");
+    assert_eq!(usd[0].income, Decimal::new(3000, 0));
+    assert_eq!(usd[0].expense, Decimal::new(400, 0));
+    assert_eq!(
+        usd[1].expense,
+        Decimal::new(900, 0),
+        "Refund netted, transfer excluded"
+    );
+    assert_eq!(usd[1].net, Decimal::new(2100, 0));
+    assert_eq!(usd[1].savings_rate, Some(Decimal::new(70, 0)));
+    assert_eq!(usd[2].income, Decimal::ZERO);
+    assert_eq!(usd[2].savings_rate, None);
+    assert_eq!(usd[2].period_end, common::date(2026, 3, 31));
+
+    let usd_total = summary
+        .totals
+        .iter()
+        .find(|t| t.currency_code == "USD")
+        .expect("Missing USD total");
+    assert_eq!(usd_total.income, Decimal::new(6000, 0));
+    assert_eq!(usd_total.expense, Decimal::new(1300, 0));
+    let eur_total = summary
+        .totals
+        .iter()
+        .find(|t| t.currency_code == "EUR")
+        .expect("Missing EUR total");
+    assert_eq!(eur_total.expense, Decimal::new(50, 0));
+
+    let mut by_week = query(common::date(2026, 2, 4), common::date(2026, 2, 15), "week");
+    by_week.currency_code = Some("usd".to_string());
+    let summary = report::get_summary(&db, user_id, by_week)
+        .await
+        .expect("Failed to get weekly summary");
+    let starts: Vec<NaiveDate> = summary.buckets.iter().map(|b| b.period_start).collect();
+    assert_eq!(
+        starts,
+        vec![common::date(2026, 2, 4), common::date(2026, 2, 9)]
+    );
+    assert_eq!(summary.buckets[0].income, Decimal::new(3000, 0));
+    assert_eq!(summary.buckets[0].expense, Decimal::ZERO);
+    assert_eq!(summary.buckets[1].period_end, common::date(2026, 2, 15));
+
+    let result = report::get_summary(
+        &db,
+        user_id,
+        query(common::date(2026, 2, 1), common::date(2026, 1, 1), "month"),
+    )
+    .await;
+    assert!(result.is_err(), "start must not be after end");
+    let result = report::get_summary(
+        &db,
+        user_id,
+        query(common::date(2026, 1, 1), common::date(2026, 2, 1), "hour"),
+    )
+    .await;
+    assert!(result.is_err(), "Unknown group_by");
+    let result = report::get_summary(
+        &db,
+        user_id,
+        query(common::date(2000, 1, 1), common::date(2026, 1, 1), "day"),
+    )
+    .await;
+    assert!(result.is_err(), "Too many buckets");
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_breakdown_report() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let checking = common::create_account(&db, user_id, "USD", 10000).await;
+    let food = common::create_category(&db, user_id, "Food", None).await;
+    let groceries = common::create_category(&db, user_id, "Groceries", Some(food)).await;
+    let travel = common::create_category(&db, user_id, "Travel", None).await;
+
+    let expense = |amount, category_id, merchant: &str, day| {
+        let mut req = txn("expense", Some(checking), None, amount, "USD", day);
+        req.category_id = Some(category_id);
+        req.merchant = Some(merchant.to_string());
+        req
+    };
+
+    let mut mall = expense(80, food, "Mall", (2026, 3, 7));
+    mall.splits = Some(vec![
+        TransactionSplitRequest {
+            category: None,
+            category_id: Some(food),
+            amount: Decimal::new(30, 0),
+            note: None,
+        },
+        TransactionSplitRequest {
+            category: None,
+            category_id: Some(travel),
+            amount: Decimal::new(50, 0),
+            note: None,
+        },
+    ]);
+    for req in [
+        expense(60, groceries, "Market", (2026, 2, 10)),
+        expense(50, food, "Cafe", (2026, 3, 6)),
+        mall,
+        txn("income", None, Some(checking), 1000, "USD", (2026, 3, 1)),
+    ] {
+        transaction::create_transaction(&db, user_id, req)
+            .await
+            .expect("Failed to create transaction");
+    }
+    let market = transaction::create_transaction(
+        &db,
+        user_id,
+        expense(100, groceries, "Market", (2026, 3, 5)),
+    )
+    .await
+    .expect("Failed to create expense");
+    let mut refund = txn("refund", None, Some(checking), 20, "USD", (2026, 3, 10));
+    refund.ref_transaction_id = Some(market.id);
+    transaction::create_transaction(&db, user_id, refund)
+        .await
+        .expect("Failed to create refund");
+
+    let report = report::get_breakdown(
+        &db,
+        user_id,
+        breakdown(common::date(2026, 3, 1), common::date(2026, 3, 31)),
+    )
+    .await
+    .expect("Failed to get breakdown");
+    assert_eq!(report.previous_start, common::date(2026, 1, 29));
+    assert_eq!(report.previous_end, common::date(2026, 2, 28));
+    assert_eq!(report.totals.len(), 1);
+    assert_eq!(report.totals[0].amount, Decimal::new(210, 0));
+    assert_eq!(report.totals[0].previous_amount, Decimal::new(60, 0));
+
+    let item = |id| {
+        report
+            .categories
+            .iter()
+            .find(|c| c.category_id == Some(id))
+            .expect("Missing category")
+    };
+    assert_eq!(
+        report.categories[0].category_id,
+        Some(food),
+        "Sorted by total"
+    );
+    assert_eq!(item(food).amount, Decimal::new(80, 0));
+    assert_eq!(item(food).total, Decimal::new(160, 0), "Rolls up Groceries");
+    assert_eq!(item(food).share, Decimal::new(7619, 2));
+    assert_eq!(item(food).change_percent, Some(Decimal::new(16667, 2)));
+    assert_eq!(item(groceries).total, Decimal::new(80, 0), "Refund netted");
+    assert_eq!(item(groceries).parent_id, Some(food));
+    assert_eq!(item(groceries).category.as_deref(), Some("Food:Groceries"));
+    assert_eq!(
+        item(travel).total,
+        Decimal::new(50, 0),
+        "Split line counted"
+    );
+    assert_eq!(item(travel).previous_total, Decimal::ZERO);
+    assert_eq!(item(travel).change_percent, None);
+
+    let merchants: Vec<(Option<&str>, Decimal)> = report
+        .merchants
+        .iter()
+        .map(|m| (m.merchant.as_deref(), m.amount))
+        .collect();
+    assert_eq!(
+        merchants,
+        vec![
+            (Some("Mall"), Decimal::new(80, 0)),
+            (Some("Market"), Decimal::new(80, 0)),
+            (Some("Cafe"), Decimal::new(50, 0)),
+        ]
+    );
+    assert_eq!(report.merchants[1].count, 1);
+    assert_eq!(report.merchants[1].previous_amount, Decimal::new(60, 0));
+
+    let mut filtered = breakdown(common::date(2026, 3, 1), common::date(2026, 3, 31));
+    filtered.keyword = Some("Market".to_string());
+    filtered.top = Some(1);
+    let report = report::get_breakdown(&db, user_id, filtered)
+        .await
+        .expect("Failed to get filtered breakdown");
+    assert_eq!(report.totals[0].amount, Decimal::new(80, 0));
+    assert_eq!(report.merchants.len(), 1);
+    assert!(report
+        .categories
+        .iter()
+        .all(|c| c.category_id != Some(travel)));
+
+    let mut income = breakdown(common::date(2026, 3, 1), common::date(2026, 3, 31));
+    income.txn_type = Some("income".to_string());
+    let report = report::get_breakdown(&db, user_id, income)
+        .await
+        .expect("Failed to get income breakdown");
+    assert_eq!(report.totals[0].amount, Decimal::new(1000, 0));
+    assert_eq!(report.categories[0].category_id, None);
+
+    let mut invalid = breakdown(common::date(2026, 3, 1), common::date(2026, 3, 31));
+    invalid.txn_type = Some("transfer".to_string());
+    let result = report::get_breakdown(&db, user_id, invalid).await;
+    assert!(result.is_err());
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_net_worth_report() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let new_account = |name: &str, r#type: &str, currency_code: &str, balance: i64| {
+        account::create_account(
+            &db,
+            user_id,
+            CreateAccountRequest {
+                name: name.to_string(),
+                r#type: r#type.to_string(),
+                ..common::account_request(currency_code, balance)
+            },
+        )
+    };
+    let checking = new_account("Checking", "bank_card", "USD", 1000)
+        .await
+        .expect("Failed to create account")
+        .id;
+    let card = new_account("Card", "credit_card", "USD", 0)
+        .await
+        .expect("Failed to create account")
+        .id;
+    new_account("Euro", "bank_card", "EUR", 500)
+        .await
+        .expect("Failed to create account");
+    let closed = new_account("Closed", "bank_card", "USD", 100)
+        .await
+        .expect("Failed to create account")
+        .id;
+
+    for req in [
+        txn("income", None, Some(checking), 2000, "USD", (2026, 1, 10)),
+        txn("expense", Some(card), None, 300, "USD", (2026, 2, 5)),
+        txn(
+            "transfer",
+            Some(checking),
+            Some(card),
+            200,
+            "USD",
+            (2026, 2, 20),
+        ),
+    ] {
+        transaction::create_transaction(&db, user_id, req)
+            .await
+            .expect("Failed to create transaction");
+    }
+    account::delete_account(&db, user_id, closed)
+        .await
+        .expect("Failed to delete account");
+    holdings::create_holdings(
+        &db,
+        user_id,
+        CreateHoldingsRequest {
+            account_id: checking,
+            asset_type: "stock".to_string(),
+            symbol: "ACME".to_string(),
+            name: None,
+            quantity: Decimal::new(10, 0),
+            cost_basis_total: Decimal::new(1000, 0),
+            currency_code: "USD".to_string(),
+            last_price: None,
+            last_price_at: None,
+            market_value: Some(Decimal::new(1500, 0)),
+        },
+    )
+    .await
+    .expect("Failed to create holding");
+
+    let history = report::get_net_worth(
+        &db,
+        user_id,
+        NetWorthQuery {
+            start: common::date(2026, 1, 15),
+            end: common::date(2026, 2, 28),
+            interval: None,
+            convert: None,
+        },
+    )
+    .await
+    .expect("Failed to get net worth");
+    let usd: Vec<_> = history
+        .points
+        .iter()
+        .filter(|p| p.currency_code == "USD")
+        .collect();
+    assert_eq!(usd.len(), 2);
+    assert_eq!(usd[0].date, common::date(2026, 1, 31));
+    assert_eq!(
+        usd[0].assets,
+        Decimal::new(3100, 0),
+        "Deleted later, still counted"
+    );
+    assert_eq!(usd[0].liabilities, Decimal::ZERO);
+    assert_eq!(usd[1].date, common::date(2026, 2, 28));
+    assert_eq!(usd[1].assets, Decimal::new(2900, 0));
+    assert_eq!(usd[1].liabilities, Decimal::new(100, 0));
+    assert_eq!(usd[1].net_worth, Decimal::new(2800, 0));
+    assert!(history
+        .points
+        .iter()
+        .any(|p| p.currency_code == "EUR" && p.net_worth == Decimal::new(500, 0)));
+
+    let today = Utc::now().date_naive();
+    let current = report::get_net_worth(
+        &db,
+        user_id,
+        NetWorthQuery {
+            start: today,
+            end: today,
+            interval: Some("day".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to get net worth");
+    let usd = current
+        .points
+        .iter()
+        .find(|p| p.currency_code == "USD")
+        .expect("Missing USD point");
+    assert_eq!(usd.assets, Decimal::new(4300, 0), "Holding value included");
+    assert_eq!(usd.net_worth, Decimal::new(4200, 0));
+
+    common::cleanup_test_user(&db, user_id).await;
+}
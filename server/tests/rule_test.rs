@@ -0,0 +1,238 @@
+mod common;
+
+use server::errors::ServiceError;
+use server::services::rule::{
+    self, ApplyRulesRequest, CreateRuleRequest, RuleActions, RuleCondition, RuleField, RuleOperator,
+};
+use server::services::transaction::{self, CreateTransactionRequest};
+use uuid::Uuid;
+
+fn condition(field: RuleField, op: RuleOperator, value: &str) -> RuleCondition {
+    RuleCondition {
+        field,
+        op,
+        value: value.to_string(),
+    }
+}
+
+fn new_rule(conditions: Vec<RuleCondition>, actions: RuleActions) -> CreateRuleRequest {
+    CreateRuleRequest {
+        name: "Rule".to_string(),
+        priority: None,
+        enabled: None,
+        conditions,
+        actions,
+    }
+}
+
+fn expense(account_id: Uuid, amount: i64, merchant: &str) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        merchant: Some(merchant.to_string()),
+        allow_duplicate: Some(true),
+        ..common::expense(account_id, amount)
+    }
+}
+
+#[tokio::test]
+async fn test_rules_fill_blank_fields_on_create() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 100000).await;
+    let coffee = common::create_category(&db, user_id, "Coffee", None).await;
+
+    let result = rule::create_rule(
+        &db,
+        user_id,
+        new_rule(
+            vec![condition(RuleField::Amount, RuleOperator::Contains, "10")],
+            RuleActions {
+                note: Some("x".to_string()),
+                ..Default::default()
+            },
+        ),
+    )
+    .await;
+    assert!(result.is_err(), "Contains is not valid for amounts");
+
+    let result = rule::create_rule(
+        &db,
+        user_id,
+        new_rule(
+            vec![condition(RuleField::Merchant, RuleOperator::Contains, "x")],
+            RuleActions::default(),
+        ),
+    )
+    .await;
+    assert!(result.is_err(), "Rule needs an action");
+
+    rule::create_rule(
+        &db,
+        user_id,
+        new_rule(
+            vec![condition(
+                RuleField::Merchant,
+                RuleOperator::Contains,
+                "STARBUCKS",
+            )],
+            RuleActions {
+                category_id: Some(coffee),
+                tags: vec!["work".to_string()],
+                note: None,
+            },
+        ),
+    )
+    .await
+    .expect("Failed to create rule");
+
+    rule::create_rule(
+        &db,
+        user_id,
+        new_rule(
+            vec![
+                condition(RuleField::Amount, RuleOperator::Gt, "5000"),
+                condition(
+                    RuleField::AccountId,
+                    RuleOperator::Equals,
+                    &account_id.to_string(),
+                ),
+            ],
+            RuleActions {
+                note: Some("Rent".to_string()),
+                ..Default::default()
+            },
+        ),
+    )
+    .await
+    .expect("Failed to create rule");
+
+    let txn =
+        transaction::create_transaction(&db, user_id, expense(account_id, 6, "Starbucks #123"))
+            .await
+            .expect("Failed to create transaction");
+    assert_eq!(txn.category_id, Some(coffee));
+    assert_eq!(txn.category.as_deref(), Some("Coffee"));
+    assert_eq!(txn.tags, vec!["work".to_string()]);
+    assert_eq!(txn.note, None);
+
+    let mut explicit = expense(account_id, 6, "STARBUCKS");
+    explicit.category = Some("Snacks".to_string());
+    explicit.tags = Some(vec!["personal".to_string()]);
+    let txn = transaction::create_transaction(&db, user_id, explicit)
+        .await
+        .expect("Failed to create transaction");
+    assert_eq!(txn.category.as_deref(), Some("Snacks"), "Set fields win");
+    assert_eq!(txn.tags, vec!["personal".to_string(), "work".to_string()]);
+
+    let txn = transaction::create_transaction(&db, user_id, expense(account_id, 6000, "Landlord"))
+        .await
+        .expect("Failed to create transaction");
+    assert_eq!(txn.note.as_deref(), Some("Rent"));
+    assert_eq!(txn.category_id, None);
+
+    let other_user = common::create_test_user(&db).await;
+    let result = rule::get_rule(
+        &db,
+        other_user,
+        rule::list_rules(&db, user_id).await.unwrap()[0].id,
+    )
+    .await;
+    assert!(matches!(result, Err(ServiceError::Forbidden)));
+
+    common::cleanup_test_user(&db, user_id).await;
+    common::cleanup_test_user(&db, other_user).await;
+}
+
+#[tokio::test]
+async fn test_apply_rules_to_history() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 100000).await;
+    let groceries = common::create_category(&db, user_id, "Groceries", None).await;
+
+    let old = transaction::create_transaction(&db, user_id, expense(account_id, 40, "Whole Foods"))
+        .await
+        .expect("Failed to create transaction");
+    let mut categorized = expense(account_id, 15, "Whole Foods Market");
+    categorized.category = Some("Lunch".to_string());
+    let categorized = transaction::create_transaction(&db, user_id, categorized)
+        .await
+        .expect("Failed to create transaction");
+    transaction::create_transaction(&db, user_id, expense(account_id, 9, "Cinema"))
+        .await
+        .expect("Failed to create transaction");
+
+    let created = rule::create_rule(
+        &db,
+        user_id,
+        new_rule(
+            vec![condition(
+                RuleField::Merchant,
+                RuleOperator::StartsWith,
+                "whole foods",
+            )],
+            RuleActions {
+                category_id: Some(groceries),
+                tags: vec!["food".to_string()],
+                note: None,
+            },
+        ),
+    )
+    .await
+    .expect("Failed to create rule");
+
+    let request = || ApplyRulesRequest {
+        rule_ids: Some(vec![created.id]),
+        account_id: Some(account_id),
+        dry_run: Some(true),
+        ..Default::default()
+    };
+
+    let preview = rule::apply_rules(&db, user_id, request())
+        .await
+        .expect("Failed to preview rules");
+    assert_eq!(preview.scanned, 3);
+    assert_eq!(preview.updated, 2);
+    let unchanged = transaction::get_transaction(&db, user_id, old.id)
+        .await
+        .expect("Failed to get transaction");
+    assert_eq!(unchanged.category_id, None, "Dry run writes nothing");
+
+    let applied = rule::apply_rules(
+        &db,
+        user_id,
+        ApplyRulesRequest {
+            dry_run: None,
+            ..request()
+        },
+    )
+    .await
+    .expect("Failed to apply rules");
+    assert!(!applied.dry_run);
+    assert_eq!(applied.updated, 2);
+
+    let old = transaction::get_transaction(&db, user_id, old.id)
+        .await
+        .expect("Failed to get transaction");
+    assert_eq!(old.category_id, Some(groceries));
+    assert_eq!(old.tags, vec!["food".to_string()]);
+
+    let categorized = transaction::get_transaction(&db, user_id, categorized.id)
+        .await
+        .expect("Failed to get transaction");
+    assert_eq!(categorized.category.as_deref(), Some("Lunch"));
+    assert_eq!(categorized.tags, vec!["food".to_string()]);
+
+    let again = rule::apply_rules(
+        &db,
+        user_id,
+        ApplyRulesRequest {
+            dry_run: None,
+            ..request()
+        },
+    )
+    .await
+    .expect("Failed to apply rules");
+    assert_eq!(again.updated, 0, "Re-applying is idempotent");
+
+    common::cleanup_test_user(&db, user_id).await;
+}
@@ -0,0 +1,196 @@
+mod common;
+
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sea_orm::DatabaseConnection;
+use server::services::holdings::{self, CreateHoldingsRequest};
+use server::services::report::{self, NetWorthQuery};
+use server::services::snapshot::{self, BackfillSnapshotsRequest, SnapshotQuery};
+use server::services::transaction::{self, CreateTransactionRequest};
+use uuid::Uuid;
+
+fn txn(
+    txn_type: &str,
+    from: Option<Uuid>,
+    to: Option<Uuid>,
+    amount: i64,
+    day: u32,
+) -> CreateTransactionRequest {
+    CreateTransactionRequest {
+        from_account_id: from,
+        to_account_id: to,
+        occurred_at: common::at(2026, 1, day),
+        allow_duplicate: Some(true),
+        ..common::txn_request(txn_type, Decimal::new(amount, 0), "USD")
+    }
+}
+
+fn backfill(start: NaiveDate, end: NaiveDate) -> BackfillSnapshotsRequest {
+    BackfillSnapshotsRequest {
+        start,
+        end: Some(end),
+    }
+}
+
+async fn net_worth_on(db: &DatabaseConnection, user_id: Uuid, day: NaiveDate) -> Decimal {
+    report::get_net_worth(
+        db,
+        user_id,
+        NetWorthQuery {
+            start: day,
+            end: day,
+            interval: Some("day".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to get net worth")
+    .points[0]
+        .net_worth
+}
+
+#[tokio::test]
+async fn test_snapshot_backfill_feeds_net_worth() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let checking =
+        common::create_named_account(&db, user_id, "Checking", "bank_card", "USD", 1000).await;
+    let card = common::create_named_account(&db, user_id, "Card", "credit_card", "USD", 0).await;
+
+    for req in [
+        txn("income", None, Some(checking), 500, 10),
+        txn("expense", Some(card), None, 200, 20),
+    ] {
+        transaction::create_transaction(&db, user_id, req)
+            .await
+            .expect("Failed to create transaction");
+    }
+
+    let result = snapshot::backfill_snapshots(
+        &db,
+        user_id,
+        backfill(common::date(2026, 1, 9), common::date(2026, 1, 21)),
+    )
+    .await
+    .expect("Failed to backfill");
+    assert_eq!(result.account_snapshots, 26);
+    assert_eq!(result.holdings_snapshots, 0);
+
+    let rows = snapshot::list_snapshots(
+        &db,
+        user_id,
+        SnapshotQuery {
+            start: common::date(2026, 1, 9),
+            end: common::date(2026, 1, 10),
+            account_id: Some(checking),
+        },
+    )
+    .await
+    .expect("Failed to list snapshots");
+    let balances: Vec<Option<Decimal>> = rows.iter().map(|r| r.balance).collect();
+    assert_eq!(
+        balances,
+        vec![Some(Decimal::new(1000, 0)), Some(Decimal::new(1500, 0))]
+    );
+
+    assert_eq!(
+        net_worth_on(&db, user_id, common::date(2026, 1, 21)).await,
+        Decimal::new(1300, 0)
+    );
+
+    transaction::create_transaction(&db, user_id, txn("expense", Some(checking), None, 100, 15))
+        .await
+        .expect("Failed to create transaction");
+    assert_eq!(
+        net_worth_on(&db, user_id, common::date(2026, 1, 21)).await,
+        Decimal::new(1300, 0),
+        "Snapshotted days are read as stored"
+    );
+    assert_eq!(
+        net_worth_on(&db, user_id, common::date(2026, 1, 22)).await,
+        Decimal::new(1200, 0),
+        "Other days are replayed"
+    );
+
+    snapshot::backfill_snapshots(
+        &db,
+        user_id,
+        backfill(common::date(2026, 1, 15), common::date(2026, 1, 21)),
+    )
+    .await
+    .expect("Failed to backfill");
+    assert_eq!(
+        net_worth_on(&db, user_id, common::date(2026, 1, 21)).await,
+        Decimal::new(1200, 0),
+        "Backfill overwrites account balances"
+    );
+
+    let result = snapshot::backfill_snapshots(
+        &db,
+        user_id,
+        backfill(common::date(2026, 2, 1), common::date(2026, 1, 1)),
+    )
+    .await;
+    assert!(result.is_err(), "start must not be after end");
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_snapshot_capture_includes_holdings() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let brokerage =
+        common::create_named_account(&db, user_id, "Brokerage", "investment", "USD", 100).await;
+    let holding = holdings::create_holdings(
+        &db,
+        user_id,
+        CreateHoldingsRequest {
+            account_id: brokerage,
+            asset_type: "stock".to_string(),
+            symbol: "ACME".to_string(),
+            name: None,
+            quantity: Decimal::new(4, 0),
+            cost_basis_total: Decimal::new(400, 0),
+            currency_code: "USD".to_string(),
+            last_price: Some(Decimal::new(125, 0)),
+            last_price_at: None,
+            market_value: None,
+        },
+    )
+    .await
+    .expect("Failed to create holding");
+
+    let today = Utc::now().date_naive();
+    snapshot::capture_snapshots(&db, today)
+        .await
+        .expect("Failed to capture snapshots");
+    snapshot::capture_snapshots(&db, today)
+        .await
+        .expect("Capturing twice replaces the day");
+
+    let rows = snapshot::list_snapshots(
+        &db,
+        user_id,
+        SnapshotQuery {
+            start: today,
+            end: today,
+            account_id: None,
+        },
+    )
+    .await
+    .expect("Failed to list snapshots");
+    assert_eq!(rows.len(), 2);
+    let position = rows
+        .iter()
+        .find(|r| r.holdings_id == Some(holding.id))
+        .expect("Missing holding snapshot");
+    assert_eq!(position.quantity, Some(Decimal::new(4, 0)));
+    assert_eq!(position.market_value, Some(Decimal::new(500, 0)));
+    assert_eq!(
+        net_worth_on(&db, user_id, today).await,
+        Decimal::new(600, 0)
+    );
+
+    common::cleanup_test_user(&db, user_id).await;
+}
@@ -0,0 +1,787 @@
+mod common;
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use server::entities::{account as account_entity, prelude::*};
+use server::errors::ServiceError;
+use server::services::account::{self, UpdateAccountRequest};
+use server::services::tag;
+use server::services::transaction::{self, TransactionSplitRequest, UpdateTransactionRequest};
+use uuid::Uuid;
+
+fn split(category: &str, amount: Decimal) -> TransactionSplitRequest {
+    TransactionSplitRequest {
+        category: Some(category.to_string()),
+        category_id: None,
+        amount,
+        note: None,
+    }
+}
+
+#[tokio::test]
+async fn test_balance_follows_expense_income_and_refund() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 1000).await;
+
+    let mut req = common::txn_request("expense", Decimal::new(25050, 2), "USD");
+    req.from_account_id = Some(account_id);
+    let expense = transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create expense");
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(74950, 2)
+    );
+
+    let mut req = common::txn_request("income", Decimal::new(500, 0), "USD");
+    req.to_account_id = Some(account_id);
+    transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create income");
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(124950, 2)
+    );
+
+    let mut req = common::txn_request("refund", Decimal::new(50, 0), "USD");
+    req.to_account_id = Some(account_id);
+    req.ref_transaction_id = Some(expense.id);
+    let refund = transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create refund");
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(129950, 2)
+    );
+
+    transaction::delete_transaction(&db, user_id, refund.id)
+        .await
+        .expect("Failed to delete refund");
+    transaction::delete_transaction(&db, user_id, expense.id)
+        .await
+        .expect("Failed to delete expense");
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(1500, 0)
+    );
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_balance_follows_cross_currency_transfer() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let usd = common::create_account(&db, user_id, "USD", 1000).await;
+    let eur = common::create_account(&db, user_id, "EUR", 0).await;
+
+    let mut req = common::txn_request("transfer", Decimal::new(100, 0), "USD");
+    req.from_account_id = Some(usd);
+    req.to_account_id = Some(eur);
+    req.to_amount = Some(Decimal::new(92, 0));
+    req.to_currency_code = Some("EUR".to_string());
+    let transfer = transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create transfer");
+
+    assert_eq!(
+        common::balance_of(&db, user_id, usd).await,
+        Decimal::new(900, 0)
+    );
+    assert_eq!(
+        common::balance_of(&db, user_id, eur).await,
+        Decimal::new(92, 0)
+    );
+
+    transaction::delete_transaction(&db, user_id, transfer.id)
+        .await
+        .expect("Failed to delete transfer");
+
+    assert_eq!(
+        common::balance_of(&db, user_id, usd).await,
+        Decimal::new(1000, 0)
+    );
+    assert_eq!(common::balance_of(&db, user_id, eur).await, Decimal::ZERO);
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_currency_must_match_account() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let usd = common::create_account(&db, user_id, "USD", 1000).await;
+    let eur = common::create_account(&db, user_id, "EUR", 0).await;
+
+    let mut req = common::txn_request("expense", Decimal::new(100, 0), "EUR");
+    req.from_account_id = Some(usd);
+    let result = transaction::create_transaction(&db, user_id, req.clone()).await;
+    assert!(
+        matches!(result, Err(ServiceError::Validation(_))),
+        "EUR expense on a USD account needs a settled amount"
+    );
+
+    req.settled_amount = Some(Decimal::new(10850, 2));
+    let foreign = transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create foreign-currency expense");
+    assert_eq!(foreign.amount, Decimal::new(10850, 2));
+    assert_eq!(foreign.currency_code, "USD");
+    assert_eq!(foreign.original_amount, Some(Decimal::new(100, 0)));
+    assert_eq!(foreign.original_currency_code.as_deref(), Some("EUR"));
+    assert_eq!(
+        common::balance_of(&db, user_id, usd).await,
+        Decimal::new(89150, 2)
+    );
+
+    let mut req = common::txn_request("expense", Decimal::new(10, 0), "USD");
+    req.from_account_id = Some(usd);
+    req.settled_amount = Some(Decimal::new(10, 0));
+    let result = transaction::create_transaction(&db, user_id, req).await;
+    assert!(
+        matches!(result, Err(ServiceError::Validation(_))),
+        "settled_amount is only for foreign currencies"
+    );
+
+    let mut req = common::txn_request("transfer", Decimal::new(100, 0), "USD");
+    req.from_account_id = Some(usd);
+    req.to_account_id = Some(eur);
+    let result = transaction::create_transaction(&db, user_id, req).await;
+    assert!(
+        matches!(result, Err(ServiceError::Validation(_))),
+        "Transfer into an EUR account needs to_amount in EUR"
+    );
+
+    let update = UpdateTransactionRequest {
+        note: Some("Hotel".to_string()),
+        ..Default::default()
+    };
+    let updated = transaction::update_transaction(&db, user_id, foreign.id, update)
+        .await
+        .expect("Failed to update foreign-currency expense");
+    assert_eq!(updated.original_amount, Some(Decimal::new(100, 0)));
+    assert_eq!(updated.amount, Decimal::new(10850, 2));
+
+    let update = UpdateTransactionRequest {
+        settled_amount: Some(Some(Decimal::new(110, 0))),
+        ..Default::default()
+    };
+    transaction::update_transaction(&db, user_id, foreign.id, update)
+        .await
+        .expect("Failed to update settled amount");
+    assert_eq!(
+        common::balance_of(&db, user_id, usd).await,
+        Decimal::new(890, 0)
+    );
+
+    let mut req = common::txn_request("refund", Decimal::new(40, 0), "EUR");
+    req.to_account_id = Some(usd);
+    req.ref_transaction_id = Some(foreign.id);
+    req.settled_amount = Some(Decimal::new(44, 0));
+    transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to refund foreign-currency expense");
+    assert_eq!(
+        common::balance_of(&db, user_id, usd).await,
+        Decimal::new(934, 0)
+    );
+
+    let result = account::update_account(
+        &db,
+        user_id,
+        usd,
+        UpdateAccountRequest {
+            name: None,
+            r#type: None,
+            currency_code: Some("EUR".to_string()),
+        },
+    )
+    .await;
+    assert!(
+        matches!(result, Err(ServiceError::Conflict(_))),
+        "Currency of an account with transactions is fixed"
+    );
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_rejected_transaction_leaves_balance_untouched() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 1000).await;
+
+    let mut req = common::txn_request("expense", Decimal::new(10, 0), "USD");
+    req.from_account_id = Some(account_id);
+    req.to_account_id = Some(Uuid::new_v4());
+    let result = transaction::create_transaction(&db, user_id, req).await;
+    assert!(
+        result.is_err(),
+        "Expense with both accounts should be rejected"
+    );
+
+    let mut req = common::txn_request("expense", Decimal::new(10, 0), "USD");
+    req.from_account_id = Some(account_id);
+    let expense = transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create expense");
+
+    let mut req = common::txn_request("refund", Decimal::new(5, 0), "USD");
+    req.to_account_id = Some(account_id);
+    req.ref_transaction_id = Some(expense.id);
+    transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create refund");
+
+    let result = transaction::delete_transaction(&db, user_id, expense.id).await;
+    assert!(
+        result.is_err(),
+        "Expense with refunds should not be deletable"
+    );
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(995, 0)
+    );
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_recompute_detects_and_repairs_drift() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 1000).await;
+
+    let mut req = common::txn_request("expense", Decimal::new(100, 0), "USD");
+    req.from_account_id = Some(account_id);
+    transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create expense");
+
+    let model = Account::find_by_id(account_id)
+        .one(&db)
+        .await
+        .expect("Failed to load account")
+        .expect("Account missing");
+    let mut active: account_entity::ActiveModel = model.into();
+    active.balance = Set(Decimal::new(950, 0));
+    active.update(&db).await.expect("Failed to corrupt balance");
+
+    let report = account::recompute_balance(&db, user_id, account_id, true)
+        .await
+        .expect("Failed to recompute balance");
+    assert_eq!(report.computed_balance, Decimal::new(900, 0));
+    assert_eq!(report.drift, Decimal::new(50, 0));
+    assert!(!report.repaired);
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(950, 0)
+    );
+
+    let reports = account::recompute_all_balances(&db, user_id, false)
+        .await
+        .expect("Failed to recompute balances");
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].repaired);
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(900, 0)
+    );
+
+    let report = account::recompute_balance(&db, user_id, account_id, false)
+        .await
+        .expect("Failed to recompute balance");
+    assert!(report.drift.is_zero());
+    assert!(!report.repaired);
+
+    let other_user = common::create_test_user(&db).await;
+    let result = account::recompute_balance(&db, other_user, account_id, false).await;
+    assert!(
+        result.is_err(),
+        "Other users should not recompute this account"
+    );
+
+    common::cleanup_test_user(&db, user_id).await;
+    common::cleanup_test_user(&db, other_user).await;
+}
+
+#[tokio::test]
+async fn test_update_rebalances_amount_type_and_accounts() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let checking = common::create_account(&db, user_id, "USD", 1000).await;
+    let savings = common::create_account(&db, user_id, "USD", 500).await;
+
+    let mut req = common::txn_request("expense", Decimal::new(100, 0), "USD");
+    req.from_account_id = Some(checking);
+    let expense = transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create expense");
+
+    let mut req = common::txn_request("refund", Decimal::new(10, 0), "USD");
+    req.to_account_id = Some(checking);
+    req.ref_transaction_id = Some(expense.id);
+    transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create refund");
+    assert_eq!(
+        common::balance_of(&db, user_id, checking).await,
+        Decimal::new(910, 0)
+    );
+
+    let update = UpdateTransactionRequest {
+        amount: Some(Decimal::new(120, 0)),
+        ..Default::default()
+    };
+    let updated = transaction::update_transaction(&db, user_id, expense.id, update)
+        .await
+        .expect("Amount of a refunded transaction should be editable");
+    assert_eq!(updated.amount, Decimal::new(120, 0));
+    assert_eq!(
+        common::balance_of(&db, user_id, checking).await,
+        Decimal::new(890, 0)
+    );
+
+    let update = UpdateTransactionRequest {
+        currency_code: Some("EUR".to_string()),
+        ..Default::default()
+    };
+    let result = transaction::update_transaction(&db, user_id, expense.id, update).await;
+    assert!(
+        result.is_err(),
+        "Currency change should conflict with refunds"
+    );
+
+    let update = UpdateTransactionRequest {
+        txn_type: Some("income".to_string()),
+        from_account_id: Some(None),
+        to_account_id: Some(Some(checking)),
+        ..Default::default()
+    };
+    let result = transaction::update_transaction(&db, user_id, expense.id, update).await;
+    assert!(
+        matches!(result, Err(ServiceError::Conflict(_))),
+        "Type change should conflict with refunds"
+    );
+
+    let update = UpdateTransactionRequest {
+        from_account_id: Some(Some(savings)),
+        ..Default::default()
+    };
+    let result = transaction::update_transaction(&db, user_id, expense.id, update).await;
+    assert!(
+        matches!(result, Err(ServiceError::Conflict(_))),
+        "Account change should conflict with refunds"
+    );
+
+    let mut req = common::txn_request("expense", Decimal::new(50, 0), "USD");
+    req.from_account_id = Some(checking);
+    let other = transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create expense");
+    assert_eq!(
+        common::balance_of(&db, user_id, checking).await,
+        Decimal::new(840, 0)
+    );
+
+    let update = UpdateTransactionRequest {
+        txn_type: Some("transfer".to_string()),
+        ..Default::default()
+    };
+    let result = transaction::update_transaction(&db, user_id, other.id, update).await;
+    assert!(
+        result.is_err(),
+        "Transfer without to_account_id should be rejected"
+    );
+    assert_eq!(
+        common::balance_of(&db, user_id, checking).await,
+        Decimal::new(840, 0)
+    );
+
+    let update = UpdateTransactionRequest {
+        txn_type: Some("transfer".to_string()),
+        to_account_id: Some(Some(savings)),
+        ..Default::default()
+    };
+    transaction::update_transaction(&db, user_id, other.id, update)
+        .await
+        .expect("Failed to turn expense into transfer");
+    assert_eq!(
+        common::balance_of(&db, user_id, checking).await,
+        Decimal::new(840, 0)
+    );
+    assert_eq!(
+        common::balance_of(&db, user_id, savings).await,
+        Decimal::new(550, 0)
+    );
+
+    let update = UpdateTransactionRequest {
+        txn_type: Some("income".to_string()),
+        from_account_id: Some(None),
+        ..Default::default()
+    };
+    let updated = transaction::update_transaction(&db, user_id, other.id, update)
+        .await
+        .expect("Failed to turn transfer into income");
+    assert_eq!(updated.from_account_id, None);
+    assert_eq!(
+        common::balance_of(&db, user_id, checking).await,
+        Decimal::new(890, 0)
+    );
+    assert_eq!(
+        common::balance_of(&db, user_id, savings).await,
+        Decimal::new(550, 0)
+    );
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_split_lines_sum_and_filter() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 1000).await;
+
+    let mut req = common::txn_request("expense", Decimal::new(100, 0), "USD");
+    req.from_account_id = Some(account_id);
+    req.splits = Some(vec![
+        split("Groceries", Decimal::new(60, 0)),
+        split("Household", Decimal::new(30, 0)),
+    ]);
+    let result = transaction::create_transaction(&db, user_id, req).await;
+    assert!(
+        result.is_err(),
+        "Splits not summing to amount should be rejected"
+    );
+
+    let mut req = common::txn_request("expense", Decimal::new(100, 0), "USD");
+    req.from_account_id = Some(account_id);
+    req.category = Some("Shopping".to_string());
+    req.splits = Some(vec![
+        split("Groceries", Decimal::new(60, 0)),
+        split("Household", Decimal::new(30, 0)),
+        split("Pharmacy", Decimal::new(10, 0)),
+    ]);
+    let created = transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create split transaction");
+    assert_eq!(created.splits.len(), 3);
+    assert_eq!(created.splits[0].category, "Groceries");
+    assert_eq!(created.splits[2].category, "Pharmacy");
+
+    let found = transaction::list_transactions(&db, user_id, common::category_query("Pharmacy"))
+        .await
+        .expect("Failed to list transactions");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, created.id);
+    assert_eq!(found[0].splits.len(), 3);
+
+    let found = transaction::list_transactions(&db, user_id, common::category_query("Shopping"))
+        .await
+        .expect("Failed to list transactions");
+    assert_eq!(found.len(), 1);
+
+    let update = UpdateTransactionRequest {
+        amount: Some(Decimal::new(110, 0)),
+        ..Default::default()
+    };
+    let result = transaction::update_transaction(&db, user_id, created.id, update).await;
+    assert!(result.is_err(), "Amount change must keep splits consistent");
+
+    let update = UpdateTransactionRequest {
+        amount: Some(Decimal::new(110, 0)),
+        splits: Some(vec![
+            split("Groceries", Decimal::new(70, 0)),
+            split("Household", Decimal::new(40, 0)),
+        ]),
+        ..Default::default()
+    };
+    let updated = transaction::update_transaction(&db, user_id, created.id, update)
+        .await
+        .expect("Failed to update split transaction");
+    assert_eq!(updated.splits.len(), 2);
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(890, 0)
+    );
+
+    let found = transaction::list_transactions(&db, user_id, common::category_query("Pharmacy"))
+        .await
+        .expect("Failed to list transactions");
+    assert!(found.is_empty());
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_batch_create_is_all_or_nothing() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 1000).await;
+
+    let expense = |amount: Decimal| {
+        let mut req = common::txn_request("expense", amount, "USD");
+        req.from_account_id = Some(account_id);
+        req
+    };
+
+    let batch = vec![
+        expense(Decimal::new(10, 0)),
+        expense(Decimal::new(-5, 0)),
+        expense(Decimal::new(20, 0)),
+        common::txn_request("income", Decimal::new(30, 0), "USD"),
+    ];
+    match transaction::create_transactions_batch(&db, user_id, batch).await {
+        Err(ServiceError::Batch(items)) => {
+            let indexes: Vec<usize> = items.iter().map(|item| item.index).collect();
+            assert_eq!(indexes, vec![1, 3]);
+        }
+        other => panic!("Expected batch error, got {:?}", other),
+    }
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(1000, 0)
+    );
+
+    let mut query = common::category_query("unused");
+    query.category = None;
+    let listed = transaction::list_transactions(&db, user_id, query)
+        .await
+        .expect("Failed to list transactions");
+    assert!(listed.is_empty(), "Failed batch should not insert anything");
+
+    let batch = vec![expense(Decimal::new(10, 0)), expense(Decimal::new(20, 0))];
+    let created = transaction::create_transactions_batch(&db, user_id, batch)
+        .await
+        .expect("Failed to create batch");
+    assert_eq!(created.len(), 2);
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(970, 0)
+    );
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_duplicate_detection() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 1000).await;
+    let now = Utc::now();
+
+    let coffee = |merchant: &str, days: i64| {
+        let mut req = common::txn_request("expense", Decimal::new(450, 2), "USD");
+        req.from_account_id = Some(account_id);
+        req.merchant = Some(merchant.to_string());
+        req.occurred_at = now + chrono::Duration::days(days);
+        req
+    };
+
+    let original = transaction::create_transaction(&db, user_id, coffee("Blue Bottle Coffee", 0))
+        .await
+        .expect("Failed to create expense");
+
+    match transaction::create_transaction(&db, user_id, coffee("BLUE-BOTTLE coffee ", 1)).await {
+        Err(ServiceError::Conflict(msg)) => assert!(msg.contains(&original.id.to_string())),
+        other => panic!("Expected duplicate conflict, got {:?}", other),
+    }
+
+    transaction::create_transaction(&db, user_id, coffee("Blue Bottle Coffee", 7))
+        .await
+        .expect("Outside the date window is not a duplicate");
+    transaction::create_transaction(&db, user_id, coffee("Starbucks", 0))
+        .await
+        .expect("Different merchant is not a duplicate");
+
+    let mut forced = coffee("Blue Bottle Coffee", 0);
+    forced.allow_duplicate = Some(true);
+    transaction::create_transaction(&db, user_id, forced)
+        .await
+        .expect("allow_duplicate should bypass the check");
+
+    let mut first = coffee("Vending", 0);
+    first.external_id = Some("bank-1".to_string());
+    transaction::create_transaction(&db, user_id, first)
+        .await
+        .expect("Failed to create expense with external id");
+
+    let mut second = coffee("Vending", 0);
+    second.external_id = Some("bank-2".to_string());
+    transaction::create_transaction(&db, user_id, second)
+        .await
+        .expect("Different external ids are distinct transactions");
+
+    let mut replay = coffee("Something else", 30);
+    replay.external_id = Some("bank-1".to_string());
+    let result = transaction::create_transaction(&db, user_id, replay).await;
+    assert!(
+        result.is_err(),
+        "Same external id on the account is a duplicate"
+    );
+
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(97300, 2)
+    );
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_duplicate_check_respects_direction() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 1000).await;
+
+    let mut req = common::txn_request("expense", Decimal::new(50, 0), "USD");
+    req.from_account_id = Some(account_id);
+    req.merchant = Some("Shop".to_string());
+    let expense = transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create expense");
+
+    let mut refund = common::txn_request("refund", Decimal::new(50, 0), "USD");
+    refund.to_account_id = Some(account_id);
+    refund.merchant = Some("Shop".to_string());
+    refund.ref_transaction_id = Some(expense.id);
+    transaction::create_transaction(&db, user_id, refund)
+        .await
+        .expect("A full refund is not a duplicate of its expense");
+
+    let mut req = common::txn_request("expense", Decimal::new(70, 0), "USD");
+    req.from_account_id = Some(account_id);
+    transaction::create_transaction(&db, user_id, req)
+        .await
+        .expect("Failed to create expense");
+
+    let mut income = common::txn_request("income", Decimal::new(70, 0), "USD");
+    income.to_account_id = Some(account_id);
+    transaction::create_transaction(&db, user_id, income)
+        .await
+        .expect("Income is not a duplicate of a same-day expense");
+
+    let mut income = common::txn_request("income", Decimal::new(70, 0), "USD");
+    income.to_account_id = Some(account_id);
+    let result = transaction::create_transaction(&db, user_id, income).await;
+    assert!(matches!(result, Err(ServiceError::Conflict(_))));
+
+    assert_eq!(
+        common::balance_of(&db, user_id, account_id).await,
+        Decimal::new(1000, 0)
+    );
+
+    common::cleanup_test_user(&db, user_id).await;
+}
+
+#[tokio::test]
+async fn test_tags_and_tag_filter() {
+    let db = common::setup_test_db().await;
+    let user_id = common::create_test_user(&db).await;
+    let account_id = common::create_account(&db, user_id, "USD", 1000).await;
+
+    let tagged = |amount: i64, tags: &[&str]| {
+        let mut req = common::txn_request("expense", Decimal::new(amount, 0), "USD");
+        req.from_account_id = Some(account_id);
+        req.tags = Some(tags.iter().map(|t| t.to_string()).collect());
+        req
+    };
+
+    let hotel = transaction::create_transaction(
+        &db,
+        user_id,
+        tagged(200, &["trip-japan-2026", "reimbursable", "Reimbursable "]),
+    )
+    .await
+    .expect("Failed to create tagged transaction");
+    assert_eq!(hotel.tags, vec!["reimbursable", "trip-japan-2026"]);
+
+    let ramen = transaction::create_transaction(&db, user_id, tagged(15, &["#trip-japan-2026"]))
+        .await
+        .expect("Failed to create tagged transaction");
+    assert_eq!(ramen.tags, vec!["trip-japan-2026"]);
+
+    let toys = transaction::create_transaction(&db, user_id, tagged(30, &["kid"]))
+        .await
+        .expect("Failed to create tagged transaction");
+
+    let result = transaction::create_transaction(&db, user_id, tagged(1, &["  "])).await;
+    assert!(result.is_err(), "Empty tags are rejected");
+
+    let tag_query = |tags: &str, mode: Option<&str>| {
+        let mut query = common::category_query("unused");
+        query.category = None;
+        query.tags = Some(tags.to_string());
+        query.tag_mode = mode.map(str::to_string);
+        query
+    };
+
+    let found =
+        transaction::list_transactions(&db, user_id, tag_query("TRIP-JAPAN-2026,kid", None))
+            .await
+            .expect("Failed to list transactions");
+    assert_eq!(found.len(), 3);
+
+    let found = transaction::list_transactions(
+        &db,
+        user_id,
+        tag_query("trip-japan-2026,reimbursable", Some("all")),
+    )
+    .await
+    .expect("Failed to list transactions");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, hotel.id);
+
+    let found = transaction::list_transactions(&db, user_id, tag_query("kid,unknown", Some("all")))
+        .await
+        .expect("Failed to list transactions");
+    assert!(found.is_empty());
+
+    let result = transaction::list_transactions(&db, user_id, tag_query("kid", Some("some"))).await;
+    assert!(result.is_err(), "Unknown tag_mode is rejected");
+
+    let update = UpdateTransactionRequest {
+        tags: Some(vec!["kid".to_string(), "reimbursable".to_string()]),
+        ..Default::default()
+    };
+    let updated = transaction::update_transaction(&db, user_id, toys.id, update)
+        .await
+        .expect("Failed to update tags");
+    assert_eq!(updated.tags, vec!["kid", "reimbursable"]);
+
+    let update = UpdateTransactionRequest {
+        note: Some("birthday".to_string()),
+        ..Default::default()
+    };
+    let updated = transaction::update_transaction(&db, user_id, toys.id, update)
+        .await
+        .expect("Failed to update transaction");
+    assert_eq!(
+        updated.tags,
+        vec!["kid", "reimbursable"],
+        "Tags are kept when omitted"
+    );
+
+    let found = transaction::list_transactions(&db, user_id, tag_query("reimbursable", None))
+        .await
+        .expect("Failed to list transactions");
+    assert_eq!(found.len(), 2);
+
+    let update = UpdateTransactionRequest {
+        tags: Some(Vec::new()),
+        ..Default::default()
+    };
+    let updated = transaction::update_transaction(&db, user_id, ramen.id, update)
+        .await
+        .expect("Failed to clear tags");
+    assert!(updated.tags.is_empty());
+
+    let tags = tag::list_tags(&db, user_id)
+        .await
+        .expect("Failed to list tags");
+    assert_eq!(tags.len(), 3);
+
+    common::cleanup_test_user(&db, user_id).await;
+}